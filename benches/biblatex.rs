@@ -0,0 +1,105 @@
+//! Compares parsing a large generated BibLaTeX file at once with reading it
+//! one entry at a time, and reports the peak heap of both.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use hayagriva::io::{from_biblatex_reader, from_biblatex_str};
+use hayagriva::Library;
+
+/// An allocator that tracks the peak size of the live heap.
+struct Tracking;
+
+static LIVE: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Tracking {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let live = LIVE.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+        PEAK.fetch_max(live, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        LIVE.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Tracking = Tracking;
+
+/// 20000 articles from 500 journals that are defined with `@string`.
+fn source() -> String {
+    let mut bib = String::new();
+    for j in 0..500 {
+        bib += &format!("@string{{j{j} = {{Journal of Experimental Studies {j}}}}}\n");
+    }
+
+    for i in 0..20000 {
+        bib += &format!(
+            "@article{{a{i},
+  author = {{Doe, Jane and Roe, Richard and Smith, John}},
+  title = {{Article number {i} on {{DNA}} sequencing}},
+  journaltitle = j{},
+  volume = {},
+  pages = {{{}--{}}},
+  date = {},
+}}
+",
+            i % 500,
+            i % 40,
+            i % 300,
+            i % 300 + 12,
+            1950 + i % 70,
+        );
+    }
+
+    bib
+}
+
+/// The peak heap in MB while running `f`, on top of what was live before.
+fn peak<T>(f: impl FnOnce() -> T) -> f64 {
+    let before = LIVE.load(Ordering::Relaxed);
+    PEAK.store(before, Ordering::Relaxed);
+    black_box(f());
+    (PEAK.load(Ordering::Relaxed) - before) as f64 / 1e6
+}
+
+fn biblatex(c: &mut Criterion) {
+    let bib = source();
+    let batch = || from_biblatex_str(&bib).unwrap();
+    let streaming = || {
+        from_biblatex_reader(bib.as_bytes())
+            .collect::<Result<Library, _>>()
+            .unwrap()
+    };
+    let filtered = || {
+        from_biblatex_reader(bib.as_bytes())
+            .map(Result::unwrap)
+            .filter(|entry| entry.key().ends_with("000"))
+            .collect::<Library>()
+    };
+
+    assert_eq!(streaming(), batch());
+    println!(
+        "{:.1} MB source, peak heap: {:.1} MB batch, {:.1} MB streaming, \
+         {:.1} MB streaming with a filter",
+        bib.len() as f64 / 1e6,
+        peak(batch),
+        peak(streaming),
+        peak(filtered),
+    );
+
+    let mut group = c.benchmark_group("biblatex");
+    group.sample_size(10);
+    group.bench_function("batch", |b| b.iter(batch));
+    group.bench_function("streaming", |b| b.iter(streaming));
+    group.bench_function("streaming with a filter", |b| b.iter(filtered));
+    group.finish();
+}
+
+criterion_group!(benches, biblatex);
+criterion_main!(benches);
@@ -0,0 +1,88 @@
+//! Measures the cache of case-transformed strings with titles that repeat,
+//! like the titles of the periodicals of many articles, and with titles that
+//! do not.
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use hayagriva::archive::locales;
+use hayagriva::io::from_yaml_str;
+use hayagriva::lang::{Case, CaseFolder, SentenceCase, TitleCase};
+use hayagriva::{BibliographyRequest, Library};
+
+use self::common::{driver, style};
+
+mod common;
+
+/// The title of one of 20 journals.
+fn journal(i: usize) -> String {
+    format!("journal of the american society for information science {}", i % 20)
+}
+
+/// 10000 articles from 20 journals.
+fn library() -> Library {
+    let mut yaml = String::new();
+    for i in 0..10000 {
+        yaml += &format!(
+            "a{i}:
+  type: article
+  title: Article number {i}
+  author: Doe, Jane
+  date: {}
+  parent:
+    type: periodical
+    title: {}
+",
+            1950 + i % 70,
+            journal(i),
+        );
+    }
+
+    from_yaml_str(&yaml).unwrap()
+}
+
+fn fold(case: Case, titles: &[String]) -> usize {
+    titles
+        .iter()
+        .map(|title| {
+            let mut folder = CaseFolder::with_config(case);
+            folder.push_str(title);
+            black_box(folder.finish()).len()
+        })
+        .sum()
+}
+
+fn case(c: &mut Criterion) {
+    let shared: Vec<String> = (0..10000).map(journal).collect();
+    let distinct: Vec<String> =
+        (0..10000).map(|i| format!("{} {i}", journal(i))).collect();
+
+    let mut group = c.benchmark_group("fold");
+    for (name, case) in
+        [("title", TitleCase::new().into()), ("sentence", SentenceCase::new().into())]
+    {
+        group.bench_function(format!("{name} case, shared titles"), |b| {
+            b.iter(|| fold(case, &shared))
+        });
+        group.bench_function(format!("{name} case, distinct titles"), |b| {
+            b.iter(|| fold(case, &distinct))
+        });
+    }
+    group.finish();
+
+    let library = library();
+    let locales = locales();
+    let style = style("chicago-author-date");
+    let mut group = c.benchmark_group("fold bibliography");
+    group.sample_size(10);
+    group.bench_function("chicago-author-date", |b| {
+        b.iter(|| {
+            driver(&library, &style, &locales)
+                .finish(BibliographyRequest::new(&style, None, &locales))
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, case);
+criterion_main!(benches);
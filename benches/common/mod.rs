@@ -0,0 +1,27 @@
+//! Helpers for the benchmarks that render whole bibliographies.
+
+use hayagriva::archive::style_by_name;
+use hayagriva::citationberg::{IndependentStyle, Locale, Style};
+use hayagriva::{BibliographyDriver, CitationItem, CitationRequest, Entry, Library};
+
+/// An independent style from the archive.
+pub fn style(name: &str) -> IndependentStyle {
+    let Some(Style::Independent(style)) = style_by_name(name) else {
+        panic!("{name} is not an independent style");
+    };
+    style
+}
+
+/// A driver in which every entry of the library is cited once.
+pub fn driver<'a>(
+    library: &'a Library,
+    style: &'a IndependentStyle,
+    locales: &'a [Locale],
+) -> BibliographyDriver<'a, Entry> {
+    let mut driver = BibliographyDriver::new();
+    for entry in library.iter() {
+        let items = vec![CitationItem::with_entry(entry)];
+        driver.citation(CitationRequest::new(items, style, None, locales, None));
+    }
+    driver
+}
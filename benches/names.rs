@@ -0,0 +1,104 @@
+//! Formats the names of an entry with 100 authors into new strings and into a
+//! reused buffer, and reports the allocations of each.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use hayagriva::io::from_yaml_str;
+use hayagriva::types::Person;
+use hayagriva::Library;
+
+/// An allocator that counts the allocations.
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+/// The number of allocations of the function.
+fn allocations<T>(f: impl FnOnce() -> T) -> usize {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    black_box(f());
+    ALLOCATIONS.load(Ordering::Relaxed) - before
+}
+
+/// An article with 100 authors, some with particles and suffixes.
+fn library() -> Library {
+    let mut yaml =
+        String::from("big:\n  type: article\n  title: A large study\n  author:\n");
+    for i in 0..100 {
+        let name = match i % 4 {
+            0 => format!("Doe{i}, Jane Anne"),
+            1 => format!("van Roe{i}, Richard"),
+            2 => format!("Smith{i}, John, Jr."),
+            _ => format!("Lee{i}, Kim"),
+        };
+        yaml += &format!("  - {name}\n");
+    }
+
+    from_yaml_str(&yaml).unwrap()
+}
+
+fn names(c: &mut Criterion) {
+    let library = library();
+    let authors: &[Person] = library.get("big").unwrap().authors().unwrap();
+
+    let name_first = || -> Vec<String> {
+        authors.iter().map(|person| person.name_first(true, false)).collect()
+    };
+    let given_first = || -> Vec<String> {
+        authors.iter().map(|person| person.given_first(false)).collect()
+    };
+    let mut buf = String::new();
+    let write = |buf: &mut String| {
+        buf.clear();
+        for person in authors {
+            person.write_name_first(buf, true, false).unwrap();
+            buf.push_str("; ");
+            person.write_given_first(buf, false).unwrap();
+            buf.push_str("; ");
+        }
+        buf.len()
+    };
+    write(&mut buf);
+
+    println!(
+        "allocations: {} name_first, {} given_first, {} both written into a \
+         buffer, {} hover_form",
+        allocations(name_first),
+        allocations(given_first),
+        allocations(|| write(&mut buf)),
+        allocations(|| library.hover_form(0)),
+    );
+
+    let mut group = c.benchmark_group("names");
+    group.bench_function("name_first", |b| b.iter(name_first));
+    group.bench_function("given_first", |b| b.iter(given_first));
+    group.bench_function("write_name_first and write_given_first", |b| {
+        b.iter(|| write(&mut buf))
+    });
+    group.bench_function("hover_form", |b| b.iter(|| library.hover_form(0)));
+    group.finish();
+}
+
+criterion_group!(benches, names);
+criterion_main!(benches);
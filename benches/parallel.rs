@@ -0,0 +1,79 @@
+//! Compares rendering a bibliography with formatting its items in parallel.
+//! Only the time of `finish` and `finish_par` is measured, not citing the
+//! entries. The speedup depends on the number of cores of the machine.
+
+use std::thread::available_parallelism;
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use hayagriva::archive::locales;
+use hayagriva::io::from_yaml_str;
+use hayagriva::{BibliographyRequest, Library};
+use rayon::ThreadPoolBuilder;
+
+use self::common::{driver, style};
+
+mod common;
+
+/// 10000 articles by different authors.
+fn library() -> Library {
+    let mut yaml = String::new();
+    for i in 0..10000 {
+        yaml += &format!(
+            "a{i}:
+  type: article
+  title: A study of the effects of variable {i} on outcome {}
+  author:
+  - Doe{i}, Jane
+  - Roe{i}, Richard
+  date: {}
+  page-range: {}-{}
+  parent:
+    type: periodical
+    title: Journal of Studies {}
+    volume: {}
+",
+            i % 13,
+            1950 + i % 70,
+            i % 300,
+            i % 300 + 12,
+            i % 50,
+            i % 40,
+        );
+    }
+
+    from_yaml_str(&yaml).unwrap()
+}
+
+fn parallel(c: &mut Criterion) {
+    let library = library();
+    let locales = locales();
+    let style = style("apa");
+    let cites = || driver(&library, &style, &locales);
+    let request = || BibliographyRequest::new(&style, None, &locales);
+
+    let cores = available_parallelism().map_or(1, usize::from);
+    let mut threads = vec![1];
+    if cores > 1 {
+        threads.push(cores);
+    }
+
+    let mut group = c.benchmark_group("parallel");
+    group.sample_size(10);
+    group.bench_function("finish", |b| {
+        b.iter_batched(cites, |driver| driver.finish(request()), BatchSize::PerIteration)
+    });
+    for n in threads {
+        let pool = ThreadPoolBuilder::new().num_threads(n).build().unwrap();
+        group.bench_function(format!("finish_par, threads: {n} of {cores}"), |b| {
+            b.iter_batched(
+                cites,
+                |driver| pool.install(|| driver.finish_par(request())),
+                BatchSize::PerIteration,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, parallel);
+criterion_main!(benches);
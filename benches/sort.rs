@@ -0,0 +1,50 @@
+//! Renders the bibliography of 10000 books in styles that sort by the
+//! rendered names of the authors.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use hayagriva::archive::locales;
+use hayagriva::io::from_yaml_str;
+use hayagriva::{BibliographyRequest, Library};
+
+use self::common::{driver, style};
+
+mod common;
+
+/// 10000 books in a scrambled order with shared authors and years.
+fn library() -> Library {
+    let mut yaml = String::new();
+    let mut seed: u32 = 7;
+    for i in 0..10000 {
+        seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+        let family: String = (0..3)
+            .map(|k| (b'a' + ((seed >> (8 + 5 * k)) % 26) as u8) as char)
+            .collect();
+        yaml += &format!(
+            "e{i}:\n  type: Book\n  title: Book {i}\n  author: {}{}, Ann\n  date: {}\n",
+            family[..1].to_uppercase(),
+            &family[1..],
+            1950 + (seed >> 24) % 70,
+        );
+    }
+
+    from_yaml_str(&yaml).unwrap()
+}
+
+fn sort(c: &mut Criterion) {
+    let library = library();
+    let locales = locales();
+
+    let mut group = c.benchmark_group("sort");
+    group.sample_size(10);
+    for name in ["apa", "chicago-author-date"] {
+        let style = style(name);
+        let request = || BibliographyRequest::new(&style, None, &locales);
+        group.bench_function(name, |b| {
+            b.iter(|| driver(&library, &style, &locales).finish(request()))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, sort);
+criterion_main!(benches);
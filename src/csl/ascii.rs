@@ -0,0 +1,141 @@
+//! Transliteration of rendered output to ASCII.
+
+use std::collections::BTreeSet;
+
+use super::elem::{ElemChild, ElemChildren};
+
+/// Replace the letters with diacritics and the typographic punctuation in the
+/// text elements with their closest ASCII equivalents.
+///
+/// Characters without an equivalent, like those of CJK scripts, are kept and
+/// added to `kept`.
+pub(super) fn transliterate(children: &mut ElemChildren, kept: &mut BTreeSet<char>) {
+    for child in children.0.iter_mut() {
+        match child {
+            ElemChild::Text(text) => transliterate_str(&mut text.text, kept),
+            ElemChild::Link { text, .. } => transliterate_str(&mut text.text, kept),
+            ElemChild::Elem(e) => transliterate(&mut e.children, kept),
+            ElemChild::Markup(_) | ElemChild::Transparent { .. } => {}
+        }
+    }
+}
+
+fn transliterate_str(text: &mut String, kept: &mut BTreeSet<char>) {
+    if text.is_ascii() {
+        return;
+    }
+
+    let mut res = String::with_capacity(text.len());
+    for c in text.chars() {
+        if c.is_ascii() {
+            res.push(c);
+        } else if let Some(replacement) = to_ascii(c) {
+            res.push_str(replacement);
+        } else {
+            kept.insert(c);
+            res.push(c);
+        }
+    }
+
+    *text = res;
+}
+
+/// The ASCII equivalent of a Latin letter or a punctuation mark.
+fn to_ascii(c: char) -> Option<&'static str> {
+    Some(match c {
+        'À'..='Å' | 'Ā' | 'Ă' | 'Ą' | 'Ǎ' => "A",
+        'à'..='å' | 'ā' | 'ă' | 'ą' | 'ǎ' => "a",
+        'Æ' => "AE",
+        'æ' => "ae",
+        'Ç' | 'Ć' | 'Ĉ' | 'Ċ' | 'Č' => "C",
+        'ç' | 'ć' | 'ĉ' | 'ċ' | 'č' => "c",
+        'Ð' | 'Ď' | 'Đ' => "D",
+        'ð' | 'ď' | 'đ' => "d",
+        'È'..='Ë' | 'Ē' | 'Ĕ' | 'Ė' | 'Ę' | 'Ě' => "E",
+        'è'..='ë' | 'ē' | 'ĕ' | 'ė' | 'ę' | 'ě' => "e",
+        'Ĝ' | 'Ğ' | 'Ġ' | 'Ģ' => "G",
+        'ĝ' | 'ğ' | 'ġ' | 'ģ' => "g",
+        'Ĥ' | 'Ħ' => "H",
+        'ĥ' | 'ħ' => "h",
+        'Ì'..='Ï' | 'Ĩ' | 'Ī' | 'Ĭ' | 'Į' | 'İ' | 'Ǐ' => "I",
+        'ì'..='ï' | 'ĩ' | 'ī' | 'ĭ' | 'į' | 'ı' | 'ǐ' => "i",
+        'Ĳ' => "IJ",
+        'ĳ' => "ij",
+        'Ĵ' => "J",
+        'ĵ' => "j",
+        'Ķ' => "K",
+        'ķ' | 'ĸ' => "k",
+        'Ĺ' | 'Ļ' | 'Ľ' | 'Ŀ' | 'Ł' => "L",
+        'ĺ' | 'ļ' | 'ľ' | 'ŀ' | 'ł' => "l",
+        'Ñ' | 'Ń' | 'Ņ' | 'Ň' | 'Ŋ' => "N",
+        'ñ' | 'ń' | 'ņ' | 'ň' | 'ŉ' | 'ŋ' => "n",
+        'Ò'..='Ö' | 'Ø' | 'Ō' | 'Ŏ' | 'Ő' | 'Ǒ' => "O",
+        'ò'..='ö' | 'ø' | 'ō' | 'ŏ' | 'ő' | 'ǒ' => "o",
+        'Œ' => "OE",
+        'œ' => "oe",
+        'Ŕ' | 'Ŗ' | 'Ř' => "R",
+        'ŕ' | 'ŗ' | 'ř' => "r",
+        'Ś' | 'Ŝ' | 'Ş' | 'Š' | 'Ș' => "S",
+        'ś' | 'ŝ' | 'ş' | 'š' | 'ș' => "s",
+        'ß' => "ss",
+        'Ţ' | 'Ť' | 'Ŧ' | 'Ț' => "T",
+        'ţ' | 'ť' | 'ŧ' | 'ț' => "t",
+        'Þ' => "TH",
+        'þ' => "th",
+        'Ù'..='Ü' | 'Ũ' | 'Ū' | 'Ŭ' | 'Ů' | 'Ű' | 'Ų' | 'Ǔ' | 'Ǖ' | 'Ǘ' | 'Ǚ' | 'Ǜ' => {
+            "U"
+        }
+        'ù'..='ü' | 'ũ' | 'ū' | 'ŭ' | 'ů' | 'ű' | 'ų' | 'ǔ' | 'ǖ' | 'ǘ' | 'ǚ' | 'ǜ' => {
+            "u"
+        }
+        'Ŵ' => "W",
+        'ŵ' => "w",
+        'Ý' | 'Ŷ' | 'Ÿ' => "Y",
+        'ý' | 'ÿ' | 'ŷ' => "y",
+        'Ź' | 'Ż' | 'Ž' => "Z",
+        'ź' | 'ż' | 'ž' => "z",
+        '\u{A0}' | '\u{2002}'..='\u{200A}' | '\u{202F}' => " ",
+        '\u{2010}' | '\u{2011}' | '\u{2012}' | '\u{2013}' | '\u{2212}' => "-",
+        '\u{2014}' | '\u{2015}' => "--",
+        '‘' | '’' | '‚' | '′' => "'",
+        '“' | '”' | '„' | '″' | '«' | '»' => "\"",
+        '‹' => "<",
+        '›' => ">",
+        '…' => "...",
+        '·' | '•' => "*",
+        '×' => "x",
+        '©' => "(c)",
+        '®' => "(R)",
+        '§' => "S",
+        '¶' => "P",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ascii(text: &str) -> (String, Vec<char>) {
+        let mut text = text.to_string();
+        let mut kept = BTreeSet::new();
+        transliterate_str(&mut text, &mut kept);
+        (text, kept.into_iter().collect())
+    }
+
+    #[test]
+    fn diacritics() {
+        assert_eq!(ascii("Gödel, Escher, Bach"), ("Godel, Escher, Bach".into(), vec![]));
+        assert_eq!(ascii("Łódź Straße"), ("Lodz Strasse".into(), vec![]));
+        assert_eq!(ascii("Ærøskøbing"), ("AEroskobing".into(), vec![]));
+        assert_eq!(ascii("“Quoted”—pp. 1–9…"), ("\"Quoted\"--pp. 1-9...".into(), vec![]));
+    }
+
+    #[test]
+    fn untransliterable() {
+        assert_eq!(
+            ascii("Müller 東京 Ωmega"),
+            ("Muller 東京 Ωmega".into(), vec!['Ω', '京', '東'])
+        );
+    }
+}
@@ -0,0 +1,83 @@
+//! Isolation of right-to-left text in rendered output.
+
+use std::mem;
+
+use super::elem::{Elem, ElemChild, ElemChildren, ElemMeta, Formatted};
+use super::options::BidiIsolation;
+
+/// The first strong isolate, which takes its direction from its content.
+const FSI: char = '\u{2068}';
+/// The pop directional isolate that ends an isolate.
+const PDI: char = '\u{2069}';
+
+/// Isolate the runs of right-to-left text in each text element, so that the
+/// punctuation around them keeps its position in left-to-right references.
+///
+/// A run starts at the first and ends at the last strong right-to-left
+/// character of an element, so neutral characters like spaces within the run
+/// are isolated with it and trailing punctuation stays outside.
+pub(super) fn isolate_rtl(children: &mut ElemChildren, mode: BidiIsolation) {
+    let mut res = Vec::with_capacity(children.0.len());
+    for child in mem::take(&mut children.0) {
+        match child {
+            ElemChild::Text(text) => isolate_text(text, mode, &mut res),
+            ElemChild::Elem(mut e) => {
+                isolate_rtl(&mut e.children, mode);
+                res.push(ElemChild::Elem(e));
+            }
+            other => res.push(other),
+        }
+    }
+
+    children.0 = res;
+}
+
+fn isolate_text(mut text: Formatted, mode: BidiIsolation, res: &mut Vec<ElemChild>) {
+    let Some(start) = text.text.find(is_strong_rtl) else {
+        res.push(ElemChild::Text(text));
+        return;
+    };
+    let last = text.text.rfind(is_strong_rtl).unwrap();
+    let end = last + text.text[last..].chars().next().unwrap().len_utf8();
+
+    match mode {
+        BidiIsolation::Characters => {
+            text.text.insert(end, PDI);
+            text.text.insert(start, FSI);
+            res.push(ElemChild::Text(text));
+        }
+        BidiIsolation::Markup => {
+            let after = text.text.split_off(end);
+            let run = text.text.split_off(start);
+            let formatting = text.formatting;
+            if !text.text.is_empty() {
+                res.push(ElemChild::Text(text));
+            }
+            res.push(ElemChild::Elem(Elem {
+                children: ElemChildren(vec![ElemChild::Text(Formatted {
+                    text: run,
+                    formatting,
+                })]),
+                display: None,
+                meta: Some(ElemMeta::Isolate),
+            }));
+            if !after.is_empty() {
+                res.push(ElemChild::Text(Formatted { text: after, formatting }));
+            }
+        }
+    }
+}
+
+/// Whether a character is a letter of a right-to-left script like Hebrew or
+/// Arabic. Arabic-Indic digits are not strong and do not start a run.
+fn is_strong_rtl(c: char) -> bool {
+    matches!(c,
+        '\u{0590}'..='\u{065F}'
+        | '\u{066A}'..='\u{06EF}'
+        | '\u{06FA}'..='\u{08FF}'
+        | '\u{FB1D}'..='\u{FDFF}'
+        | '\u{FE70}'..='\u{FEFF}'
+        | '\u{10800}'..='\u{10FFF}'
+        | '\u{1E800}'..='\u{1EFFF}'
+    )
+}
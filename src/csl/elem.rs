@@ -7,6 +7,8 @@ use citationberg::{
     Display, FontStyle, FontVariant, FontWeight, TextDecoration, VerticalAlign,
 };
 
+use super::output::{link_segments, LINK_BREAK};
+
 /// A container for elements with useful methods.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Elem {
@@ -39,6 +41,9 @@ impl Elem {
         format: BufWriteFormat,
     ) -> Result<(), fmt::Error> {
         match (format, self.display) {
+            (BufWriteFormat::Html, _) if self.meta == Some(ElemMeta::Isolate) => {
+                w.write_str("<bdi>")?
+            }
             (BufWriteFormat::Html, Some(Display::Block)) => w.write_str("<div>")?,
             (BufWriteFormat::Html, Some(Display::Indent)) => {
                 w.write_str("<div style=\"padding-left: 4em;\">")?
@@ -58,6 +63,9 @@ impl Elem {
         }
 
         match (format, self.display) {
+            (BufWriteFormat::Html, _) if self.meta == Some(ElemMeta::Isolate) => {
+                w.write_str("</bdi>")?
+            }
             (BufWriteFormat::Html, Some(_)) => w.write_str("</div>")?,
             (_, Some(Display::Block)) => w.write_char('\n')?,
             (_, _) => {}
@@ -121,6 +129,132 @@ pub(super) fn simplify_children(children: ElemChildren) -> ElemChildren {
     }))
 }
 
+/// Move URL prefixes like "https://doi.org/" that directly precede a link into
+/// the link if its target starts with them, so that the whole URL is a link.
+pub(super) fn absorb_link_prefixes(children: &mut ElemChildren) {
+    for i in 1..children.0.len() {
+        let (before, after) = children.0.split_at_mut(i);
+        let ElemChild::Link { text, url } = &mut after[0] else { continue };
+        let Some(prev) = last_text_mut_child(before) else { continue };
+        let Some(prefix) = url.strip_suffix(text.text.as_str()) else { continue };
+        if prefix.is_empty()
+            || prev.formatting != text.formatting
+            || !prev.text.ends_with(prefix)
+        {
+            continue;
+        }
+
+        prev.text.truncate(prev.text.len() - prefix.len());
+        text.text.insert_str(0, prefix);
+    }
+
+    for child in &mut children.0 {
+        if let ElemChild::Elem(e) = child {
+            absorb_link_prefixes(&mut e.children);
+        }
+    }
+}
+
+/// Remove the `http://` or `https://` scheme from the displayed text of
+/// links. Their targets are not changed.
+pub(super) fn strip_link_schemes(children: &mut ElemChildren) {
+    for child in &mut children.0 {
+        match child {
+            ElemChild::Link { text, .. } => {
+                for scheme in ["https://", "http://"] {
+                    if let Some(rest) = text.text.strip_prefix(scheme) {
+                        text.text = rest.to_string();
+                        break;
+                    }
+                }
+            }
+            ElemChild::Elem(e) => strip_link_schemes(&mut e.children),
+            _ => {}
+        }
+    }
+}
+
+/// Insert a zero-width space wherever a link may be broken across lines. Only
+/// links that display their URL, possibly without the scheme, are marked.
+pub(super) fn mark_link_breaks(children: &mut ElemChildren) {
+    for child in &mut children.0 {
+        match child {
+            ElemChild::Link { text, url } => {
+                let shows_url = text.text == *url
+                    || ["https://", "http://"].iter().any(|scheme| {
+                        url.strip_prefix(scheme) == Some(text.text.as_str())
+                    });
+                if shows_url {
+                    text.text = link_segments(&text.text).join(LINK_BREAK);
+                }
+            }
+            ElemChild::Elem(e) => mark_link_breaks(&mut e.children),
+            _ => {}
+        }
+    }
+}
+
+/// Shorten the displayed text of links by removing their query string and
+/// truncating them to `max_len` characters. Their targets are not changed.
+pub(super) fn shorten_links(
+    children: &mut ElemChildren,
+    strip_query: bool,
+    max_len: Option<usize>,
+) {
+    for child in &mut children.0 {
+        match child {
+            ElemChild::Link { text, .. } => {
+                if strip_query {
+                    if let Some(idx) = text.text.find('?') {
+                        text.text.truncate(idx);
+                    }
+                }
+                if let Some(max_len) = max_len {
+                    truncate_link(&mut text.text, max_len);
+                }
+            }
+            ElemChild::Elem(e) => shorten_links(&mut e.children, strip_query, max_len),
+            _ => {}
+        }
+    }
+}
+
+/// Truncate a link to at most `max_len` characters including an ellipsis.
+///
+/// The link is cut after the last slash within its path that fits. If there
+/// is none, it is cut after the last fitting character that is not part of a
+/// percent-escape.
+fn truncate_link(link: &mut String, max_len: usize) {
+    if link.chars().count() <= max_len {
+        return;
+    }
+
+    // The number of bytes of the first `max_len - 1` characters, leaving
+    // room for the ellipsis.
+    let limit = link
+        .char_indices()
+        .nth(max_len.saturating_sub(1))
+        .map_or(link.len(), |(i, _)| i);
+    let host_end = link.find("://").map_or(0, |i| i + 3);
+    let host_end = link[host_end..].find('/').map_or(link.len(), |i| host_end + i);
+
+    let cut = match link[..limit].rfind('/') {
+        Some(i) if i > host_end => i + 1,
+        _ => {
+            // A percent-escape has two more characters after the percent
+            // sign, so it is incomplete if one is among the last two.
+            let tail = link[..limit].char_indices().rev().nth(1).map_or(0, |(i, _)| i);
+            match link[tail..limit].find('%') {
+                Some(i) => tail + i,
+                None => limit,
+            }
+        }
+    };
+
+    link.truncate(cut);
+    link.push('…');
+}
+
 /// Which CSL construct created an element.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ElemMeta {
@@ -141,6 +275,22 @@ pub enum ElemMeta {
     Name(NameVariable, usize),
     /// The entry corresponds to a citation item.
     Entry(usize),
+    /// The element isolates right-to-left text from its surroundings. HTML
+    /// output wraps it in a `<bdi>` element.
+    Isolate,
+    /// The element is the text that the author put before the cites of a
+    /// citation, like "see".
+    CitationPrefix,
+    /// The element is the text that the author put after the cites of a
+    /// citation, like "for a review".
+    CitationSuffix,
+    /// The element is the signal that introduces a cite, like "cf.".
+    Signal,
+    /// The element is a verbatim part of a field that the finishing passes
+    /// do not change. It is only created if
+    /// [`StyleOptions::tidy_punctuation`](super::StyleOptions::tidy_punctuation)
+    /// is set.
+    Verbatim,
 }
 
 /// A container for element children with useful methods.
@@ -181,6 +331,23 @@ impl ElemChildren {
         None
     }
 
+    /// Retrieve a mutable reference to the first child with a matching meta by
+    /// DFS.
+    pub(super) fn get_meta_mut(&mut self, meta: ElemMeta) -> Option<&mut Elem> {
+        for child in &mut self.0 {
+            if let ElemChild::Elem(e) = child {
+                if e.meta == Some(meta) {
+                    return Some(e);
+                }
+                if let Some(e) = e.children.get_meta_mut(meta) {
+                    return Some(e);
+                }
+            }
+        }
+
+        None
+    }
+
     /// Remove the first child with any meta by DFS.
     pub(super) fn remove_any_meta(&mut self) -> Option<ElemChild> {
         for i in 0..self.0.len() {
@@ -221,6 +388,24 @@ impl ElemChildren {
     }
 }
 
+/// Capitalize the first letter of the first text leaf.
+pub(super) fn capitalize_first(children: &mut ElemChildren) {
+    fn first_text_mut(children: &mut [ElemChild]) -> Option<&mut Formatted> {
+        children.first_mut().and_then(|c| match c {
+            ElemChild::Text(t) => Some(t),
+            ElemChild::Elem(e) => first_text_mut(&mut e.children.0),
+            _ => None,
+        })
+    }
+
+    let Some(text) = first_text_mut(&mut children.0) else { return };
+    let Some(first) = text.text.chars().next() else { return };
+    if first.is_lowercase() {
+        let rest = text.text.split_off(first.len_utf8());
+        text.text = first.to_uppercase().chain(rest.chars()).collect();
+    }
+}
+
 pub(crate) fn last_text_mut_child(children: &mut [ElemChild]) -> Option<&mut Formatted> {
     children.last_mut().and_then(|c| match c {
         ElemChild::Text(t) => Some(t),
@@ -294,7 +479,7 @@ impl ElemChild {
                 w.write_str(url)?;
                 w.write_str("\">")?;
                 text.formatting.write_start(w, format)?;
-                w.write_str(&text.text)?;
+                w.write_str(&text.text.replace(LINK_BREAK, "<wbr>"))?;
                 text.formatting.write_end(w, format)?;
                 w.write_str("</a>")
             }
@@ -593,7 +778,7 @@ impl<T> NonEmptyStack<T> {
         drain.chain(first)
     }
 
-    pub fn iter(&self) -> impl Iterator<Item = &T> {
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> {
         self.head.iter().chain(std::iter::once(&self.last))
     }
 
@@ -0,0 +1,428 @@
+//! Tracking the citations of a document by the keys of their entries.
+
+use std::collections::{HashMap, HashSet};
+
+use citationberg::{IndependentStyle, Locale, LocaleCode};
+use indexmap::{IndexMap, IndexSet};
+use thiserror::Error;
+
+use super::{
+    BibliographyDriver, BibliographyRequest, CitationItem, CitationRequest, CitePurpose,
+    Rendered, RenderedBibliography, Signal, SpecificLocator, StyleOptions,
+};
+use crate::{Entry, Library};
+
+/// Collects the citations of a document by the keys of the cited entries and
+/// renders them with a style.
+///
+/// Citations must be added in the order in which they appear in the
+/// document, so that the style can distinguish first from subsequent
+/// citations of an entry. When the document is complete,
+/// [`finish`](Self::finish) renders all citations and a bibliography that
+/// contains the cited entries in the order of the style. Numeric styles list
+/// them in the order of their first citation.
+///
+/// Entries can be added to the bibliography without citing them with
+/// [`nocite`](Self::nocite) or, all at once, with
+/// [`nocite_all`](Self::nocite_all), like `\nocite{key}` and `\nocite{*}`
+/// in LaTeX. They are sorted and numbered as if they were cited at the end
+/// of the document. The engine reports
+/// the entries that were never cited and the cited keys that are not in the
+/// library.
+///
+/// To link citations and bibliography items in both directions, cite with
+/// [`cite_at`](Self::cite_at) and finish with
+/// [`finish_with_links`](Self::finish_with_links).
+#[derive(Debug, Clone)]
+pub struct CitationEngine<'a> {
+    library: &'a Library,
+    style: &'a IndependentStyle,
+    locale: Option<LocaleCode>,
+    locale_files: &'a [Locale],
+    options: StyleOptions,
+    driver: BibliographyDriver<'a, Entry>,
+    /// The keys of the cited entries in the order of their first citation.
+    cited: IndexSet<&'a str>,
+    /// The keys of the entries to include in the bibliography without a
+    /// citation in the order in which they were added.
+    uncited: IndexSet<&'a str>,
+    uncited_placement: UncitedPlacement,
+    /// Whether the bibliography includes all entries of the library.
+    all_entries: bool,
+    /// The keys that were cited but are not in the library.
+    missing: IndexSet<&'a str>,
+    /// The keys of the entries in each citation.
+    citation_keys: Vec<Vec<&'a str>>,
+    /// The identifiers of the locations that cite each entry.
+    back_references: IndexMap<&'a str, Vec<String>>,
+}
+
+impl<'a> CitationEngine<'a> {
+    /// Create a new engine for a document that cites entries of the library.
+    pub fn new(
+        library: &'a Library,
+        style: &'a IndependentStyle,
+        locale: Option<LocaleCode>,
+        locale_files: &'a [Locale],
+    ) -> Self {
+        Self {
+            library,
+            style,
+            locale,
+            locale_files,
+            options: StyleOptions::default(),
+            driver: BibliographyDriver::new(),
+            cited: IndexSet::new(),
+            uncited: IndexSet::new(),
+            uncited_placement: UncitedPlacement::default(),
+            all_entries: false,
+            missing: IndexSet::new(),
+            citation_keys: Vec::new(),
+            back_references: IndexMap::new(),
+        }
+    }
+
+    /// Override the punctuation of the style for citations and the
+    /// bibliography.
+    pub fn with_options(mut self, options: StyleOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Set where the entries that are included without a citation appear in
+    /// the bibliography.
+    pub fn with_uncited_placement(mut self, placement: UncitedPlacement) -> Self {
+        self.uncited_placement = placement;
+        self
+    }
+
+    /// Set whether the bibliography includes all entries of the library,
+    /// also those that were not cited. They are placed like the entries
+    /// added with [`nocite`](Self::nocite).
+    pub fn with_uncited_entries(mut self, include: bool) -> Self {
+        self.all_entries = include;
+        self
+    }
+
+    /// Add a citation of one or more entries.
+    ///
+    /// The style orders the cites within the citation, for example
+    /// alphabetically or by number, and may collapse them, like "[2]–[5]". A
+    /// key that occurs more than once is only cited at its first occurrence.
+    ///
+    /// Returns for each cite whether it is the first citation of its entry.
+    /// Keys that are not in the library are left out of the citation and
+    /// returned together in the error. The citation of the other keys is
+    /// still added, so that the citations stay in step with the document.
+    pub fn cite(
+        &mut self,
+        cites: Vec<KeyedCite<'a>>,
+        note_number: Option<usize>,
+    ) -> Result<Vec<bool>, UnknownKeysError> {
+        let mut keys = HashSet::new();
+        let mut items = Vec::with_capacity(cites.len());
+        let mut unknown = Vec::new();
+        for cite in &cites {
+            // Cite each entry only once per citation.
+            if !keys.insert(cite.key) {
+                continue;
+            }
+
+            let Some(entry) = self.library.get(cite.key) else {
+                self.missing.insert(cite.key);
+                unknown.push(cite.key.to_string());
+                continue;
+            };
+            let mut item =
+                CitationItem::new(entry, cite.locator, None, cite.hidden, cite.purpose);
+            item.suppress_author = cite.suppress_author;
+            item.signal = cite.signal;
+            items.push(item);
+        }
+
+        let first = cites
+            .iter()
+            .map(|cite| {
+                self.library.get(cite.key).is_some() && self.cited.insert(cite.key)
+            })
+            .collect();
+        self.citation_keys
+            .push(items.iter().map(|item| item.entry.key()).collect());
+        self.driver.citation(
+            CitationRequest::new(
+                items,
+                self.style,
+                self.locale.clone(),
+                self.locale_files,
+                note_number,
+            )
+            .with_options(self.options.clone()),
+        );
+
+        if unknown.is_empty() {
+            Ok(first)
+        } else {
+            Err(UnknownKeysError(unknown))
+        }
+    }
+
+    /// Add a citation like [`cite`](Self::cite) and record the location of
+    /// the citation, like a page number or the identifier of a node in the
+    /// document, as a back reference of each cited entry.
+    pub fn cite_at(
+        &mut self,
+        cites: Vec<KeyedCite<'a>>,
+        note_number: Option<usize>,
+        location: impl Into<String>,
+    ) -> Result<Vec<bool>, UnknownKeysError> {
+        let keys: Vec<_> = cites
+            .iter()
+            .map(|cite| cite.key)
+            .filter(|key| self.library.get(key).is_some())
+            .collect();
+        let first = self.cite(cites, note_number);
+
+        let location = location.into();
+        for key in keys {
+            let locations = self.back_references.entry(key).or_default();
+            // Several citations at the same location are listed once.
+            if locations.last() != Some(&location) {
+                locations.push(location.clone());
+            }
+        }
+
+        first
+    }
+
+    /// Whether the entry with the key has been cited.
+    pub fn is_cited(&self, key: &str) -> bool {
+        self.cited.contains(key)
+    }
+
+    /// Include the entry with the key in the bibliography without citing it.
+    /// This has no effect if the entry is cited.
+    pub fn nocite(&mut self, key: &'a str) -> Result<(), UnknownKeyError> {
+        if self.library.get(key).is_none() {
+            self.missing.insert(key);
+            return Err(UnknownKeyError(key.to_string()));
+        }
+
+        self.uncited.insert(key);
+        Ok(())
+    }
+
+    /// Include all entries of the library in the bibliography, also those
+    /// that are not cited. This is the same as
+    /// [`with_uncited_entries(true)`](Self::with_uncited_entries).
+    pub fn nocite_all(&mut self) {
+        self.all_entries = true;
+    }
+
+    /// The keys of the cited entries in the order of their first citation.
+    pub fn cited_keys_in_order(&self) -> impl Iterator<Item = &str> + '_ {
+        self.cited.iter().copied()
+    }
+
+    /// The entries of the library that were neither cited nor included with
+    /// [`nocite`](Self::nocite), in the order of the library.
+    pub fn unused_entries(&self) -> impl Iterator<Item = &'a Entry> + '_ {
+        self.library.iter().filter(|entry| {
+            !self.cited.contains(entry.key()) && !self.uncited.contains(entry.key())
+        })
+    }
+
+    /// The keys that were cited or included with [`nocite`](Self::nocite)
+    /// but are not in the library, in the order of their first use.
+    pub fn missing_keys(&self) -> impl Iterator<Item = &str> + '_ {
+        self.missing.iter().copied()
+    }
+
+    /// Render the bibliography of the citations added so far without
+    /// finishing the document.
+    pub fn bibliography(&self) -> Option<RenderedBibliography> {
+        self.clone().finish().bibliography
+    }
+
+    /// Render all citations in the order they were added and the
+    /// bibliography of the cited and included entries.
+    pub fn finish(self) -> Rendered {
+        self.finish_with_links().0
+    }
+
+    /// Render the document like [`finish`](Self::finish) and return the
+    /// links between its citations and the items of its bibliography.
+    pub fn finish_with_links(mut self) -> (Rendered, CitationLinks) {
+        let citations = self.driver.citations.len();
+
+        // Entries without a citation are included as if they were cited
+        // invisibly at the end of the document.
+        let library = self.library;
+        let all = self.all_entries.then(|| library.keys()).into_iter().flatten();
+        let uncited: IndexSet<_> = self
+            .uncited
+            .iter()
+            .copied()
+            .chain(all)
+            .filter(|key| !self.cited.contains(key))
+            .collect();
+        for key in &uncited {
+            let entry = self.library.get(key).unwrap();
+            self.driver.citation(CitationRequest::new(
+                vec![CitationItem::new(entry, None, None, true, None)],
+                self.style,
+                self.locale.clone(),
+                self.locale_files,
+                None,
+            ));
+        }
+
+        let mut rendered = self.driver.finish(
+            BibliographyRequest::new(self.style, self.locale, self.locale_files)
+                .with_options(self.options),
+        );
+        rendered.citations.truncate(citations);
+
+        if self.uncited_placement == UncitedPlacement::End {
+            if let Some(bibliography) = &mut rendered.bibliography {
+                bibliography
+                    .items
+                    .sort_by_key(|item| uncited.contains(item.key.as_str()));
+            }
+        }
+
+        let positions: HashMap<&str, usize> = rendered
+            .bibliography
+            .iter()
+            .flat_map(|bibliography| bibliography.items.iter().enumerate())
+            .map(|(i, item)| (item.key.as_str(), i))
+            .collect();
+        let targets = self
+            .citation_keys
+            .iter()
+            .map(|keys| {
+                keys.iter()
+                    .map(|&key| CitationTarget {
+                        key: key.to_string(),
+                        index: positions.get(key).copied(),
+                    })
+                    .collect()
+            })
+            .collect();
+        let back_references = self
+            .back_references
+            .into_iter()
+            .map(|(key, locations)| (key.to_string(), locations))
+            .collect();
+        let uncited = rendered
+            .bibliography
+            .iter()
+            .flat_map(|bibliography| bibliography.items.iter())
+            .filter(|item| uncited.contains(item.key.as_str()))
+            .map(|item| item.key.clone())
+            .collect();
+
+        (rendered, CitationLinks { targets, back_references, uncited })
+    }
+}
+
+/// The links between the citations of a document and the items of its
+/// bibliography.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CitationLinks {
+    /// The cited entries of each citation in the order in which the
+    /// citations were added. The entries of a citation are in the order in
+    /// which they were passed, which may differ from the order of the style.
+    pub targets: Vec<Vec<CitationTarget>>,
+    /// The locations of the citations of each entry in the order of their
+    /// first citation with [`CitationEngine::cite_at`].
+    pub back_references: IndexMap<String, Vec<String>>,
+    /// The keys of the entries in the bibliography that were included
+    /// without a citation, in the order of the bibliography. They have no
+    /// back references.
+    pub uncited: Vec<String>,
+}
+
+/// An entry that a citation refers to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CitationTarget {
+    /// The key of the entry.
+    pub key: String,
+    /// The index of the item of the entry in the bibliography. It is `None`
+    /// if the style has no bibliography.
+    pub index: Option<usize>,
+}
+
+/// Where the entries that are included without a citation appear in the
+/// bibliography.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum UncitedPlacement {
+    /// Sort them among the cited entries by the rules of the style. In
+    /// numeric styles, they follow the cited entries.
+    #[default]
+    Merged,
+    /// Put them after all cited entries, keeping the order of the style
+    /// within both parts.
+    End,
+}
+
+/// A citation of an entry by its key.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct KeyedCite<'a> {
+    /// The key of the entry in the library.
+    pub key: &'a str,
+    /// The location within the entry, like a page.
+    pub locator: Option<SpecificLocator<'a>>,
+    /// How the citation is used in the text, like as the subject of a
+    /// sentence.
+    pub purpose: Option<CitePurpose>,
+    /// Whether the citation is not printed, but its entry still appears in
+    /// the bibliography.
+    pub hidden: bool,
+    /// Whether to omit the author because the text already names them.
+    pub suppress_author: bool,
+    /// The signal that introduces the cite, like "see" or "cf.".
+    pub signal: Option<Signal>,
+}
+
+impl<'a> KeyedCite<'a> {
+    /// Cite the entry with the key.
+    pub fn new(key: &'a str) -> Self {
+        Self {
+            key,
+            locator: None,
+            purpose: None,
+            hidden: false,
+            suppress_author: false,
+            signal: None,
+        }
+    }
+
+    /// Cite a location within the entry with the key.
+    pub fn with_locator(key: &'a str, locator: SpecificLocator<'a>) -> Self {
+        Self { locator: Some(locator), ..Self::new(key) }
+    }
+
+    /// Introduce the cite with a signal, like "e.g.," or "see also".
+    pub fn with_signal(mut self, signal: Signal) -> Self {
+        self.signal = Some(signal);
+        self
+    }
+}
+
+/// A citation refers to a key that is not in the library.
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+#[error("no entry with the key `{0}` in the library")]
+pub struct UnknownKeyError(pub String);
+
+/// A citation refers to keys that are not in the library. They are listed in
+/// the order of the citation.
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+#[error("no entries with the keys {} in the library", list_keys(.0))]
+pub struct UnknownKeysError(pub Vec<String>);
+
+fn list_keys(keys: &[String]) -> String {
+    keys.iter()
+        .map(|key| format!("`{key}`"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,189 @@
+//! Notes for figures and tables that are reproduced from another work.
+
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::sync::OnceLock;
+
+use citationberg::taxonomy::DateVariable;
+use citationberg::{FontStyle, IndependentStyle, Locale, LocaleCode};
+
+use super::elem::{ElemChild, ElemChildren, ElemMeta, Formatting};
+use super::taxonomy::EntryLike;
+use super::terms::ExtraTerm;
+use super::{
+    BibliographyDriver, BibliographyRequest, CitationItem, CitationRequest,
+    SpecificLocator,
+};
+
+/// The source part of the note, like "“Title,” by A. Author, 2020, Journal,
+/// 3(2), p. 4 (https://doi.org/10.1000/1)", following the APA manual. The
+/// word before the names is added in the language of the locale after
+/// rendering.
+const SOURCE_STYLE: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<style xmlns="http://purl.org/net/xbiblio/csl" class="in-text" version="1.0" default-locale="en-US">
+    <info>
+        <title>Reproduction note</title>
+        <id>reproduction-note</id>
+        <updated>2023-01-01T00:00:00+00:00</updated>
+    </info>
+    <macro name="locator">
+        <group delimiter=" ">
+            <label variable="locator" form="short"/>
+            <text variable="locator"/>
+        </group>
+    </macro>
+    <citation>
+        <layout>
+            <group delimiter=", ">
+                <choose>
+                    <if variable="container-title">
+                        <text variable="title" quotes="true"/>
+                    </if>
+                    <else>
+                        <group delimiter=" ">
+                            <text variable="title" font-style="italic"/>
+                            <text macro="locator" prefix="(" suffix=")"/>
+                        </group>
+                    </else>
+                </choose>
+                <names variable="author">
+                    <name and="text" initialize-with=". " delimiter=", "/>
+                    <substitute>
+                        <names variable="editor"/>
+                    </substitute>
+                </names>
+                <date variable="issued">
+                    <date-part name="year"/>
+                </date>
+                <choose>
+                    <if variable="container-title">
+                        <group delimiter=", ">
+                            <text variable="container-title" font-style="italic"/>
+                            <group>
+                                <text variable="volume" font-style="italic"/>
+                                <text variable="issue" prefix="(" suffix=")"/>
+                            </group>
+                            <text macro="locator"/>
+                        </group>
+                    </if>
+                    <else>
+                        <text variable="publisher"/>
+                    </else>
+                </choose>
+            </group>
+            <choose>
+                <if variable="DOI">
+                    <text variable="DOI" prefix=" (https://doi.org/" suffix=")"/>
+                </if>
+                <else>
+                    <text variable="URL" prefix=" (" suffix=")"/>
+                </else>
+            </choose>
+        </layout>
+    </citation>
+</style>"#;
+
+/// The parsed [`SOURCE_STYLE`].
+fn source_style() -> &'static IndependentStyle {
+    static STYLE: OnceLock<IndependentStyle> = OnceLock::new();
+    STYLE.get_or_init(|| IndependentStyle::from_xml(SOURCE_STYLE).unwrap())
+}
+
+/// The note below a figure or table that is reproduced from another work, like
+/// "*Note.* Adapted from *Title* (p. 4), by A. Author, 2020, Publisher.
+/// Copyright 2020 by Holder.", following the APA manual.
+#[derive(Debug, Clone)]
+pub struct ReproductionNote<'a, T: EntryLike> {
+    /// The work the figure or table is reproduced from.
+    pub entry: &'a T,
+    /// Whether the figure or table was changed, in which case it is "adapted
+    /// from" the work. Otherwise, it is "reprinted from" it.
+    pub adapted: bool,
+    /// Where in the work the figure or table is found, usually a page.
+    pub locator: Option<SpecificLocator<'a>>,
+    /// The holder of the copyright of the figure or table, if it is not in the
+    /// public domain or under an open license.
+    pub copyright_holder: Option<&'a str>,
+    /// The copyright year if it is not the year the work was published in.
+    pub copyright_year: Option<i32>,
+    /// The locale to render the work and the words of the note in.
+    pub locale: Option<LocaleCode>,
+}
+
+impl<'a, T: EntryLike + Clone + Hash + PartialEq + Eq + Debug> ReproductionNote<'a, T> {
+    /// Create a note for a figure or table that is reprinted unchanged.
+    pub fn new(entry: &'a T) -> Self {
+        Self {
+            entry,
+            adapted: false,
+            locator: None,
+            copyright_holder: None,
+            copyright_year: None,
+            locale: None,
+        }
+    }
+
+    /// Render the note. Its words are in the language of the locale if
+    /// hayagriva knows them and in English otherwise.
+    pub fn render(&self, locale_files: &[Locale]) -> ElemChildren {
+        let style = source_style();
+        let mut driver = BibliographyDriver::new();
+        let mut item = CitationItem::with_entry(self.entry);
+        item.locator = self.locator;
+        driver.citation(CitationRequest::new(
+            vec![item],
+            style,
+            self.locale.clone(),
+            locale_files,
+            None,
+        ));
+        let rendered = driver.finish(BibliographyRequest::new(
+            style,
+            self.locale.clone(),
+            locale_files,
+        ));
+
+        let locale = self.locale.clone().unwrap_or_else(LocaleCode::en_us);
+        let term = |term: ExtraTerm| {
+            term.localize(&locale)
+                .or_else(|| term.localize(&LocaleCode::en_us()))
+                .unwrap_or_default()
+        };
+        let italic = Formatting {
+            font_style: FontStyle::Italic,
+            ..Default::default()
+        };
+        let text = |text: String| ElemChild::Text(Formatting::default().add_text(text));
+        let by = term(ExtraTerm::By);
+        let source = term(if self.adapted {
+            ExtraTerm::AdaptedFrom
+        } else {
+            ExtraTerm::ReprintedFrom
+        });
+
+        let mut note = ElemChildren(vec![
+            ElemChild::Text(italic.add_text(term(ExtraTerm::NoteLabel).into())),
+            text(format!(" {source} ")),
+        ]);
+        if let Some(mut citation) = rendered.citations.into_iter().next() {
+            if let Some(names) = citation.citation.get_meta_mut(ElemMeta::Names) {
+                names.children.0.insert(0, text(format!("{by} ")));
+            }
+            note.0.extend(citation.citation.0);
+        }
+        note.0.push(text(".".into()));
+
+        if let Some(holder) = self.copyright_holder {
+            let year = self.copyright_year.or_else(|| {
+                Some(self.entry.resolve_date_variable(DateVariable::Issued)?.year)
+            });
+            let copyright = term(ExtraTerm::Copyright);
+            note.0.push(text(match year {
+                Some(year) => format!(" {copyright} {year} {by} {holder}."),
+                None => format!(" {copyright} {by} {holder}."),
+            }));
+        }
+
+        note
+    }
+}
@@ -0,0 +1,221 @@
+//! Re-rendering a library after single entries change.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use citationberg::{IndependentStyle, Locale, LocaleCode};
+
+use super::taxonomy::EntryLike;
+use super::{
+    BibliographyDriver, BibliographyRequest, CertainCiteProperties, CitationItem,
+    CitationRequest, DisambiguateState, ElemChildren, IbidState, PendingItem, Rendered,
+    StyleOptions, UnknownKeyError,
+};
+use crate::{Entry, Library};
+
+/// The properties of a bibliography item that its formatting depends on
+/// besides its entry.
+type ItemProperties =
+    (CertainCiteProperties, usize, IbidState, DisambiguateState, Option<LocaleCode>);
+
+/// The sort keys and bibliography items of the entries of a previous render,
+/// by the keys of the entries.
+#[derive(Debug, Default)]
+pub(super) struct RenderCache {
+    sort_keys: Mutex<HashMap<String, Vec<Option<String>>>>,
+    items: Mutex<HashMap<String, (ItemProperties, ElemChildren)>>,
+}
+
+impl RenderCache {
+    /// The rendered sort keys of the entry with the key. They are computed
+    /// if the cache does not contain them.
+    pub(super) fn sort_keys(
+        &self,
+        key: &str,
+        compute: impl FnOnce() -> Vec<Option<String>>,
+    ) -> Vec<Option<String>> {
+        if let Some(keys) = self.sort_keys.lock().unwrap().get(key) {
+            return keys.clone();
+        }
+
+        let keys = compute();
+        self.sort_keys.lock().unwrap().insert(key.to_string(), keys.clone());
+        keys
+    }
+
+    /// The formatted bibliography item. It is rendered again if the cache
+    /// does not contain it or if the item was rendered with different
+    /// properties, for example another year suffix or citation number.
+    pub(super) fn item<T: EntryLike>(
+        &self,
+        item: &PendingItem<'_, T>,
+        render: impl FnOnce() -> ElemChildren,
+    ) -> ElemChildren {
+        let key = item.entry.key();
+        let props = (
+            item.certain,
+            item.citation_number,
+            item.ibid,
+            item.disambiguation.clone(),
+            item.locale.clone(),
+        );
+        if let Some((cached, children)) = self.items.lock().unwrap().get(key.as_ref()) {
+            if *cached == props {
+                return children.clone();
+            }
+        }
+
+        let children = render();
+        self.items
+            .lock()
+            .unwrap()
+            .insert(key.into_owned(), (props, children.clone()));
+        children
+    }
+
+    /// Forget everything that was rendered for the entry with the key.
+    fn invalidate(&mut self, key: &str) {
+        self.sort_keys.get_mut().unwrap().remove(key);
+        self.items.get_mut().unwrap().remove(key);
+    }
+}
+
+/// Renders all entries of a library with a style and keeps the result up to
+/// date when single entries change, like in an editor.
+///
+/// Each entry is cited once, in the order of the library. When an entry is
+/// replaced with [`update_entry`](Self::update_entry), the style still sorts,
+/// numbers and disambiguates all entries, but only the entries whose entry or
+/// properties, like their year suffix or citation number, changed are
+/// formatted again.
+#[derive(Debug)]
+pub struct IncrementalEngine<'a> {
+    library: Library,
+    style: &'a IndependentStyle,
+    locale: Option<LocaleCode>,
+    locale_files: &'a [Locale],
+    options: StyleOptions,
+    cache: RenderCache,
+    rendered: Rendered,
+}
+
+impl<'a> IncrementalEngine<'a> {
+    /// Create a new engine and render the library with the style.
+    pub fn new(
+        library: Library,
+        style: &'a IndependentStyle,
+        locale: Option<LocaleCode>,
+        locale_files: &'a [Locale],
+    ) -> Self {
+        Self::with_options(library, style, locale, locale_files, StyleOptions::default())
+    }
+
+    /// Create a new engine that overrides the punctuation of the style and
+    /// render the library.
+    pub fn with_options(
+        library: Library,
+        style: &'a IndependentStyle,
+        locale: Option<LocaleCode>,
+        locale_files: &'a [Locale],
+        options: StyleOptions,
+    ) -> Self {
+        let mut engine = Self {
+            library,
+            style,
+            locale,
+            locale_files,
+            options,
+            cache: RenderCache::default(),
+            rendered: Rendered {
+                bibliography: None,
+                citations: Vec::new(),
+                non_ascii: Vec::new(),
+            },
+        };
+        engine.rendered = engine.render();
+        engine
+    }
+
+    /// The library with all updates applied.
+    pub fn library(&self) -> &Library {
+        &self.library
+    }
+
+    /// The current citations and bibliography. The citation at each index
+    /// cites the entry at the same index of the library.
+    pub fn rendered(&self) -> &Rendered {
+        &self.rendered
+    }
+
+    /// Replace the entry with the key and render the library again.
+    ///
+    /// Returns the keys of the entries whose citation or bibliography item
+    /// changed, in the order of the library. Besides the updated entry, these
+    /// may be entries that are disambiguated from it or renumbered because it
+    /// moved. The key of the new entry is set to `key`.
+    pub fn update_entry(
+        &mut self,
+        key: &str,
+        mut entry: Entry,
+    ) -> Result<Vec<String>, UnknownKeyError> {
+        let Some(slot) = self.library.0.get_mut(key) else {
+            return Err(UnknownKeyError(key.to_string()));
+        };
+        entry.key = key.to_string();
+        *slot = entry;
+        self.cache.invalidate(key);
+
+        let rendered = self.render();
+        let old = std::mem::replace(&mut self.rendered, rendered);
+        let old_items = items_by_key(&old);
+        let new_items = items_by_key(&self.rendered);
+
+        Ok(self
+            .library
+            .keys()
+            .enumerate()
+            .filter(|&(i, key)| {
+                old.citations.get(i).map(|c| &c.citation)
+                    != self.rendered.citations.get(i).map(|c| &c.citation)
+                    || old_items.get(key) != new_items.get(key)
+            })
+            .map(|(_, key)| key.to_string())
+            .collect())
+    }
+
+    /// Cite every entry of the library once and render the citations and the
+    /// bibliography.
+    fn render(&self) -> Rendered {
+        let mut driver = BibliographyDriver::new().with_cache(&self.cache);
+        for entry in self.library.iter() {
+            driver.citation(
+                CitationRequest::new(
+                    vec![CitationItem::with_entry(entry)],
+                    self.style,
+                    self.locale.clone(),
+                    self.locale_files,
+                    None,
+                )
+                .with_options(self.options.clone()),
+            );
+        }
+
+        driver.finish(
+            BibliographyRequest::new(self.style, self.locale.clone(), self.locale_files)
+                .with_options(self.options.clone()),
+        )
+    }
+}
+
+/// The formatted parts of the bibliography items by the keys of their
+/// entries.
+fn items_by_key(rendered: &Rendered) -> HashMap<&str, impl PartialEq + '_> {
+    rendered
+        .bibliography
+        .iter()
+        .flat_map(|bibliography| bibliography.items.iter())
+        .map(|item| {
+            (item.key.as_str(), (&item.first_field, &item.content, &item.annotation))
+        })
+        .collect()
+}
@@ -1,53 +1,97 @@
 use std::borrow::Cow;
 use std::cell::RefCell;
 use std::collections::hash_map::Entry as HmEntry;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fmt::{Debug, Write};
 use std::hash::Hash;
 use std::num::{NonZeroI16, NonZeroUsize};
 use std::{mem, vec};
 
 use citationberg::taxonomy::{
-    DateVariable, Locator, NameVariable, OtherTerm, StandardVariable, Term, Variable,
+    DateVariable, Kind, Locator, NameVariable, OtherTerm, StandardVariable, Term,
+    Variable,
 };
 use citationberg::{
     taxonomy as csl_taxonomy, Affixes, BaseLanguage, Citation, CitationFormat, Collapse,
-    CslMacro, Display, GrammarGender, IndependentStyle, InheritableNameOptions, Layout,
-    LayoutRenderingElement, Locale, LocaleCode, Names, SecondFieldAlign, StyleCategory,
-    StyleClass, TermForm, ToFormatting,
+    CslMacro, DisambiguationRule, Display, FontStyle, GrammarGender, IndependentStyle,
+    InheritableNameOptions, Layout, LayoutRenderingElement, Locale, LocaleCode, Names,
+    SecondFieldAlign, SortDirection, SortKey, StyleCategory, StyleClass, TermForm,
+    ToFormatting,
 };
-use citationberg::{DateForm, LongShortForm, OrdinalLookup, TextCase};
+use citationberg::{DateForm, LongShortForm, NumberForm, OrdinalLookup, TextCase};
 use indexmap::IndexSet;
 
-use crate::csl::elem::{simplify_children, NonEmptyStack};
+use crate::csl::elem::{
+    absorb_link_prefixes, capitalize_first, mark_link_breaks, shorten_links,
+    simplify_children, strip_link_schemes, NonEmptyStack,
+};
 use crate::csl::rendering::names::NameDisambiguationProperties;
 use crate::csl::rendering::RenderCsl;
-use crate::lang::CaseFolder;
-use crate::types::{ChunkKind, ChunkedString, Date, Person};
+use crate::lang::{Case, CaseExceptions, CaseFolder};
+use crate::types::{
+    ChunkKind, ChunkedString, DateParts, EntryType, Era, MaybeTyped, Person,
+    PublicationStatus, StringChunk,
+};
+use crate::{Entry, GroupBy, Library};
 
+use self::ascii::transliterate;
+use self::bidi::isolate_rtl;
 use self::elem::last_text_mut_child;
 pub use self::elem::{
     BufWriteFormat, Elem, ElemChild, ElemChildren, ElemMeta, Formatted, Formatting,
 };
-use self::taxonomy::{EntryLike, NumberVariableResult};
+pub use self::engine::{
+    CitationEngine, CitationLinks, CitationTarget, KeyedCite, UncitedPlacement,
+    UnknownKeyError, UnknownKeysError,
+};
+pub use self::figure::ReproductionNote;
+pub use self::incremental::IncrementalEngine;
+use self::incremental::RenderCache;
+pub use self::notes::{FormattedNote, NoteStream};
+pub use self::options::{
+    BidiIsolation, NameOrder, PublisherLocation, RoleLabelCase, RoleLabelStyle,
+    StyleOptions, TitleLanguages,
+};
+pub use self::output::{
+    link_break_points, render_ansi, render_latex, render_latex_bibliography,
+    render_plain, render_rtf, render_rtf_bibliography, AnsiLinks, AnsiOptions,
+    PlainItalics, PlainLinks, PlainTextOptions,
+};
+use self::punctuation::{
+    apply_period_options, bind_labels, smarten, tidy_punctuation, QuoteMarks,
+};
+use self::taxonomy::{is_disambiguating, letter, EntryLike, NumberVariableResult};
+use self::terms::ExtraTerm;
 
-#[cfg(feature = "rkyv")]
+#[cfg(feature = "archive")]
 pub mod archive;
+mod ascii;
+mod bidi;
 mod elem;
+mod engine;
+mod figure;
+mod incremental;
+mod notes;
+mod options;
+mod output;
+mod punctuation;
 mod rendering;
 mod sort;
 mod taxonomy;
+mod terms;
 
 /// This struct formats a set of citations according to a style.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct BibliographyDriver<'a, T: EntryLike> {
     /// The citations we have seen so far.
     citations: Vec<CitationRequest<'a, T>>,
+    /// Formatted parts of the entries from a previous render.
+    cache: Option<&'a RenderCache>,
 }
 
 impl<T: EntryLike> Default for BibliographyDriver<'_, T> {
     fn default() -> Self {
-        Self { citations: Vec::new() }
+        Self { citations: Vec::new(), cache: None }
     }
 }
 
@@ -64,9 +108,27 @@ struct SpeculativeItemRender<'a, T: EntryLike> {
     hidden: bool,
     locale: Option<LocaleCode>,
     purpose: Option<CitePurpose>,
+    /// Whether to omit the author in the final render. The speculative renders
+    /// keep it, so that the item is disambiguated from other works.
+    suppress_author: bool,
+    signal: Option<Signal>,
     collapse_verdict: Option<CollapseVerdict>,
 }
 
+/// A bibliography item whose cite properties are final.
+#[derive(Debug)]
+struct PendingItem<'a, T: EntryLike> {
+    entry: &'a T,
+    certain: CertainCiteProperties,
+    citation_number: usize,
+    ibid: IbidState,
+    disambiguation: DisambiguateState,
+    locale: Option<LocaleCode>,
+    year_suffix: String,
+    /// The persons of the first names of the item, to find repeated authors.
+    persons: Vec<Cow<'a, Person>>,
+}
+
 #[derive(Debug, Hash, PartialEq, Eq)]
 struct SpeculativeCiteRender<'a, 'b, T: EntryLike> {
     items: Vec<SpeculativeItemRender<'a, T>>,
@@ -79,6 +141,13 @@ impl<'a, T: EntryLike> BibliographyDriver<'a, T> {
         Self::default()
     }
 
+    /// Reuse the sort keys and bibliography items of entries that were
+    /// formatted before.
+    fn with_cache(mut self, cache: &'a RenderCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
     /// Create a new citation with the given items.
     pub fn citation(&mut self, mut req: CitationRequest<'a, T>) {
         let style = req.style();
@@ -87,6 +156,7 @@ impl<'a, T: EntryLike> BibliographyDriver<'a, T> {
             item.initial_idx = i;
         }
         style.sort(&mut req.items, style.csl.citation.sort.as_ref(), req.locale.as_ref());
+        req.items.sort_by_key(|item| item.signal);
         self.citations.push(req);
     }
 }
@@ -95,6 +165,78 @@ impl<'a, T: EntryLike> BibliographyDriver<'a, T> {
 impl<'a, T: EntryLike + Hash + PartialEq + Eq + Debug> BibliographyDriver<'a, T> {
     /// Render the bibliography.
     pub fn finish(self, request: BibliographyRequest<'_>) -> Rendered {
+        BibliographyDriver::finish_with(self, request, |items, render| {
+            items.iter().map(render).collect()
+        })
+    }
+
+    /// Render the bibliography and format its items in parallel.
+    ///
+    /// The citations are sorted and disambiguated first, so the result is the
+    /// same as that of [`finish`](Self::finish).
+    #[cfg(feature = "rayon")]
+    pub fn finish_par(self, request: BibliographyRequest<'_>) -> Rendered
+    where
+        T: Sync,
+    {
+        use rayon::prelude::*;
+        BibliographyDriver::finish_with(self, request, |items, render| {
+            items.par_iter().map(render).collect()
+        })
+    }
+
+    /// Render the bibliography. The items of the bibliography are formatted
+    /// by passing each of them to the renderer in `map`.
+    fn finish_with(
+        self,
+        request: BibliographyRequest<'a>,
+        map: impl FnOnce(
+            &[PendingItem<'a, T>],
+            &(dyn Fn(&PendingItem<'a, T>) -> ElemChildren + Sync),
+        ) -> Vec<ElemChildren>,
+    ) -> Rendered {
+        let StreamedRendered { citations, bibliography, non_ascii } =
+            self.finish_stream(request);
+        let Some(mut stream) = bibliography else {
+            return Rendered { bibliography: None, citations, non_ascii };
+        };
+
+        let pending: Vec<_> = stream.pending.by_ref().collect();
+        let (style, locale) = (&stream.style, stream.request.locale.as_ref());
+        let cache = stream.cache;
+        let rendered = map(&pending, &|item| render_bib_item(style, locale, cache, item));
+        let items = pending
+            .into_iter()
+            .zip(rendered)
+            .map(|(item, rendered)| stream.finish_item(item, rendered))
+            .collect();
+
+        let mut non_ascii: BTreeSet<char> = non_ascii.into_iter().collect();
+        non_ascii.extend(stream.non_ascii);
+        Rendered {
+            bibliography: Some(RenderedBibliography {
+                hanging_indent: stream.hanging_indent,
+                second_field_align: stream.second_field_align,
+                line_spacing: stream.line_spacing,
+                entry_spacing: stream.entry_spacing,
+                items,
+            }),
+            citations,
+            non_ascii: non_ascii.into_iter().collect(),
+        }
+    }
+
+    /// Render the citations and prepare the bibliography, whose items are
+    /// only formatted as they are taken from the returned
+    /// [`BibliographyStream`].
+    ///
+    /// Sorting, numbering, and disambiguation need all citations, so they
+    /// run up front, like for [`finish`](Self::finish). The items are the same
+    /// as those that `finish` returns.
+    pub fn finish_stream(
+        mut self,
+        request: BibliographyRequest<'a>,
+    ) -> StreamedRendered<'a, T> {
         // 1.  Assign citation numbers by bibliography ordering or by citation
         //     order and render them a first time without their locators.
         let bib_style = request.style();
@@ -109,14 +251,29 @@ impl<'a, T: EntryLike + Hash + PartialEq + Eq + Debug> BibliographyDriver<'a, T>
 
         let mut entries: Vec<_> =
             entry_set.into_iter().map(CitationItem::with_entry).collect();
-        bib_style.sort(
+        bib_style.sort_cached(
             &mut entries,
             bib_style.csl.bibliography.as_ref().and_then(|b| b.sort.as_ref()),
             request.locale.as_ref(),
+            self.cache,
+            true,
         );
-        let citation_number = |item: &T| {
-            entries.iter().position(|e| e.entry == item).expect("entry not found")
-        };
+        let numbers: HashMap<&T, usize> =
+            entries.iter().enumerate().map(|(i, e)| (e.entry, i)).collect();
+        let citation_number = |item: &T| *numbers.get(item).expect("entry not found");
+
+        // Cites that the citation sort considers equal follow the bibliography,
+        // so that their year suffixes ascend. The items stay in the signal
+        // groups that `citation` sorted them into.
+        for citation in self.citations.iter_mut() {
+            let style = citation.style();
+            if let Some(sort) = style.csl.citation.sort.as_ref() {
+                for group in citation.items.chunk_by_mut(|a, b| a.signal == b.signal) {
+                    group.sort_by_key(|item| citation_number(item.entry));
+                    style.sort(group, Some(sort), citation.locale.as_ref());
+                }
+            }
+        }
 
         let mut seen: HashSet<*const T> = HashSet::new();
         let mut res: Vec<SpeculativeCiteRender<T>> = Vec::new();
@@ -158,6 +315,8 @@ impl<'a, T: EntryLike + Hash + PartialEq + Eq + Debug> BibliographyDriver<'a, T>
                         is_near_note,
                         is_first: seen.insert(*entry),
                         initial_idx: item.initial_idx,
+                        suppress_author: false,
+                        repeated_container: false,
                     },
                     speculative: SpeculativeCiteProperties::speculate(
                         None,
@@ -186,17 +345,28 @@ impl<'a, T: EntryLike + Hash + PartialEq + Eq + Debug> BibliographyDriver<'a, T>
                     hidden: item.hidden,
                     locale: item.locale.clone(),
                     purpose: item.purpose,
+                    suppress_author: item.suppress_author,
+                    signal: item.signal,
                     collapse_verdict: None,
                 });
 
                 last_cite = Some(item);
             }
 
+            // A citation of multiple works cannot be referred to with "ibid."
+            if items.iter().filter(|item| !item.hidden).count() > 1 {
+                last_cite = None;
+            }
+
             res.push(SpeculativeCiteRender { items: renders, request: citation });
         }
 
         // 2.  Disambiguate the citations.
         //
+        // The global given name rules apply to all citations, so the names
+        // of persons with the same family name are expanded first.
+        disambiguate_given_names(&mut res);
+
         // If we have set the disambiguation state for an item, we need to set
         // the same state for all entries referencing that item.
         for _ in 0..6 {
@@ -258,8 +428,8 @@ impl<'a, T: EntryLike + Hash + PartialEq + Eq + Debug> BibliographyDriver<'a, T>
         // 3. Group adjacent citations.
         for cite in res.iter_mut() {
             // This map contains the last index of each entry with this names
-            // elem.
-            let mut map: HashMap<String, usize> = HashMap::new();
+            // elem and the index of their group.
+            let mut map: HashMap<String, (usize, usize)> = HashMap::new();
             let mut group_idx = 0;
 
             for i in 0..cite.items.len() {
@@ -281,24 +451,37 @@ impl<'a, T: EntryLike + Hash + PartialEq + Eq + Debug> BibliographyDriver<'a, T>
                     continue;
                 };
 
-                let Some(name_elem) = cite.items[i]
-                    .rendered
-                    .get_meta(ElemMeta::Names)
-                    .map(|e| format!("{:?}", e))
+                // A cite without its author is not grouped with the other
+                // works of the author.
+                if cite.items[i].suppress_author {
+                    continue;
+                }
+
+                // Works whose names only look the same, like "Smith et al."
+                // with different co-authors, are not grouped.
+                let Some(name_elem) =
+                    cite.items[i].rendered.get_meta(ElemMeta::Names).map(|e| {
+                        format!(
+                            "{:?}{:?}{:?}",
+                            e,
+                            first_persons(&cite.items[i]),
+                            cite.items[i].signal
+                        )
+                    })
                 else {
                     continue;
                 };
 
                 let mut prev = None;
-                let target = *map
-                    .entry(name_elem)
-                    .and_modify(|i| {
+                let (target, group) = *map
+                    .entry(name_elem.clone())
+                    .and_modify(|(i, _)| {
                         prev = Some(*i);
                         *i += 1
                     })
                     .or_insert_with(|| {
                         group_idx += 1;
-                        i
+                        (i, group_idx)
                     });
 
                 let mut pos = i;
@@ -307,8 +490,17 @@ impl<'a, T: EntryLike + Hash + PartialEq + Eq + Debug> BibliographyDriver<'a, T>
                     pos -= 1;
                 }
 
+                // The groups after the target moved back by one.
+                if target < i {
+                    for (name, (last, _)) in map.iter_mut() {
+                        if *name != name_elem && *last >= target {
+                            *last += 1;
+                        }
+                    }
+                }
+
                 cite.items[target].delim_override = Some(delim);
-                cite.items[target].group_idx = Some(group_idx);
+                cite.items[target].group_idx = Some(group);
                 if let Some(prev) = prev {
                     cite.items[prev].delim_override = None;
                 }
@@ -317,14 +509,30 @@ impl<'a, T: EntryLike + Hash + PartialEq + Eq + Debug> BibliographyDriver<'a, T>
 
         // 4. Render citations with locator.
         // 4a. Make final calls on all [`SpeculativeCiteProperties`].
-        //     - Re-check for ibid.
-        for i in 0..res.len() {
-            for j in 0..res[i].items.len() {
+        //     - Re-check for ibid. Go backwards so that the preceding cite
+        //       still has its locator.
+        for i in (0..res.len()).rev() {
+            for j in (0..res[i].items.len()).rev() {
                 // TODO filter is not hidden
                 let last = if j == 0 && i == 0 {
                     None
                 } else if j == 0 {
-                    res[i - 1].items.last()
+                    // In a new note, "ibid." refers to the whole previous
+                    // note, which may consist of several citations.
+                    let note = res[i - 1].request.note_number;
+                    let start = if note.is_some() && note != res[i].request.note_number {
+                        res[..i]
+                            .iter()
+                            .rposition(|cite| cite.request.note_number != note)
+                            .map_or(0, |k| k + 1)
+                    } else {
+                        i - 1
+                    };
+                    let mut visible = res[start..i]
+                        .iter()
+                        .flat_map(|cite| cite.items.iter())
+                        .filter(|item| !item.hidden);
+                    visible.next().filter(|_| visible.next().is_none())
                 } else {
                     Some(&res[i].items[j - 1])
                 }
@@ -366,8 +574,39 @@ impl<'a, T: EntryLike + Hash + PartialEq + Eq + Debug> BibliographyDriver<'a, T>
             }
         }
 
+        //     - Sort the cites by their final citation number if the style
+        //       sorts by it. The number is unknown when the citation is added.
+        for cite in res.iter_mut() {
+            let direction = cite
+                .request
+                .style
+                .citation
+                .sort
+                .as_ref()
+                .and_then(|sort| sort.keys.first())
+                .and_then(|key| match key {
+                    SortKey::Variable {
+                        variable:
+                            Variable::Number(csl_taxonomy::NumberVariable::CitationNumber),
+                        sort_direction,
+                    } => Some(*sort_direction),
+                    _ => None,
+                });
+
+            if let Some(direction) = direction {
+                // Each signal group is sorted on its own.
+                for group in cite.items.chunk_by_mut(|a, b| a.signal == b.signal) {
+                    group.sort_by_key(|item| item.cite_props.speculative.citation_number);
+                    if direction == SortDirection::Descending {
+                        group.reverse();
+                    }
+                }
+            }
+        }
+
         // Rerender.
         let mut final_citations: Vec<RenderedCitation> = Vec::new();
+        let mut non_ascii = BTreeSet::new();
         for cite in res.iter_mut() {
             let style_ctx = cite.request.style();
             // 5. Collapse grouped citations.
@@ -376,9 +615,20 @@ impl<'a, T: EntryLike + Hash + PartialEq + Eq + Debug> BibliographyDriver<'a, T>
             }
 
             for item in cite.items.iter_mut() {
+                item.cite_props.certain.suppress_author = item.suppress_author;
                 item.rendered = last_purpose_render(&style_ctx, item, cite.request);
             }
 
+            // A note that refers to the previous one starts with a term like
+            // "ibid." that begins the sentence of the note.
+            if cite.request.style.settings.class == StyleClass::Note {
+                if let Some(first) = cite.items.iter_mut().find(|item| !item.hidden) {
+                    if first.cite_props.speculative.ibid != IbidState::Different {
+                        capitalize_first(&mut first.rendered);
+                    }
+                }
+            }
+
             // 6. Add affixes.
             let formatting = Formatting::default()
                 .apply(cite.request.style.citation.layout.to_formatting());
@@ -390,13 +640,18 @@ impl<'a, T: EntryLike + Hash + PartialEq + Eq + Debug> BibliographyDriver<'a, T>
                 } else {
                     let mut elem_children: Vec<ElemChild> = Vec::new();
 
-                    if let Some(prefix) = cite.request.prefix() {
+                    if let Some(prefix) = cite.request.layout_prefix() {
                         elem_children.push(ElemChild::Text(Formatted {
                             text: prefix.to_string(),
                             formatting,
                         }));
                     }
 
+                    if let Some(prefix) = cite_prefix(cite.request, formatting) {
+                        elem_children.push(prefix);
+                    }
+
+                    let mut last_signal = None;
                     for (i, item) in cite.items.iter().enumerate() {
                         if item.hidden {
                             continue;
@@ -422,6 +677,21 @@ impl<'a, T: EntryLike + Hash + PartialEq + Eq + Debug> BibliographyDriver<'a, T>
                             }
                         }
 
+                        // The signal is printed once for its group of items.
+                        let first = last_signal.is_none();
+                        if let Some(signal) = item
+                            .signal
+                            .filter(|&signal| last_signal != Some(Some(signal)))
+                        {
+                            elem_children.push(signal_elem(
+                                signal,
+                                first && cite_prefix(cite.request, formatting).is_none(),
+                                cite.request,
+                                formatting,
+                            ));
+                        }
+                        last_signal = Some(item.signal);
+
                         elem_children.push(ElemChild::Elem(Elem {
                             children: item.rendered.clone(),
                             display: None,
@@ -431,7 +701,11 @@ impl<'a, T: EntryLike + Hash + PartialEq + Eq + Debug> BibliographyDriver<'a, T>
                         }));
                     }
 
-                    if let Some(suffix) = cite.request.suffix() {
+                    if let Some(suffix) = cite_suffix(cite.request, formatting) {
+                        elem_children.push(suffix);
+                    }
+
+                    if let Some(suffix) = cite.request.layout_suffix() {
                         let print = last_text_mut_child(&mut elem_children)
                             .map_or(true, |t| !t.text.ends_with(suffix));
                         if print {
@@ -441,74 +715,424 @@ impl<'a, T: EntryLike + Hash + PartialEq + Eq + Debug> BibliographyDriver<'a, T>
                         }
                     }
 
-                    simplify_children(ElemChildren(elem_children))
+                    let mut children = simplify_children(ElemChildren(elem_children));
+                    if cite.request.options.tidy_punctuation {
+                        tidy_punctuation(&mut children);
+                    }
+                    finish_links(&mut children, &cite.request.options);
+                    if cite.request.options.ascii_only {
+                        transliterate(&mut children, &mut non_ascii);
+                    }
+                    if cite.request.options.non_breaking_spaces {
+                        bind_labels(&mut children);
+                    }
+                    if let Some(mode) = cite.request.options.bidi_isolation {
+                        isolate_rtl(&mut children, mode);
+                    }
+                    children
                 },
             })
         }
 
-        let bib_render = if let Some(bibliography) = &request.style.bibliography {
-            let mut items = Vec::new();
-            for entry in entries.into_iter() {
-                let cited_item = res
-                    .iter()
-                    .flat_map(|cite| cite.items.iter())
-                    .find(|item| item.entry == entry.entry)
-                    .unwrap();
+        let bibliography = request.style.bibliography.as_ref().map(|bibliography| {
+            // The first cite of each entry determines its bibliography item.
+            let mut first_cites = HashMap::new();
+            for item in res.iter().flat_map(|cite| cite.items.iter()) {
+                first_cites.entry(item.entry).or_insert(item);
+            }
 
-                items.push((
-                    simplify_children(
-                        bib_style
-                            .bibliography(
-                                entry.entry,
-                                CiteProperties {
-                                    certain: cited_item.cite_props.certain,
-                                    speculative: SpeculativeCiteProperties {
-                                        locator: None,
-                                        citation_number: cited_item
-                                            .cite_props
-                                            .speculative
-                                            .citation_number,
-                                        ibid: cited_item.cite_props.speculative.ibid,
-                                        disambiguation: cited_item
-                                            .cite_props
-                                            .speculative
-                                            .disambiguation
-                                            .clone(),
-                                    },
-                                },
-                                cited_item.locale.as_ref(),
-                                request.locale.as_ref(),
-                            )
-                            .unwrap(),
-                    ),
-                    entry.entry.key().to_string(),
-                ))
+            let mut pending = Vec::new();
+            let mut prev_periodical = None;
+            for entry in entries.iter() {
+                let cited_item = first_cites[entry.entry];
+
+                // The first of consecutive entries in the same periodical
+                // keeps its title.
+                let periodical = request
+                    .options
+                    .repeated_container_marker
+                    .as_ref()
+                    .and_then(|_| periodical_title(entry.entry));
+                let repeated_container =
+                    periodical.is_some() && periodical == prev_periodical;
+                prev_periodical = periodical;
+
+                pending.push(PendingItem {
+                    entry: entry.entry,
+                    certain: CertainCiteProperties {
+                        repeated_container,
+                        ..cited_item.cite_props.certain
+                    },
+                    citation_number: cited_item.cite_props.speculative.citation_number,
+                    ibid: cited_item.cite_props.speculative.ibid,
+                    disambiguation: cited_item
+                        .cite_props
+                        .speculative
+                        .disambiguation
+                        .for_bibliography(),
+                    locale: cited_item.locale.clone(),
+                    year_suffix: match cited_item.cite_props.speculative.disambiguation {
+                        DisambiguateState::YearSuffix(s) => letter(s),
+                        _ => String::new(),
+                    },
+                    persons: first_persons(cited_item),
+                });
             }
 
-            Some(RenderedBibliography {
+            BibliographyStream {
                 hanging_indent: bibliography.hanging_indent,
                 second_field_align: bibliography.second_field_align,
                 line_spacing: bibliography.line_spacing,
                 entry_spacing: bibliography.entry_spacing,
-                items: items
-                    .into_iter()
-                    .map(|(mut i, key)| {
-                        if bibliography.second_field_align.is_some() {
-                            BibliographyItem::new(key, i.remove_any_meta(), i)
-                        } else {
-                            BibliographyItem::new(key, None, i)
-                        }
-                    })
-                    .collect(),
-            })
+                style: bib_style,
+                pending: pending.into_iter(),
+                prev_names: None,
+                non_ascii: BTreeSet::new(),
+                cache: self.cache,
+                request,
+            }
+        });
+
+        StreamedRendered {
+            citations: final_citations,
+            bibliography,
+            non_ascii: non_ascii.into_iter().collect(),
+        }
+    }
+}
+
+/// The citations of a [`BibliographyDriver`] with a bibliography whose items
+/// are formatted on demand.
+#[derive(Debug)]
+pub struct StreamedRendered<'a, T: EntryLike> {
+    /// The bibliography, if the style has one.
+    pub bibliography: Option<BibliographyStream<'a, T>>,
+    /// The citation items.
+    pub citations: Vec<RenderedCitation>,
+    /// The characters that remain in the citations without an ASCII
+    /// transliteration although [`StyleOptions::ascii_only`] is set. Those of
+    /// the bibliography are collected by the stream.
+    pub non_ascii: Vec<char>,
+}
+
+/// A bibliography whose items are formatted one at a time as they are
+/// iterated, so that they can be written out without keeping all of them in
+/// memory.
+#[derive(Debug)]
+pub struct BibliographyStream<'a, T: EntryLike> {
+    /// Render the bibliography in a hanging indent.
+    pub hanging_indent: bool,
+    /// When set, the second field is aligned.
+    pub second_field_align: Option<SecondFieldAlign>,
+    /// The line spacing within the bibliography as a multiple of regular line
+    /// spacing.
+    pub line_spacing: NonZeroI16,
+    /// Extra space between entries as a multiple of line height.
+    pub entry_spacing: i16,
+    style: StyleContext<'a>,
+    request: BibliographyRequest<'a>,
+    pending: vec::IntoIter<PendingItem<'a, T>>,
+    /// The names of the previous item, to substitute repeated authors.
+    prev_names: Option<String>,
+    non_ascii: BTreeSet<char>,
+    cache: Option<&'a RenderCache>,
+}
+
+impl<'a, T: EntryLike + Hash + PartialEq + Eq + Debug> BibliographyStream<'a, T> {
+    /// The characters that remain in the items formatted so far without an
+    /// ASCII transliteration although [`StyleOptions::ascii_only`] is set.
+    pub fn non_ascii(&self) -> Vec<char> {
+        self.non_ascii.iter().copied().collect()
+    }
+
+    /// Format the remaining items and write them to `w`, one per line. An
+    /// aligned first field, like a number, is separated from its item by a
+    /// space, and an annotation follows on its own line.
+    #[cfg(feature = "std")]
+    pub fn write_to(
+        &mut self,
+        w: &mut impl std::io::Write,
+        format: BufWriteFormat,
+    ) -> std::io::Result<()> {
+        let mut buf = String::new();
+        for item in self {
+            buf.clear();
+            if let Some(first_field) = &item.first_field {
+                first_field.write_buf(&mut buf, format).unwrap();
+                buf.push(' ');
+            }
+            item.content.write_buf(&mut buf, format).unwrap();
+            buf.push('\n');
+            if let Some(annotation) = &item.annotation {
+                annotation.write_buf(&mut buf, format).unwrap();
+                buf.push('\n');
+            }
+            w.write_all(buf.as_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Apply the options that depend on the previous item and tidy the
+    /// formatted item.
+    fn finish_item(
+        &mut self,
+        item: PendingItem<'a, T>,
+        mut rendered: ElemChildren,
+    ) -> BibliographyItem {
+        let options = &self.request.options;
+        if let Some(substitute) = &options.repeated_author_substitute {
+            // Compare the plain text because the names may have absorbed the
+            // punctuation that follows them.
+            let names = rendered.get_meta(ElemMeta::Names).map(|e| {
+                let text = format!("{:#}", e.children);
+                format!("{}{:?}", text.trim_end_matches([' ', '.', ',']), item.persons)
+            });
+            if names.is_some() && names == self.prev_names {
+                substitute_names(&mut rendered, substitute);
+            }
+            self.prev_names = names;
+        }
+
+        let mut annotation =
+            options.annotations.then(|| annotation(item.entry)).flatten();
+        if options.tidy_punctuation {
+            tidy_punctuation(&mut rendered);
+        }
+        finish_links(&mut rendered, options);
+        let locale = self.style.locale();
+        if options.citation_counts {
+            if let Some(count) = item.entry.citation_count() {
+                append_citation_count(&mut rendered, count, &locale);
+            }
+        }
+        apply_period_options(&mut rendered, options, &locale);
+        if options.ascii_only {
+            transliterate(&mut rendered, &mut self.non_ascii);
+            if let Some(annotation) = &mut annotation {
+                transliterate(&mut annotation.children, &mut self.non_ascii);
+            }
+        }
+        if options.non_breaking_spaces {
+            bind_labels(&mut rendered);
+        }
+        let first_field = if self.second_field_align.is_some() {
+            rendered.remove_any_meta()
         } else {
             None
         };
+        if let Some(mode) = options.bidi_isolation {
+            isolate_rtl(&mut rendered, mode);
+        }
+        BibliographyItem::new(
+            item.entry.key().to_string(),
+            first_field,
+            rendered,
+            annotation,
+            item.year_suffix,
+        )
+    }
+}
 
-        Rendered {
-            bibliography: bib_render,
-            citations: final_citations,
+impl<'a, T: EntryLike + Hash + PartialEq + Eq + Debug> Iterator
+    for BibliographyStream<'a, T>
+{
+    type Item = BibliographyItem;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.pending.next()?;
+        let rendered =
+            render_bib_item(&self.style, self.request.locale.as_ref(), self.cache, &item);
+        Some(self.finish_item(item, rendered))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.pending.size_hint()
+    }
+}
+
+impl<'a, T: EntryLike + Hash + PartialEq + Eq + Debug> ExactSizeIterator
+    for BibliographyStream<'a, T>
+{
+}
+
+/// Format a bibliography item with the style.
+fn render_bib_item<T: EntryLike + Hash + PartialEq + Eq + Debug>(
+    style: &StyleContext<'_>,
+    term_locale: Option<&LocaleCode>,
+    cache: Option<&RenderCache>,
+    item: &PendingItem<'_, T>,
+) -> ElemChildren {
+    match cache {
+        Some(cache) => {
+            cache.item(item, || render_uncached_bib_item(style, term_locale, item))
         }
+        None => render_uncached_bib_item(style, term_locale, item),
+    }
+}
+
+fn render_uncached_bib_item<T: EntryLike + Hash + PartialEq + Eq + Debug>(
+    style: &StyleContext<'_>,
+    term_locale: Option<&LocaleCode>,
+    item: &PendingItem<'_, T>,
+) -> ElemChildren {
+    simplify_children(
+        style
+            .bibliography(
+                item.entry,
+                CiteProperties {
+                    certain: item.certain,
+                    speculative: SpeculativeCiteProperties {
+                        locator: None,
+                        citation_number: item.citation_number,
+                        ibid: item.ibid,
+                        disambiguation: item.disambiguation.clone(),
+                    },
+                },
+                item.locale.as_ref(),
+                term_locale,
+            )
+            .unwrap(),
+    )
+}
+
+/// The title of the periodical that contains an article.
+fn periodical_title<T: EntryLike>(entry: &T) -> Option<String> {
+    [Kind::ArticleJournal, Kind::ArticleMagazine, Kind::ArticleNewspaper]
+        .into_iter()
+        .any(|kind| entry.matches_entry_type(kind))
+        .then(|| {
+            entry.resolve_standard_variable(
+                LongShortForm::default(),
+                StandardVariable::ContainerTitle,
+            )
+        })
+        .flatten()
+        .map(|title| title.to_string())
+}
+
+/// The persons of the first names element of an item, like its authors.
+fn first_persons<'a, T: EntryLike>(
+    item: &SpeculativeItemRender<'a, T>,
+) -> Vec<Cow<'a, Person>> {
+    item.first_name
+        .iter()
+        .flat_map(|props| props.variables())
+        .flat_map(|&variable| item.entry.resolve_name_variable(variable))
+        .collect()
+}
+
+/// Replace the names of a bibliography item with a substitute, like "———".
+/// The label of the names, like "ed.", is kept.
+fn substitute_names(children: &mut ElemChildren, substitute: &str) {
+    let Some(names) = children.get_meta_mut(ElemMeta::Names) else { return };
+    let label = names.children.get_meta(ElemMeta::Label).cloned();
+    let formatting = names
+        .children
+        .0
+        .iter()
+        .find_map(|c| match c {
+            ElemChild::Text(t) => Some(t.formatting),
+            _ => None,
+        })
+        .unwrap_or_default();
+
+    names.children = ElemChildren(
+        std::iter::once(ElemChild::Text(Formatted {
+            text: substitute.into(),
+            formatting,
+        }))
+        .chain(label.map(ElemChild::Elem))
+        .collect(),
+    );
+}
+
+/// The prefix of the author for a citation, followed by a space.
+fn cite_prefix<T: EntryLike>(
+    request: &CitationRequest<'_, T>,
+    formatting: Formatting,
+) -> Option<ElemChild> {
+    let prefix = request.prefix.as_deref()?.trim();
+    if prefix.is_empty() {
+        return None;
+    }
+
+    Some(affix_elem(format!("{prefix} "), formatting, ElemMeta::CitationPrefix))
+}
+
+/// The suffix of the author for a citation. It is separated from the cites
+/// by a comma unless it starts with punctuation itself.
+fn cite_suffix<T: EntryLike>(
+    request: &CitationRequest<'_, T>,
+    formatting: Formatting,
+) -> Option<ElemChild> {
+    let suffix = request.suffix.as_deref()?.trim();
+    if suffix.is_empty() {
+        return None;
+    }
+
+    let text = if suffix.starts_with([',', ';', ':', '.']) {
+        suffix.to_string()
+    } else {
+        format!(", {suffix}")
+    };
+
+    Some(affix_elem(text, formatting, ElemMeta::CitationSuffix))
+}
+
+/// The signal before a cite, followed by a space. It is in the language of
+/// the locale if hayagriva knows it and in English otherwise. It is
+/// capitalized if it starts the sentence of a note.
+fn signal_elem<T: EntryLike>(
+    signal: Signal,
+    first: bool,
+    request: &CitationRequest<'_, T>,
+    formatting: Formatting,
+) -> ElemChild {
+    let mut signal_formatting = formatting;
+    if request.options.italic_signals.contains(&signal) {
+        signal_formatting.font_style = FontStyle::Italic;
+    }
+
+    let locale = request
+        .locale
+        .clone()
+        .or_else(|| request.style.default_locale.clone())
+        .unwrap_or_else(LocaleCode::en_us);
+    let text = ExtraTerm::Signal(signal).localize(&locale).unwrap_or(signal.as_str());
+    let mut children = ElemChildren(vec![
+        ElemChild::Text(signal_formatting.add_text(text.into())),
+        ElemChild::Text(formatting.add_text(" ".into())),
+    ]);
+    if first && request.style.settings.class == StyleClass::Note {
+        capitalize_first(&mut children);
+    }
+
+    ElemChild::Elem(Elem {
+        children,
+        display: None,
+        meta: Some(ElemMeta::Signal),
+    })
+}
+
+fn affix_elem(text: String, formatting: Formatting, meta: ElemMeta) -> ElemChild {
+    ElemChild::Elem(Elem {
+        children: ElemChildren(vec![ElemChild::Text(Formatted { text, formatting })]),
+        display: None,
+        meta: Some(meta),
+    })
+}
+
+/// Make the full displayed URLs clickable and apply the link options.
+fn finish_links(children: &mut ElemChildren, options: &StyleOptions) {
+    absorb_link_prefixes(children);
+    if options.strip_link_scheme {
+        strip_link_schemes(children);
+    }
+    if options.strip_url_query || options.url_max_length.is_some() {
+        shorten_links(children, options.strip_url_query, options.url_max_length);
+    }
+    if options.link_breaks {
+        mark_link_breaks(children);
     }
 }
 
@@ -627,20 +1251,16 @@ fn date_replacement<T: EntryLike>(
         text: if let Some(date) = date {
             format!(
                 "{}{}",
-                if date.year > 0 { date.year } else { date.year.abs() + 1 },
-                if date.year < 1000 {
-                    if date.year < 0 {
-                        "BC"
-                    } else {
-                        "AD"
-                    }
-                } else {
-                    ""
+                date.era_year(),
+                match date.era() {
+                    _ if date.year >= 1000 => "",
+                    Era::BeforeCommonEra => "BC",
+                    Era::CommonEra => "AD",
                 }
             )
         } else if let Some(no_date) = ctx
             .ctx(entry, cite_props.clone(), locale, term_locale)
-            .term(Term::Other(OtherTerm::NoDate), TermForm::default(), false)
+            .term(Term::Other(OtherTerm::NoDate), TermForm::Short, false)
         {
             no_date.to_string()
         } else {
@@ -670,6 +1290,9 @@ fn last_purpose_render<T: EntryLike + Debug>(
 
 type AmbiguousGroup = Vec<(usize, usize)>;
 
+/// A person and its position in a list of names.
+type PositionedPerson<'a> = (usize, Cow<'a, Person>);
+
 /// Progressively transform names to disambiguate them.
 fn disambiguate_names<F, T>(
     renders: &[SpeculativeCiteRender<'_, '_, T>],
@@ -697,9 +1320,10 @@ fn disambiguate_names<F, T>(
 
         if let Some(name_props) = name_props_slot {
             let mut name_props = name_props.clone();
+            let rule = givenname_rule(renders[cite_idx].request);
             if name_props.disambiguate(
-                style.citation.disambiguate_add_givenname,
-                style.citation.givenname_disambiguation_rule,
+                rule.is_some(),
+                rule.unwrap_or(style.citation.givenname_disambiguation_rule),
                 style.citation.disambiguate_add_names,
             ) {
                 mark(item.entry, DisambiguateState::NameDisambiguation(name_props))
@@ -708,6 +1332,117 @@ fn disambiguate_names<F, T>(
     }
 }
 
+/// The rule for adding given names to the names of a citation, if the style
+/// or the options enable it.
+fn givenname_rule<T: EntryLike>(
+    request: &CitationRequest<'_, T>,
+) -> Option<DisambiguationRule> {
+    request.options.givenname_disambiguation.or_else(|| {
+        let citation = &request.style.citation;
+        citation
+            .disambiguate_add_givenname
+            .then_some(citation.givenname_disambiguation_rule)
+    })
+}
+
+/// Add given names to the names of different persons with the same family
+/// name in all citations, as the global given name disambiguation rules
+/// require. Unlike with `by-cite`, the citations do not need to be ambiguous.
+///
+/// The initials of a name are added if another person shares its family
+/// name, and the full given name if the other person also has the same
+/// initials and the rule allows it.
+fn disambiguate_given_names<T: EntryLike>(
+    renders: &mut [SpeculativeCiteRender<'_, '_, T>],
+) {
+    let global_rule = |request: &CitationRequest<'_, T>| {
+        givenname_rule(request).filter(|rule| *rule != DisambiguationRule::ByCite)
+    };
+
+    // The distinct persons with each family name.
+    let mut families: HashMap<(Option<String>, String), Vec<Person>> = HashMap::new();
+    for cite in renders.iter() {
+        let Some(rule) = global_rule(cite.request) else { continue };
+        for item in &cite.items {
+            let Some((_, persons)) = rendered_persons(item, rule) else { continue };
+            for (_, person) in persons {
+                let same_family = families
+                    .entry((person.prefix.clone(), person.name.clone()))
+                    .or_default();
+                if !same_family.iter().any(|p| p.given_name == person.given_name) {
+                    same_family.push(person.into_owned());
+                }
+            }
+        }
+    }
+
+    let initials = |person: &Person| {
+        let mut buf = String::new();
+        person.initials(&mut buf, None, false).unwrap();
+        buf
+    };
+
+    for cite in renders.iter_mut() {
+        let Some(rule) = global_rule(cite.request) else { continue };
+        let allow_full_first_name = rule.allows_full_first_names();
+        let style_ctx = cite.request.style();
+        for item in cite.items.iter_mut() {
+            let Some((variable, persons)) = rendered_persons(item, rule) else {
+                continue;
+            };
+            let mut expand = vec![];
+            let mut expand_further = vec![];
+            for (i, person) in persons {
+                let others: Vec<_> = families
+                    [&(person.prefix.clone(), person.name.clone())]
+                    .iter()
+                    .filter(|p| p.given_name != person.given_name)
+                    .collect();
+                if others.is_empty() {
+                    continue;
+                }
+
+                expand.push(i);
+                let own = initials(&person);
+                if others.iter().any(|p| initials(p) == own) {
+                    expand_further.push(i);
+                }
+            }
+
+            let mut props = item.first_name.clone().unwrap();
+            let mut change =
+                props.disambiguate_list(variable, &expand, allow_full_first_name);
+            change |=
+                props.disambiguate_list(variable, &expand_further, allow_full_first_name);
+
+            if change {
+                item.cite_props.speculative.disambiguation =
+                    DisambiguateState::NameDisambiguation(props);
+                item.rendered = do_rerender(&style_ctx, item, cite.request);
+            }
+        }
+    }
+}
+
+/// The persons whose names are rendered in the first name variable of an item
+/// and may be disambiguated by the rule, with their positions.
+fn rendered_persons<'a, T: EntryLike>(
+    item: &SpeculativeItemRender<'a, T>,
+    rule: DisambiguationRule,
+) -> Option<(NameVariable, Vec<PositionedPerson<'a>>)> {
+    let props = item.first_name.as_ref()?;
+    let variable = *props.variables().first()?;
+    let persons = item
+        .entry
+        .resolve_name_variable(variable)
+        .into_iter()
+        .enumerate()
+        .take(if rule.allows_multiple_names() { usize::MAX } else { 1 })
+        .filter(|(i, _)| props.is_visible(variable, *i))
+        .collect();
+    Some((variable, persons))
+}
+
 /// Mark qualifying entries for disambiguation with `cs:choose`.
 fn disambiguate_with_choose<F, T>(
     renders: &[SpeculativeCiteRender<'_, '_, T>],
@@ -771,14 +1506,15 @@ fn disambiguate_year_suffix<F, T>(
                 .speculative
                 .disambiguation
                 .may_disambiguate_with_year_suffix()
-                && !entries.contains(&item.entry)
+                && !entries.iter().any(|&(_, entry)| entry == item.entry)
             {
-                entries.push(item.entry);
+                entries.push((item.cite_props.speculative.citation_number, item.entry));
             }
         }
 
-        // Assign year suffixes.
-        for (i, entry) in entries.into_iter().enumerate() {
+        // Assign year suffixes in the order of the bibliography.
+        entries.sort_by_key(|&(number, _)| number);
+        for (i, (_, entry)) in entries.into_iter().enumerate() {
             mark(entry, DisambiguateState::YearSuffix(i as u8));
         }
     }
@@ -854,23 +1590,27 @@ fn collapse_items<'a, T: EntryLike>(cite: &mut SpeculativeCiteRender<'a, '_, T>)
         .as_deref()
         .or(style.citation.layout.delimiter.as_deref());
 
-    let group_delimiter = style.citation.cite_group_delimiter.as_deref();
+    let group_delimiter = style.citation.cite_group_delimiter.as_deref().or(Some(", "));
 
     match style.citation.collapse {
         Some(Collapse::CitationNumber) if style.settings.class == StyleClass::InText => {
             // Option with the start, end of the range and the next expected number.
             let mut range_start: Option<(usize, usize, usize)> = None;
 
+            // Ranges of three or more numbers are shown as their first and
+            // last number joined by an en dash, like "[2]–[5]".
             let end_range =
                 |items: &mut [SpeculativeItemRender<'a, T>],
                  range_start: &mut Option<(usize, usize, usize)>| {
                     if let &mut Some((start, end, _)) = range_start {
-                        if start < end {
-                            items[start].delim_override = after_collapse_delim;
-
-                            for item in &mut items[start + 1..=end] {
+                        if start + 1 < end {
+                            for item in &mut items[start + 1..end] {
                                 item.hidden = true;
                             }
+                            items[end].delim_override = Some("–");
+                            if let Some(next) = items.get_mut(end + 1) {
+                                next.delim_override = after_collapse_delim;
+                            }
                         }
                     }
 
@@ -879,6 +1619,7 @@ fn collapse_items<'a, T: EntryLike>(cite: &mut SpeculativeCiteRender<'a, '_, T>)
 
             for i in 0..cite.items.len() {
                 if cite.items[i].hidden
+                    || cite.items[i].cite_props.speculative.locator.is_some()
                     || cite.items[i].rendered.get_meta(ElemMeta::CitationNumber).is_none()
                 {
                     end_range(&mut cite.items, &mut range_start);
@@ -889,7 +1630,8 @@ fn collapse_items<'a, T: EntryLike>(cite: &mut SpeculativeCiteRender<'a, '_, T>)
                     Some((start, end, next))
                         if end + 1 == i
                             && cite.items[i].cite_props.speculative.citation_number
-                                == next =>
+                                == next
+                            && cite.items[i].signal == cite.items[end].signal =>
                     {
                         // Extend the range.
                         range_start = Some((start, i, next + 1));
@@ -904,6 +1646,8 @@ fn collapse_items<'a, T: EntryLike>(cite: &mut SpeculativeCiteRender<'a, '_, T>)
                     }
                 }
             }
+
+            end_range(&mut cite.items, &mut range_start);
         }
         Some(Collapse::CitationNumber) => {}
         Some(Collapse::Year | Collapse::YearSuffix | Collapse::YearSuffixRanged) => {
@@ -919,7 +1663,7 @@ fn collapse_items<'a, T: EntryLike>(cite: &mut SpeculativeCiteRender<'a, '_, T>)
                         cite.items[i].collapse_verdict = Some(CollapseVerdict::First);
                     }
                     // This is a different group.
-                    Some((i, _)) => {
+                    Some(_) => {
                         cite.items[i].delim_override = after_collapse_delim;
                         group_idx = cite.items[i].group_idx.map(|idx| (i, idx));
                     }
@@ -953,6 +1697,9 @@ pub struct Rendered {
     pub bibliography: Option<RenderedBibliography>,
     /// The citation items.
     pub citations: Vec<RenderedCitation>,
+    /// The characters that remain in the output without an ASCII
+    /// transliteration although [`StyleOptions::ascii_only`] is set.
+    pub non_ascii: Vec<char>,
 }
 
 /// A fully rendered bibliography.
@@ -973,17 +1720,198 @@ pub struct RenderedBibliography {
     pub items: Vec<BibliographyItem>,
 }
 
+impl RenderedBibliography {
+    /// How the [prefixes](BibliographyItem::prefix) and
+    /// [bodies](BibliographyItem::body) of the items are laid out.
+    pub fn layout(&self) -> ItemLayout {
+        match self.second_field_align {
+            Some(align) => ItemLayout::Aligned(align),
+            None if self.hanging_indent => ItemLayout::HangingIndent,
+            None => ItemLayout::Block,
+        }
+    }
+
+    /// The plain text of the items grouped by the year of their entries in
+    /// the library, like [`Library::grouped`] with [`GroupBy::Year`]: the most
+    /// recent year comes first and the items without a date come last, in
+    /// the group `None`. Within a group, the items keep the order of the
+    /// bibliography.
+    pub fn grouped_by_year(&self, library: &Library) -> Vec<(Option<i32>, Vec<String>)> {
+        self.grouped(library, &GroupBy::Year, |entry| entry.date_any().map(|d| d.year))
+    }
+
+    /// The plain text of the items grouped by the type of their entries in
+    /// the library, in the order of [`EntryType`]. Within a group, the items
+    /// keep the order of the bibliography.
+    pub fn grouped_by_type(&self, library: &Library) -> Vec<(EntryType, Vec<String>)> {
+        let by_type =
+            GroupBy::Custom(Box::new(|entry| Some(format!("{:?}", entry.entry_type()))));
+        let mut groups = self.grouped(library, &by_type, |entry| *entry.entry_type());
+        groups.sort_by_key(|&(entry_type, _)| entry_type);
+        groups
+    }
+
+    /// Group the items with [`Library::grouped`] and name each group by the
+    /// key of its first entry. Groups without items are left out.
+    fn grouped<K>(
+        &self,
+        library: &Library,
+        group_by: &GroupBy,
+        key: impl Fn(&Entry) -> K,
+    ) -> Vec<(K, Vec<String>)> {
+        let positions: HashMap<&str, usize> = self
+            .items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| (item.key.as_str(), i))
+            .collect();
+        library
+            .grouped(group_by, "")
+            .into_iter()
+            .filter_map(|group| {
+                let mut indices: Vec<usize> = group
+                    .entries
+                    .iter()
+                    .filter_map(|entry| positions.get(entry.key()).copied())
+                    .collect();
+                indices.sort_unstable();
+                let first = library.get(&self.items[*indices.first()?].key)?;
+                let items = indices
+                    .into_iter()
+                    .map(|i| format!("{:#}", self.items[i].content))
+                    .collect();
+                Some((key(first), items))
+            })
+            .collect()
+    }
+
+    /// The indices of the items for which the style rendered no text.
+    ///
+    /// This happens when an entry lacks all variables the style uses for its
+    /// type, for example a standalone web item in a style that only prints
+    /// the container of an entry. Use it to find entries that need more data.
+    pub fn entries_with_empty_source(&self) -> Vec<usize> {
+        self.items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| !item.content.has_content())
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// The year suffix of each item by its index, like `a` for "2020a".
+    ///
+    /// Items that did not need to be disambiguated by a year suffix map to an
+    /// empty string.
+    pub fn disambiguation_map(&self) -> BTreeMap<usize, String> {
+        self.items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| (i, item.year_suffix.clone()))
+            .collect()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct BibliographyItem {
     pub key: String,
     pub first_field: Option<ElemChild>,
     pub content: ElemChildren,
+    /// The annotation paragraph that follows the item in an annotated
+    /// bibliography. It is only set if [`StyleOptions::annotations`] is
+    /// enabled and the entry has a note or an abstract.
+    pub annotation: Option<Elem>,
+    /// The letter that distinguishes the item from other works of the same
+    /// authors in the same year, like `a` in "2020a". It is empty if the item
+    /// needed no such disambiguation.
+    pub year_suffix: String,
 }
 
 impl BibliographyItem {
-    fn new(key: String, first_field: Option<ElemChild>, content: ElemChildren) -> Self {
-        Self { key, first_field, content }
+    fn new(
+        key: String,
+        first_field: Option<ElemChild>,
+        content: ElemChildren,
+        annotation: Option<Elem>,
+        year_suffix: String,
+    ) -> Self {
+        Self { key, first_field, content, annotation, year_suffix }
     }
+
+    /// The label of the item, like `[12]` in a numeric style. It is empty if
+    /// the style does not align the bodies of the items in a second column.
+    pub fn prefix(&self) -> ElemChildren {
+        ElemChildren(self.first_field.iter().cloned().collect())
+    }
+
+    /// The reference without its label.
+    pub fn body(&self) -> &ElemChildren {
+        &self.content
+    }
+}
+
+/// The layout of the items in a bibliography.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ItemLayout {
+    /// The prefix is a label and the bodies of all items are aligned in a
+    /// second column. With [`SecondFieldAlign::Margin`], the labels are put
+    /// into the margin. Otherwise, they are flush with the text.
+    Aligned(SecondFieldAlign),
+    /// The prefix is empty and all lines of the body except for the first are
+    /// indented.
+    HangingIndent,
+    /// The prefix is empty and the body is a regular paragraph.
+    Block,
+}
+
+/// The note or abstract of an entry as an indented paragraph. The text is
+/// used as it is, without any case conversion.
+fn annotation<T: EntryLike>(entry: &T) -> Option<Elem> {
+    let text = entry
+        .resolve_standard_variable(LongShortForm::Long, StandardVariable::Note)
+        .or_else(|| {
+            entry.resolve_standard_variable(
+                LongShortForm::Long,
+                StandardVariable::Abstract,
+            )
+        })?
+        .to_string();
+
+    Some(Elem {
+        children: ElemChildren(vec![ElemChild::Text(Formatted {
+            text,
+            formatting: Formatting::default(),
+        })]),
+        display: Some(Display::Indent),
+        meta: None,
+    })
+}
+
+/// End the bibliography item with a sentence that says how often the entry
+/// was cited, in the language of the locale. Items that end in a link or
+/// without a full stop get one before the sentence.
+fn append_citation_count(children: &mut ElemChildren, count: u32, locale: &LocaleCode) {
+    fn ends_with_link(children: &[ElemChild]) -> bool {
+        match children.last() {
+            Some(ElemChild::Link { .. }) => true,
+            Some(ElemChild::Elem(e)) => ends_with_link(&e.children.0),
+            _ => false,
+        }
+    }
+
+    let term = if count == 1 { ExtraTerm::CitedOnce } else { ExtraTerm::CitedTimes };
+    let Some(sentence) = term.localize(locale) else { return };
+    let text = format!("{children:#}");
+    let delimiter =
+        if ends_with_link(&children.0) || !text.trim_end().ends_with(['.', '!', '?']) {
+            ". "
+        } else {
+            " "
+        };
+    children.0.push(ElemChild::Text(Formatted {
+        text: format!("{delimiter}{}", sentence.replace("{}", &count.to_string())),
+        formatting: Formatting::default(),
+    }));
 }
 
 /// A fully rendered citation.
@@ -1054,6 +1982,8 @@ pub(crate) struct StyleContext<'a> {
     locale_files: &'a [Locale],
     /// Which locale we're using.
     locale_override: Option<LocaleCode>,
+    /// Overrides for the punctuation of the style.
+    options: StyleOptions,
 }
 
 impl<'a> StyleContext<'a> {
@@ -1061,8 +1991,14 @@ impl<'a> StyleContext<'a> {
         style: &'a IndependentStyle,
         locale: Option<LocaleCode>,
         locale_files: &'a [Locale],
+        options: StyleOptions,
     ) -> Self {
-        Self { csl: style, locale_files, locale_override: locale }
+        Self {
+            csl: style,
+            locale_files,
+            locale_override: locale,
+            options,
+        }
     }
 
     fn ctx<'b, T: EntryLike>(
@@ -1082,7 +2018,10 @@ impl<'a> StyleContext<'a> {
                 None,
             ),
             style: self,
-            writing: WritingContext::new(self.csl.settings.options.clone()),
+            writing: WritingContext::new(
+                self.csl.settings.options.clone(),
+                self.options.sentence_case_exceptions.clone(),
+            ),
         }
     }
 
@@ -1103,7 +2042,10 @@ impl<'a> StyleContext<'a> {
                 None,
             ),
             style: self,
-            writing: WritingContext::new(self.csl.settings.options.clone()),
+            writing: WritingContext::new(
+                self.csl.settings.options.clone(),
+                self.options.sentence_case_exceptions.clone(),
+            ),
         }
     }
 
@@ -1248,14 +2190,7 @@ impl<'a> StyleContext<'a> {
                     ctx.ensure_space();
                 }
 
-                if self.csl.info.category.iter().any(|c| {
-                    matches!(
-                        c,
-                        StyleCategory::CitationFormat {
-                            format: CitationFormat::Label | CitationFormat::Numeric
-                        }
-                    )
-                }) {
+                if self.is_numeric_or_label() {
                     // Print the label.
                     if let Some(prefix) = self.csl.citation.layout.prefix.as_ref() {
                         ctx.push_str(prefix);
@@ -1278,6 +2213,14 @@ impl<'a> StyleContext<'a> {
                     );
                 }
             }
+            Some(CitePurpose::Year) | Some(CitePurpose::Full) | None
+                if ctx.instance.cite_props.certain.suppress_author
+                    && !self.is_numeric_or_label() =>
+            {
+                ctx.set_special_form(Some(SpecialForm::SuppressAuthor));
+                do_regular(&mut ctx);
+                ctx.set_special_form(None);
+            }
             Some(CitePurpose::Year) | Some(CitePurpose::Full) | None => {
                 do_regular(&mut ctx);
             }
@@ -1300,6 +2243,19 @@ impl<'a> StyleContext<'a> {
         Some(ctx)
     }
 
+    /// Whether the style cites entries by a number or a label instead of by
+    /// their authors.
+    fn is_numeric_or_label(&self) -> bool {
+        self.csl.info.category.iter().any(|c| {
+            matches!(
+                c,
+                StyleCategory::CitationFormat {
+                    format: CitationFormat::Label | CitationFormat::Numeric
+                }
+            )
+        })
+    }
+
     /// Return the locale to use for this style.
     fn locale(&self) -> LocaleCode {
         self.locale_override
@@ -1310,7 +2266,7 @@ impl<'a> StyleContext<'a> {
 }
 
 /// A citation request. A citation can contain references to multiple items.
-#[derive(Debug, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct CitationRequest<'a, T: EntryLike> {
     /// The items to format.
     pub items: Vec<CitationItem<'a, T>>,
@@ -1329,6 +2285,16 @@ pub struct CitationRequest<'a, T: EntryLike> {
     /// `near-note` will always test false if this is none for the referenced
     /// note.
     note_number: Option<usize>,
+    /// Overrides for the punctuation of the style.
+    options: StyleOptions,
+    /// Text that is printed before the cites within the affixes of the style,
+    /// like "see" in "(see Smith, 2020)".
+    pub prefix: Option<String>,
+    /// Text that is printed after the cites and their locators within the
+    /// affixes of the style, like "for a review" in "(Smith, 2020, for a
+    /// review)". A comma separates it from the cites unless it starts with a
+    /// punctuation mark.
+    pub suffix: Option<String>,
 }
 
 impl<'a, T: EntryLike> CitationRequest<'a, T> {
@@ -1346,9 +2312,30 @@ impl<'a, T: EntryLike> CitationRequest<'a, T> {
             locale,
             locale_files,
             note_number: note_number.filter(|_| style.settings.class == StyleClass::Note),
+            options: StyleOptions::default(),
+            prefix: None,
+            suffix: None,
         }
     }
 
+    /// Override the punctuation of the style for this citation.
+    pub fn with_options(mut self, options: StyleOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Print text before the cites, like "see" or "e.g.,".
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = Some(prefix.into());
+        self
+    }
+
+    /// Print text after the cites, like "for a review".
+    pub fn with_suffix(mut self, suffix: impl Into<String>) -> Self {
+        self.suffix = Some(suffix.into());
+        self
+    }
+
     /// Create a new citation request without a note number.
     pub fn from_items(
         items: Vec<CitationItem<'a, T>>,
@@ -1359,14 +2346,19 @@ impl<'a, T: EntryLike> CitationRequest<'a, T> {
     }
 
     fn style(&self) -> StyleContext<'a> {
-        StyleContext::new(self.style, self.locale.clone(), self.locale_files)
+        StyleContext::new(
+            self.style,
+            self.locale.clone(),
+            self.locale_files,
+            self.options.clone(),
+        )
     }
 
     fn shall_affix(&self) -> bool {
         self.items.iter().all(|p| p.purpose.is_none())
     }
 
-    fn prefix(&self) -> Option<&'a str> {
+    fn layout_prefix(&self) -> Option<&'a str> {
         self.style
             .citation
             .layout
@@ -1375,7 +2367,7 @@ impl<'a, T: EntryLike> CitationRequest<'a, T> {
             .filter(|_| self.shall_affix())
     }
 
-    fn suffix(&self) -> Option<&'a str> {
+    fn layout_suffix(&self) -> Option<&'a str> {
         self.style
             .citation
             .layout
@@ -1400,6 +2392,8 @@ pub struct BibliographyRequest<'a> {
     /// The files used to retrieve locale settings and terms if the style does
     /// not define all neccessary items.
     pub locale_files: &'a [Locale],
+    /// Overrides for the punctuation of the style in the bibliography.
+    options: StyleOptions,
 }
 
 impl<'a> BibliographyRequest<'a> {
@@ -1409,11 +2403,27 @@ impl<'a> BibliographyRequest<'a> {
         locale: Option<LocaleCode>,
         locale_files: &'a [Locale],
     ) -> Self {
-        Self { style, locale, locale_files }
+        Self {
+            style,
+            locale,
+            locale_files,
+            options: StyleOptions::default(),
+        }
+    }
+
+    /// Override the punctuation of the style for the bibliography.
+    pub fn with_options(mut self, options: StyleOptions) -> Self {
+        self.options = options;
+        self
     }
 
     fn style(&self) -> StyleContext<'a> {
-        StyleContext::new(self.style, self.locale.clone(), self.locale_files)
+        StyleContext::new(
+            self.style,
+            self.locale.clone(),
+            self.locale_files,
+            self.options.clone(),
+        )
     }
 }
 
@@ -1432,6 +2442,12 @@ pub struct CitationItem<'a, T: EntryLike> {
     pub hidden: bool,
     /// Format the item in a special way.
     pub purpose: Option<CitePurpose>,
+    /// Whether to omit the author because the text already names them, like
+    /// "Smith argued this earlier (2020, p. 4)". Numeric styles ignore this.
+    pub suppress_author: bool,
+    /// The signal that introduces the item, like "see" or "cf.". The items of
+    /// a citation are grouped by their signal in the conventional order.
+    pub signal: Option<Signal>,
     /// The initial index of this item in the list of items.
     initial_idx: usize,
 }
@@ -1445,6 +2461,8 @@ impl<'a, T: EntryLike> CitationItem<'a, T> {
             locale: None,
             hidden: false,
             purpose: None,
+            suppress_author: false,
+            signal: None,
             initial_idx: 0,
         }
     }
@@ -1457,6 +2475,8 @@ impl<'a, T: EntryLike> CitationItem<'a, T> {
             locale: None,
             hidden: false,
             purpose: None,
+            suppress_author: false,
+            signal: None,
             initial_idx: 0,
         }
     }
@@ -1475,6 +2495,8 @@ impl<'a, T: EntryLike> CitationItem<'a, T> {
             locale,
             hidden,
             purpose,
+            suppress_author: false,
+            signal: None,
             initial_idx: 0,
         }
     }
@@ -1484,6 +2506,18 @@ impl<'a, T: EntryLike> CitationItem<'a, T> {
         self.purpose = Some(purpose);
         self
     }
+
+    /// Omit the author of the item from the citation.
+    pub fn suppress_author(mut self) -> Self {
+        self.suppress_author = true;
+        self
+    }
+
+    /// Introduce the item with a signal.
+    pub fn signal(mut self, signal: Signal) -> Self {
+        self.signal = Some(signal);
+        self
+    }
 }
 
 impl<'a> StyleContext<'a> {
@@ -1618,9 +2652,12 @@ impl Default for WritingContext {
 }
 
 impl WritingContext {
-    fn new(options: InheritableNameOptions) -> Self {
+    fn new(options: InheritableNameOptions, exceptions: CaseExceptions) -> Self {
+        let mut buf = CaseFolder::default();
+        buf.set_exceptions(exceptions);
         Self {
             name_options: NonEmptyStack::new(options),
+            buf,
             ..Self::default()
         }
     }
@@ -1660,6 +2697,13 @@ impl WritingContext {
             return;
         }
 
+        let text = mem::take(&mut self.buf).finish();
+        self.append_text(text);
+    }
+
+    /// Add text to the last child if the formats match or as a new child
+    /// otherwise.
+    fn append_text(&mut self, text: String) {
         let format = *self.formatting();
 
         // Append to last child if formats match.
@@ -1671,15 +2715,36 @@ impl WritingContext {
             }
         }) {
             if format == child.formatting {
-                child.text.push_str(&mem::take(&mut self.buf).finish());
+                child.text.push_str(&text);
                 return;
             }
         }
 
-        let formatted = format.add_text(mem::take(&mut self.buf).finish());
+        let formatted = format.add_text(text);
         self.elem_stack.last_mut().0.push(ElemChild::Text(formatted))
     }
 
+    /// Add a verbatim chunk as an element of its own, so that the finishing
+    /// passes can leave it alone. The text after it is transformed as if the
+    /// chunk had been pushed to the buffer.
+    fn push_verbatim_elem(&mut self, value: &str) {
+        let text = self.buf.take_before_verbatim();
+        if !text.is_empty() {
+            self.append_text(text);
+        }
+
+        let format = *self.formatting();
+        let children = ElemChildren(vec![ElemChild::Text(format.add_text(value.into()))]);
+        self.elem_stack.last_mut().0.push(
+            Elem {
+                children,
+                display: None,
+                meta: Some(ElemMeta::Verbatim),
+            }
+            .into(),
+        );
+    }
+
     /// Add another subtree to the children element. This must be done to
     /// include a new element or to check that the subtree is empty.
     fn push_elem(&mut self, format: citationberg::Formatting) -> DisplayLoc {
@@ -1906,6 +2971,24 @@ impl WritingContext {
                 .sum::<usize>()
     }
 
+    /// Whether text written next starts a sentence because nothing or only a
+    /// sentence-ending punctuation mark has been written before it.
+    fn at_sentence_start(&self) -> bool {
+        let last = if self.buf.has_content() {
+            Some(self.buf.as_str())
+        } else {
+            self.elem_stack
+                .iter()
+                .rev()
+                .find(|e| e.has_content())
+                .map(|e| e.last_text().map_or("", |t| t.text.as_str()))
+        };
+
+        let Some(text) = last else { return true };
+        let text = text.trim_end();
+        text.is_empty() || text.ends_with(['.', '!', '?'])
+    }
+
     /// Check if the last subtree is empty.
     fn last_is_empty(&self) -> bool {
         !self.buf.has_content() && !self.elem_stack.last().has_content()
@@ -1973,6 +3056,12 @@ struct CertainCiteProperties {
     pub is_first: bool,
     /// The index of the item in the original citation request.
     pub initial_idx: usize,
+    /// Whether the author of the item is omitted from the citation. This is
+    /// only set for the final render.
+    pub suppress_author: bool,
+    /// Whether the previous bibliography entry appeared in the same
+    /// periodical, so that its title is replaced by a marker.
+    pub repeated_container: bool,
 }
 
 impl CertainCiteProperties {
@@ -2048,6 +3137,15 @@ impl DisambiguateState {
         matches!(self, Self::None | Self::NameDisambiguation(_) | Self::Choose)
     }
 
+    /// The state for rendering the bibliography, which is not affected by
+    /// name disambiguation.
+    fn for_bibliography(&self) -> Self {
+        match self {
+            Self::NameDisambiguation(_) => Self::None,
+            other => other.clone(),
+        }
+    }
+
     /// Return the more advanced disambiguation state between the two.
     fn max(self, other: Self) -> Self {
         match (self, other) {
@@ -2077,26 +3175,31 @@ pub enum IbidState {
 }
 
 impl IbidState {
-    fn is_ibid_with_locator(self) -> bool {
+    /// Whether the cite refers to the same item as the preceding cite. The
+    /// `ibid` position also applies to cites with a different locator.
+    fn is_ibid(self) -> bool {
         matches!(self, Self::IbidWithLocator | Self::Ibid)
     }
 
+    /// Determine the state of a cite from the cite that immediately precedes
+    /// it. That is the previous cite in the same citation or, for the first
+    /// cite of a citation, the only cite of the previous citation.
+    ///
+    /// A cite without a locator that follows a cite with a locator is not
+    /// ibid, since "ibid." would refer to the locator of the previous cite.
     fn with_last<T>(this: &CitationItem<T>, last: Option<&CitationItem<T>>) -> Self
     where
         T: EntryLike + PartialEq,
     {
-        if let Some(last) = last {
-            if last.entry == this.entry && !last.hidden {
-                if last.locator == this.locator {
-                    IbidState::Ibid
-                } else {
-                    IbidState::IbidWithLocator
-                }
-            } else {
-                IbidState::Different
-            }
-        } else {
-            IbidState::Different
+        let Some(last) = last.filter(|last| last.entry == this.entry && !last.hidden)
+        else {
+            return IbidState::Different;
+        };
+
+        match (last.locator, this.locator) {
+            (Some(_), None) => IbidState::Different,
+            (last, this) if last == this => IbidState::Ibid,
+            _ => IbidState::IbidWithLocator,
         }
     }
 }
@@ -2106,6 +3209,20 @@ impl IbidState {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct SpecificLocator<'a>(pub Locator, pub LocatorPayload<'a>);
 
+impl<'a> SpecificLocator<'a> {
+    /// Create a locator of some type, like a page or a paragraph, with a value
+    /// like `"42"` or `"3-5"`.
+    pub fn new(locator: Locator, value: &'a str) -> Self {
+        Self(locator, LocatorPayload::Str(value))
+    }
+
+    /// Create a locator with a label that is printed verbatim instead of a
+    /// term of the locale, like "box 3" for archival material.
+    pub fn custom(label: &'a str, value: &'a str) -> Self {
+        Self(Locator::Custom, LocatorPayload::Labeled { label, value })
+    }
+}
+
 /// The type of content a `cs:text` element should yield for a locator.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum LocatorPayload<'a> {
@@ -2114,6 +3231,14 @@ pub enum LocatorPayload<'a> {
     /// An element with the original index of the locator will be yielded. The
     /// consumer can then recognize this and replace it with their own content.
     Transparent,
+    /// Print the value after a label that replaces the term for the type of
+    /// the locator.
+    Labeled {
+        /// The label, like "box".
+        label: &'a str,
+        /// The value, like "3".
+        value: &'a str,
+    },
 }
 
 impl<'a, T: EntryLike> Context<'a, T> {
@@ -2199,7 +3324,10 @@ impl<'a, T: EntryLike> Context<'a, T> {
 
     /// Pull punctuation into a quote if applicable
     fn do_pull_punctuation<'s>(&mut self, mut s: &'s str) -> &'s str {
-        if self.writing.pull_punctuation && s.starts_with(['.', ',', ';', '!', '?']) {
+        let pull = self.writing.pull_punctuation;
+        if (pull || self.style.punctuation_in_quotes())
+            && s.starts_with(['.', ',', ';', '!', '?'])
+        {
             let close_quote =
                 self.term(OtherTerm::CloseQuote.into(), TermForm::default(), false);
             let close_inner_quote =
@@ -2207,14 +3335,16 @@ impl<'a, T: EntryLike> Context<'a, T> {
 
             let mut used_buf = false;
             let buf = if self.writing.buf.is_empty() {
+                // The quote may close a nested element, like a title in a
+                // substitute.
                 match self
                     .writing
                     .elem_stack
                     .last_mut_predicate(|p| !p.is_empty())
-                    .and_then(|p| p.0.last_mut())
+                    .and_then(|p| p.last_text_mut())
                 {
-                    Some(ElemChild::Text(f)) => &mut f.text,
-                    _ => {
+                    Some(f) => &mut f.text,
+                    None => {
                         used_buf = true;
                         self.writing.buf.as_string_mut()
                     }
@@ -2228,10 +3358,21 @@ impl<'a, T: EntryLike> Context<'a, T> {
                 // Check if buf ends with a close quote.
                 // If it does, replace it with the punctuation.
                 if let Some(head) = buf.strip_suffix(quote) {
-                    buf.truncate(head.len());
-
+                    // A quote that already ends with punctuation swallows
+                    // a period or comma, like in “Why?” or “Title.”
                     let punctuation = s.chars().next().unwrap();
+                    let swallow = head.ends_with(['.', ',', '!', '?'])
+                        && matches!(punctuation, '.' | ',');
+                    if !pull && !swallow {
+                        break;
+                    }
+
                     s = &s[punctuation.len_utf8()..];
+                    if swallow {
+                        break;
+                    }
+
+                    buf.truncate(head.len());
                     buf.push(punctuation);
                     buf.push_str(quote);
 
@@ -2251,9 +3392,14 @@ impl<'a, T: EntryLike> Context<'a, T> {
     fn push_str(&mut self, s: &str) {
         let s = self.do_pull_punctuation(s);
 
-        self.writing.buf.reconfigure(
-            (*self.writing.cases.last()).map(Into::into).unwrap_or_default(),
-        );
+        let case = match *self.writing.cases.last() {
+            Some(TextCase::SentenceCase) => match self.style.options.sentence_case {
+                Some(config) => Case::Sentence(config),
+                None => TextCase::SentenceCase.into(),
+            },
+            case => case.map(Into::into).unwrap_or_default(),
+        };
+        self.writing.buf.reconfigure(case);
 
         fn last_buffer(ctx: &mut WritingContext) -> Option<&mut String> {
             let last = ctx
@@ -2273,10 +3419,21 @@ impl<'a, T: EntryLike> Context<'a, T> {
             s.chars().next_back().map_or(false, |c| c.is_whitespace())
         });
 
+        // Semicolons only come from the delimiter options, so styles keep
+        // the spaces that they put before their own semicolons.
+        let options = &self.style.options;
+        let eats_semicolon =
+            options.name_delimiter.is_some() || options.component_delimiter.is_some();
+
         // Punctuation eats spaces. Whitespace should be trimmed.
         if ends_with_space
             && s.chars().next().map_or(false, |c| {
-                c.is_whitespace() || c == '.' || c == ',' || c == ']' || c == ')'
+                c.is_whitespace()
+                    || c == '.'
+                    || c == ','
+                    || (c == ';' && eats_semicolon)
+                    || c == ']'
+                    || c == ')'
             })
         {
             if let Some(buf) = last_buffer(&mut self.writing) {
@@ -2317,6 +3474,10 @@ impl<'a, T: EntryLike> Context<'a, T> {
         for chunk in &chunked.0 {
             match chunk.kind {
                 ChunkKind::Normal => self.push_str(&chunk.value),
+                ChunkKind::Verbatim if self.style.options.tidy_punctuation => {
+                    self.writing.push_verbatim_elem(&chunk.value);
+                    self.writing.pull_punctuation = false;
+                }
                 ChunkKind::Verbatim => {
                     self.writing.buf.push_verbatim(&chunk.value);
                     self.writing.pull_punctuation = false;
@@ -2368,8 +3529,8 @@ impl<'a, T: EntryLike> Context<'a, T> {
             }
         }
 
-        let mut form = Some(form);
-        while let Some(current_form) = form {
+        let mut current = Some(form);
+        while let Some(current_form) = current {
             if let Some(localization) = self.style.lookup_locale(|l| {
                 let term = l.term(term, current_form)?;
                 Some(if plural { term.multiple() } else { term.single() })
@@ -2377,7 +3538,17 @@ impl<'a, T: EntryLike> Context<'a, T> {
                 return localization;
             }
 
-            form = current_form.fallback();
+            current = current_form.fallback();
+        }
+
+        // Locales without a term for report and patent numbers abbreviate
+        // them like issue numbers ("No.", "Nr.", "nᵒ").
+        if term == Term::NumberVariable(csl_taxonomy::NumberVariable::Number) {
+            return self.term(
+                Term::NumberVariable(csl_taxonomy::NumberVariable::Issue),
+                form,
+                plural,
+            );
         }
 
         None
@@ -2457,7 +3628,29 @@ impl<'a, T: EntryLike> Context<'a, T> {
     ) -> Option<NumberVariableResult<'a>> {
         self.writing.usage_info.borrow_mut().last_mut().has_vars = true;
         self.writing.prepare_variable_query(variable)?;
-        let res = self.instance.resolve_number_variable(variable);
+        let mut res = self.instance.resolve_number_variable(variable);
+
+        // The number is printed as the publication number instead.
+        if variable == csl_taxonomy::NumberVariable::Number && self.is_database_thesis() {
+            res = None;
+        }
+
+        if let (
+            Some(NumberVariableResult::Regular(MaybeTyped::String(timestamp))),
+            Some(SpecificLocator(Locator::Timestamp, _)),
+        ) = (&mut res, self.instance.cite_props.speculative.locator)
+        {
+            if variable == csl_taxonomy::NumberVariable::Locator {
+                let delimiter = self
+                    .term(
+                        OtherTerm::PageRangeDelimiter.into(),
+                        TermForm::default(),
+                        false,
+                    )
+                    .unwrap_or("–");
+                *timestamp = taxonomy::timestamp_range(timestamp, delimiter);
+            }
+        }
 
         if res.is_some() {
             self.writing.usage_info.borrow_mut().last_mut().has_non_empty_vars = true;
@@ -2473,23 +3666,344 @@ impl<'a, T: EntryLike> Context<'a, T> {
         form: LongShortForm,
         variable: csl_taxonomy::StandardVariable,
     ) -> Option<Cow<'a, ChunkedString>> {
-        self.writing.usage_info.borrow_mut().last_mut().has_vars = true;
+        if !is_disambiguating(variable) {
+            self.writing.usage_info.borrow_mut().last_mut().has_vars = true;
+        }
         self.writing.prepare_variable_query(variable)?;
-        let res = self.instance.resolve_standard_variable(form, variable);
+        let res = match (variable, self.style.options.publisher_location) {
+            (StandardVariable::PublisherPlace, Some(_)) => None,
+            (StandardVariable::Publisher, Some(policy)) => {
+                self.resolve_publisher(form, policy)
+            }
+            (StandardVariable::Publisher, None) => self.resolve_own_publisher(form),
+            // APA describes submitted manuscripts in brackets after the title.
+            (StandardVariable::Genre, _) => self
+                .instance
+                .resolve_standard_variable(form, variable)
+                .or_else(|| self.resolve_submitted_genre()),
+            (StandardVariable::ArchiveLocation, _) if self.is_database_thesis() => {
+                self.resolve_publication_number()
+            }
+            (StandardVariable::ContainerTitle, _)
+                if self.instance.cite_props.certain.repeated_container =>
+            {
+                self.style
+                    .options
+                    .repeated_container_marker
+                    .as_deref()
+                    .map(|marker| Cow::Owned(StringChunk::verbatim(marker).into()))
+            }
+            (StandardVariable::ContainerTitle, _) => self
+                .instance
+                .resolve_standard_variable(form, variable)
+                .or_else(|| self.resolve_proceedings_title(form)),
+            (StandardVariable::Title, _)
+                if form == LongShortForm::Short
+                    && self.style.options.derive_short_titles =>
+            {
+                self.resolve_localized_title().or_else(|| self.resolve_short_title())
+            }
+            (StandardVariable::TitleShort, _)
+                if self.style.options.derive_short_titles =>
+            {
+                self.resolve_short_title()
+            }
+            (StandardVariable::Title, _) => {
+                self.resolve_localized_title().or_else(|| {
+                    self.instance.entry.resolve_title(self.style.options.title_separator)
+                })
+            }
+            _ => self.instance.resolve_standard_variable(form, variable),
+        };
 
         if res.is_some() {
-            self.writing.usage_info.borrow_mut().last_mut().has_non_empty_vars = true;
+            let mut info = self.writing.usage_info.borrow_mut();
+            info.last_mut().has_vars = true;
+            info.last_mut().has_non_empty_vars = true;
         }
         res
     }
 
+    /// Resolve the publisher together with its location as prescribed by the
+    /// publisher location policy of the style options.
+    fn resolve_publisher(
+        &self,
+        form: LongShortForm,
+        policy: PublisherLocation,
+    ) -> Option<Cow<'a, ChunkedString>> {
+        let publisher = self.resolve_own_publisher(form);
+        let location = match policy {
+            PublisherLocation::Never => None,
+            _ => self.instance.entry.resolve_location(form),
+        };
+
+        match (location, publisher) {
+            (Some(location), Some(publisher)) => {
+                let mut joined = location.into_owned();
+                joined.push_str(": ", ChunkKind::Normal);
+                joined.0.extend(publisher.into_owned().0);
+                Some(Cow::Owned(joined))
+            }
+            (location, None) if policy == PublisherLocation::Always => location,
+            (_, publisher) => publisher,
+        }
+    }
+
+    /// Resolve the publisher of the item. The institution of a thesis takes
+    /// its place if the style options print theses like APA.
+    fn resolve_own_publisher(
+        &self,
+        form: LongShortForm,
+    ) -> Option<Cow<'a, ChunkedString>> {
+        self.instance
+            .resolve_standard_variable(form, StandardVariable::Publisher)
+            .or_else(|| {
+                let thesis = self.style.options.thesis_publication_numbers
+                    && self.instance.entry.matches_entry_type(Kind::Thesis);
+                thesis
+                    .then(|| {
+                        self.instance
+                            .resolve_standard_variable(form, StandardVariable::Authority)
+                    })
+                    .flatten()
+            })
+    }
+
+    /// Whether the item is a thesis from a database whose number the style
+    /// options print as its publication number.
+    fn is_database_thesis(&self) -> bool {
+        self.style.options.thesis_publication_numbers
+            && self.instance.entry.matches_entry_type(Kind::Thesis)
+            && self
+                .instance
+                .resolve_standard_variable(LongShortForm::Long, StandardVariable::Archive)
+                .is_some()
+    }
+
+    /// The publication number of a thesis from a database, like "Publication
+    /// No. 12345".
+    fn resolve_publication_number(&self) -> Option<Cow<'a, ChunkedString>> {
+        let label = ExtraTerm::PublicationNumber.localize(&self.style.locale())?;
+        let number = match self
+            .instance
+            .resolve_number_variable(csl_taxonomy::NumberVariable::Number)?
+        {
+            NumberVariableResult::Regular(MaybeTyped::Typed(n)) => n.to_string(),
+            NumberVariableResult::Regular(MaybeTyped::String(s)) => s,
+            NumberVariableResult::Transparent(_) => return None,
+        };
+
+        let mut res = ChunkedString::new();
+        res.push_str(label, ChunkKind::Normal);
+        res.push_str(" ", ChunkKind::Normal);
+        res.push_str(&number, ChunkKind::Normal);
+        Some(Cow::Owned(res))
+    }
+
+    /// The description of a manuscript without a genre that was submitted for
+    /// publication, like "Manuscript submitted for publication", in the
+    /// language of the locale.
+    fn resolve_submitted_genre(&self) -> Option<Cow<'a, ChunkedString>> {
+        let submitted = self
+            .instance
+            .resolve_standard_variable(LongShortForm::Long, StandardVariable::Status)
+            .is_some_and(|s| s.to_str() == PublicationStatus::Submitted.name());
+        if !submitted || !self.instance.entry.matches_entry_type(Kind::Manuscript) {
+            return None;
+        }
+
+        let genre = ExtraTerm::ManuscriptSubmitted.localize(&self.style.locale())?;
+        Some(Cow::Owned(StringChunk::normal(genre).into()))
+    }
+
+    /// Assemble the title of untitled proceedings from their conference, like
+    /// "Proceedings of the 5th International Conference on Digital Libraries,
+    /// Vienna", if the style options ask for it. The number of the conference
+    /// is printed as an ordinal of the locale.
+    fn resolve_proceedings_title(
+        &self,
+        form: LongShortForm,
+    ) -> Option<Cow<'a, ChunkedString>> {
+        if !self.style.options.proceedings_titles {
+            return None;
+        }
+
+        let prefix = ExtraTerm::ProceedingsOf.localize(&self.style.locale())?;
+        let conference = self.instance.entry.resolve_conference(form)?;
+
+        let mut title = ChunkedString::new();
+        title.push_str(prefix, ChunkKind::Normal);
+        title.push_str(" ", ChunkKind::Normal);
+        match conference.number {
+            Some(MaybeTyped::Typed(number)) => {
+                let mut buf = String::new();
+                number
+                    .with_form(&mut buf, NumberForm::Ordinal, None, self.ordinal_lookup())
+                    .unwrap();
+                buf.push(' ');
+                title.push_str(&buf, ChunkKind::Normal);
+            }
+            Some(MaybeTyped::String(number)) => {
+                title.push_str(&number, ChunkKind::Normal);
+                title.push_str(" ", ChunkKind::Normal);
+            }
+            None => {}
+        }
+        title.0.extend(conference.name.0.iter().cloned());
+
+        if let Some(location) = conference.location {
+            title.push_str(", ", ChunkKind::Normal);
+            title.0.extend(location.0.iter().cloned());
+        }
+
+        Some(Cow::Owned(title))
+    }
+
+    /// Resolve the titles of a multilingual edition as prescribed by the title
+    /// languages policy of the style options.
+    ///
+    /// Returns `None` if the entry has no localized titles or if no policy is
+    /// set and the entry has a regular title.
+    fn resolve_localized_title(&self) -> Option<Cow<'a, ChunkedString>> {
+        let entry = self.instance.entry;
+        let titles = entry.resolve_localized_titles(LongShortForm::Long);
+        if titles.is_empty() {
+            return None;
+        }
+
+        let policy = match self.style.options.title_languages {
+            Some(policy) => policy,
+            None if entry
+                .resolve_standard_variable(LongShortForm::Long, StandardVariable::Title)
+                .is_some() =>
+            {
+                return None
+            }
+            None => TitleLanguages::Primary,
+        };
+
+        let locale = self.style.locale();
+        let bibliography_language = locale.0.split('-').next();
+        let position = |language: Option<&str>| {
+            titles.iter().position(|&(l, _)| Some(l) == language)
+        };
+        let primary = position(entry.base_language().as_deref())
+            .or_else(|| position(bibliography_language))
+            .unwrap_or(0);
+
+        let mut res = titles[primary].1.clone();
+        match policy {
+            TitleLanguages::Primary => {}
+            TitleLanguages::WithTranslation => {
+                let translation = position(bibliography_language)
+                    .filter(|&i| i != primary)
+                    .or_else(|| (0..titles.len()).find(|&i| i != primary));
+                if let Some(i) = translation {
+                    res.push_str(" [", ChunkKind::Normal);
+                    res.0.extend(titles[i].1 .0.iter().cloned());
+                    res.push_str("]", ChunkKind::Normal);
+                }
+            }
+            TitleLanguages::All => {
+                for (i, (_, title)) in titles.iter().enumerate() {
+                    if i != primary {
+                        res.push_str(" / ", ChunkKind::Normal);
+                        res.0.extend(title.0.iter().cloned());
+                    }
+                }
+            }
+        }
+
+        Some(Cow::Owned(res))
+    }
+
+    /// Resolve the short title of the entry or, if it has none, its title
+    /// without the subtitle.
+    fn resolve_short_title(&self) -> Option<Cow<'a, ChunkedString>> {
+        let short = self.instance.resolve_standard_variable(
+            LongShortForm::Short,
+            StandardVariable::TitleShort,
+        )?;
+        let title = self
+            .instance
+            .resolve_standard_variable(LongShortForm::Long, StandardVariable::Title)?;
+        if short != title {
+            return Some(short);
+        }
+
+        let mut main = ChunkedString::new();
+        for chunk in &title.0 {
+            if chunk.kind == ChunkKind::Normal {
+                if let Some(i) = chunk.value.find(": ") {
+                    main.push_str(&chunk.value[..i], ChunkKind::Normal);
+                    return Some(Cow::Owned(main));
+                }
+            }
+            main.push_chunk(chunk.clone());
+        }
+
+        Some(title)
+    }
+
+    /// Apply typographic quotation marks and dashes to titles if requested by
+    /// the style options.
+    fn smarten_title(
+        &self,
+        variable: csl_taxonomy::StandardVariable,
+        s: Cow<'a, ChunkedString>,
+    ) -> Cow<'a, ChunkedString> {
+        if !self.style.options.smart_punctuation
+            || !matches!(
+                variable,
+                StandardVariable::Title
+                    | StandardVariable::TitleShort
+                    | StandardVariable::ContainerTitle
+                    | StandardVariable::ContainerTitleShort
+                    | StandardVariable::CollectionTitle
+                    | StandardVariable::VolumeTitle
+                    | StandardVariable::PartTitle
+                    | StandardVariable::OriginalTitle
+                    | StandardVariable::ReviewedTitle
+                    | StandardVariable::EventTitle
+            )
+        {
+            return s;
+        }
+
+        let mark = |term: OtherTerm, default| {
+            self.term(term.into(), TermForm::default(), false).unwrap_or(default)
+        };
+        let outer = QuoteMarks {
+            open: mark(OtherTerm::OpenQuote, "“"),
+            close: mark(OtherTerm::CloseQuote, "”"),
+            open_inner: mark(OtherTerm::OpenInnerQuote, "‘"),
+            close_inner: mark(OtherTerm::CloseInnerQuote, "’"),
+        };
+
+        // Within quotation marks, the nesting levels are swapped.
+        let marks = if self.writing.inner_quotes {
+            QuoteMarks {
+                open: outer.open_inner,
+                close: outer.close_inner,
+                open_inner: outer.open,
+                close_inner: outer.close,
+            }
+        } else {
+            outer
+        };
+
+        let mut s = s.into_owned();
+        smarten(&mut s, &marks);
+        Cow::Owned(s)
+    }
+
     /// Resolve a date variable.
     ///
     /// Honors suppressions.
     fn resolve_date_variable(
         &self,
         variable: csl_taxonomy::DateVariable,
-    ) -> Option<Cow<'a, Date>> {
+    ) -> Option<Cow<'a, DateParts>> {
         self.writing.usage_info.borrow_mut().last_mut().has_vars = true;
         self.writing.prepare_variable_query(variable)?;
         let res = self.instance.entry.resolve_date_variable(variable);
@@ -2682,6 +4196,38 @@ pub enum CitePurpose {
     Prose,
 }
 
+/// A signal that introduces a cite and states how the work relates to the
+/// claim in the text, like "see" or "cf.".
+///
+/// The variants are in the conventional order of signals within a citation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Signal {
+    /// The work is one of several examples, "e.g.,".
+    Eg,
+    /// The work supports the claim, "see".
+    See,
+    /// The work provides additional support, "see also".
+    SeeAlso,
+    /// The work supports the claim by analogy, "cf.".
+    Cf,
+    /// The work contradicts the claim, "contra".
+    Contra,
+}
+
+impl Signal {
+    /// The signal in English and in lowercase. Citations print it in the
+    /// language of their locale instead if hayagriva knows it.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Eg => "e.g.,",
+            Self::See => "see",
+            Self::SeeAlso => "see also",
+            Self::Cf => "cf.",
+            Self::Contra => "contra",
+        }
+    }
+}
+
 /// A special citation form to use for the [`CitationItem`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 enum SpecialForm {
@@ -2744,11 +4290,7 @@ mod tests {
                 ));
             }
 
-            driver.finish(BibliographyRequest {
-                style: &style,
-                locale: None,
-                locale_files: &en_locale,
-            });
+            driver.finish(BibliographyRequest::new(&style, None, &en_locale));
 
             // for cite in finished.citations {
             //     println!("{}", cite.citation.to_string(BufWriteFormat::Plain))
@@ -0,0 +1,113 @@
+//! Numbering the footnotes of a document with note citations.
+
+use std::fmt::Debug;
+use std::hash::Hash;
+
+use citationberg::VerticalAlign;
+
+use super::elem::{ElemChild, ElemChildren, Formatting};
+use super::taxonomy::EntryLike;
+use super::{
+    BibliographyDriver, BibliographyRequest, CitationRequest, RenderedBibliography,
+};
+
+/// Numbers the footnotes of a document and renders their citations as they
+/// are written.
+///
+/// Each call to [`push_note`](Self::push_note) adds one footnote with one
+/// or more citations and renders them right away, so that note styles like
+/// Chicago or OSCOLA can choose between the full form, the short form, and
+/// "ibid." by the citations in the previous notes. When the document is
+/// complete, [`finish`](Self::finish) renders the bibliography.
+#[derive(Debug, Clone)]
+pub struct NoteStream<'a, T: EntryLike> {
+    driver: BibliographyDriver<'a, T>,
+    /// The number of notes so far.
+    notes: usize,
+}
+
+impl<T: EntryLike> Default for NoteStream<'_, T> {
+    fn default() -> Self {
+        Self { driver: BibliographyDriver::new(), notes: 0 }
+    }
+}
+
+impl<'a, T: EntryLike + Clone + Hash + PartialEq + Eq + Debug> NoteStream<'a, T> {
+    /// Create a new stream for a document without notes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a footnote with the citations and render them. The note number of
+    /// the citations is replaced by the number of the new note.
+    ///
+    /// All preceding citations are rendered again to determine the positions
+    /// of the new ones, so rendering a document note by note is slower than
+    /// rendering it at once with a [`BibliographyDriver`].
+    pub fn push_note(&mut self, citations: &[CitationRequest<'a, T>]) -> FormattedNote {
+        self.notes += 1;
+        let number = self.notes;
+        for citation in citations {
+            let mut citation = citation.clone();
+            citation.note_number = Some(number);
+            self.driver.citation(citation);
+        }
+
+        let mut note = FormattedNote { number, citations: vec![] };
+        let Some(first) = citations.first() else { return note };
+
+        let request = BibliographyRequest::new(
+            first.style,
+            first.locale.clone(),
+            first.locale_files,
+        )
+        .with_options(first.options.clone());
+        let mut rendered = self.driver.clone().finish(request);
+        let start = rendered.citations.len() - citations.len();
+        note.citations = rendered
+            .citations
+            .drain(start..)
+            .map(|citation| citation.citation)
+            .collect();
+
+        note
+    }
+
+    /// The number of notes so far.
+    pub fn len(&self) -> usize {
+        self.notes
+    }
+
+    /// Whether no notes have been added.
+    pub fn is_empty(&self) -> bool {
+        self.notes == 0
+    }
+
+    /// Render the bibliography of all cited entries.
+    pub fn finish(
+        self,
+        request: BibliographyRequest<'_>,
+    ) -> Option<RenderedBibliography> {
+        self.driver.finish(request).bibliography
+    }
+}
+
+/// A footnote with its rendered citations.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FormattedNote {
+    /// The number of the note, starting at one.
+    pub number: usize,
+    /// The rendered citations in the order in which they were passed.
+    pub citations: Vec<ElemChildren>,
+}
+
+impl FormattedNote {
+    /// The superscript note number that refers to the note from the text.
+    pub fn marker(&self) -> ElemChildren {
+        let formatting = Formatting {
+            vertical_align: VerticalAlign::Sup,
+            ..Default::default()
+        };
+        ElemChildren(vec![ElemChild::Text(formatting.add_text(self.number.to_string()))])
+    }
+}
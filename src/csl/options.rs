@@ -0,0 +1,272 @@
+use citationberg::{DisambiguationRule, NameAnd, NameAsSortOrder};
+
+use super::Signal;
+use crate::lang::{CaseExceptions, SentenceCase};
+use crate::types::TitleSeparator;
+
+/// Overrides for the punctuation and delimiter decisions of a style.
+///
+/// Institutions often require small deviations from a published style, like
+/// semicolons between authors or no period after a DOI. Each field that is
+/// `None` or `false` keeps the choice of the style, so the default options
+/// produce exactly the output of the unmodified style.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct StyleOptions {
+    /// The delimiter between the names in a list of names.
+    pub name_delimiter: Option<String>,
+    /// The conjunction before the last name in a list of names.
+    pub and: Option<NameAnd>,
+    /// The delimiter between the components of an entry. This replaces all
+    /// group delimiters that consist of a period and a space.
+    pub component_delimiter: Option<String>,
+    /// Whether bibliography entries end with a period. This does not apply to
+    /// entries that end with a link.
+    pub trailing_period: Option<bool>,
+    /// Whether a period follows a link at the end of a bibliography entry.
+    pub period_after_url: Option<bool>,
+    /// Whether to clean up the punctuation that styles leave behind when
+    /// optional parts of an entry are empty, like "..", ". ." or " ,".
+    /// Links, math, and verbatim parts of fields are not changed, and
+    /// neither are ellipses like "..." or ". . .".
+    pub tidy_punctuation: bool,
+    /// Whether to replace straight quotes in titles with the quotation marks
+    /// of the locale and apostrophes, and hyphens in numeric ranges with en
+    /// dashes. Verbatim and math parts of the titles are not changed.
+    pub smart_punctuation: bool,
+    /// Whether to use non-breaking spaces between initials and between labels
+    /// like "p." or "Vol." and their numbers. Renderers map them to their own
+    /// representation, like `~` in LaTeX.
+    pub non_breaking_spaces: bool,
+    /// Whether to group the digits of large page and volume numbers with the
+    /// separator of the locale, like "1,234" in English or "1.234" in German.
+    pub group_digits: bool,
+    /// Whether to remove the `https://` scheme from the displayed text of
+    /// links, as recommended by MLA. The link targets keep their scheme.
+    pub strip_link_scheme: bool,
+    /// Whether to remove the query string, like `?utm_source=feed`, from the
+    /// displayed text of links.
+    pub strip_url_query: bool,
+    /// The maximum number of characters in the displayed text of links.
+    /// Longer links are shortened at a slash in their path and end with an
+    /// ellipsis. The link targets are not changed.
+    pub url_max_length: Option<usize>,
+    /// Whether to mark where links whose text is their URL may be broken
+    /// across lines, like after slashes. The marks are zero-width spaces that
+    /// HTML output writes as `<wbr>` and LaTeX output as `\allowbreak`.
+    pub link_breaks: bool,
+    /// Whether to add the note or, if there is none, the abstract of each
+    /// entry to its bibliography item as an annotation.
+    pub annotations: bool,
+    /// When to print the location of the publisher. If set, the location is
+    /// printed before the publisher, like "New York: Penguin". This does not
+    /// affect the location of conferences and exhibitions.
+    pub publisher_location: Option<PublisherLocation>,
+    /// Whether and how to isolate right-to-left text, like Arabic or Hebrew
+    /// titles, so that the surrounding punctuation is not reordered.
+    pub bidi_isolation: Option<BidiIsolation>,
+    /// The rule for adding given names to the names in citations of
+    /// different persons with the same family name. If set, this replaces the
+    /// rule of the style and enables the disambiguation even if the style does
+    /// not. APA, for example, requires
+    /// [`PrimaryName`](DisambiguationRule::PrimaryName): "J. Smith" and "A.
+    /// Smith" are cited with their initials in all citations, regardless of
+    /// the year.
+    pub givenname_disambiguation: Option<DisambiguationRule>,
+    /// Whether to transliterate the output to ASCII, like "é" to "e" and "ß"
+    /// to "ss", and to replace typographic quotes and dashes. Characters
+    /// without an ASCII equivalent, like those of CJK scripts, are kept and
+    /// listed in [`Rendered::non_ascii`](crate::Rendered::non_ascii). The
+    /// characters added by other options, like non-breaking spaces, are kept
+    /// as well.
+    pub ascii_only: bool,
+    /// Which titles of a multilingual edition to print. If not set, the title
+    /// of an entry is printed and only entries without one use their primary
+    /// title.
+    pub title_languages: Option<TitleLanguages>,
+    /// The capitalization of the labels of the roles in a list of names,
+    /// like "Ed." or "ed." for editors. If set, this replaces the text case
+    /// of the label in the style.
+    pub role_label_case: Option<RoleLabelCase>,
+    /// How the labels of the roles in a list of names are printed, like
+    /// "(Trans.)" after or "Translated by" before the names. If set, this
+    /// replaces the form, position, and affixes of the label in the style.
+    /// Lists of names without a label in the style stay without one.
+    pub role_label_style: Option<RoleLabelStyle>,
+    /// The text that replaces the names of a bibliography entry if they are
+    /// the same as those of the previous entry, like the 3-em dash "———" of
+    /// Chicago or "---" of MLA. Entries of the same first author with
+    /// different co-authors keep their names.
+    pub repeated_author_substitute: Option<String>,
+    /// The marker that replaces the title of a periodical if the previous
+    /// bibliography entry appeared in the same periodical, like "ibid." The
+    /// first of consecutive articles in a periodical keeps its title.
+    pub repeated_container_marker: Option<String>,
+    /// Whether to derive the short form of titles that have none by dropping
+    /// the subtitle, like "The Great Book" for "The Great Book: A Study".
+    /// Styles like APA print the short title in citations of works without
+    /// an author.
+    pub derive_short_titles: bool,
+    /// Whether to assemble the title of proceedings that have none from their
+    /// conference, like "Proceedings of the 5th International Conference on
+    /// Digital Libraries, Vienna". This needs a locale in English, French,
+    /// German, or Spanish.
+    pub proceedings_titles: bool,
+    /// Whether to print theses as APA requires. The institution that grants
+    /// the degree, given as the organization, takes the place of the
+    /// publisher. The number of a thesis from a database, like ProQuest, is
+    /// printed as its publication number, like "(Publication No. 12345)
+    /// [Doctoral dissertation, University of Nowhere]". The label of the
+    /// number needs a locale in English, French, German, or Spanish.
+    pub thesis_publication_numbers: bool,
+    /// The signals that introduce cites to print in italics, like
+    /// [`Signal::Cf`] for OSCOLA or all signals for the Bluebook.
+    pub italic_signals: Vec<Signal>,
+    /// Whether to print the roles of a list of names with several roles in
+    /// the conventional order, like editors before translators and
+    /// translators before illustrators, instead of the order of the style.
+    /// Roles without a conventional position follow in the order of the
+    /// style.
+    pub canonical_role_order: bool,
+    /// The order of the given and family names of authors. If set, this
+    /// replaces the order of the style. It does not affect other roles, like
+    /// editors, or the sorting of the bibliography.
+    pub name_order: Option<NameOrder>,
+    /// Whether to sort persons with the same family name by the initials of
+    /// their given names instead of the full given names, if the style prints
+    /// initials. Different persons that look the same in the bibliography,
+    /// like "Smith, A." for Alice and Albert Smith, are then ordered by the
+    /// next sort key, like the year, as required by APA.
+    pub sort_by_initials: bool,
+    /// The punctuation that joins a title and its subtitle. Styles that follow
+    /// the Chicago Manual of Style use a colon, the default.
+    pub title_separator: TitleSeparator,
+    /// The rules for the text that the style prints in sentence case, like
+    /// the titles of articles in APA. If `None`, the default rules apply.
+    pub sentence_case: Option<SentenceCase>,
+    /// Words and phrases whose spelling the text in sentence case keeps, like
+    /// product names or gene symbols.
+    pub sentence_case_exceptions: CaseExceptions,
+    /// Whether to print the delimiter between an institution that is the
+    /// first name of a list and the "and" or "et al." that follows it, like
+    /// the comma in "World Health Organization, & Doe, J.". Styles print it
+    /// because a first name is usually inverted, which the name of an
+    /// institution is not. Lists of three or more names keep their
+    /// delimiters.
+    pub delimiter_after_institution: Option<bool>,
+    /// Whether to print DOIs in lowercase. DOIs are case-insensitive, so
+    /// their links still resolve. Otherwise, only the registrant prefix
+    /// before the slash is lowercased and the suffix keeps its case.
+    pub lowercase_doi: bool,
+    /// Whether styles that do not name the illustrators of a work, like APA,
+    /// list them with the translators, as in "(M. Sendak, Ill.)". Styles
+    /// that name illustrators are not affected. An illustrator who is also
+    /// an author is never listed as an illustrator.
+    pub list_illustrators: bool,
+    /// Whether to end the bibliography items of entries with a
+    /// `citation-count` with the count, as in "Cited 42 times.", like in an
+    /// evaluative bibliography. The sentence is in the language of the
+    /// locale. Entries without a count and locales in languages that
+    /// hayagriva has no sentence for are not changed.
+    pub citation_counts: bool,
+}
+
+/// The order of the given and family names in a list of names.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum NameOrder {
+    /// Print all names family name first, like "Smith, J., & Jones, T.".
+    FamilyFirst,
+    /// Print the first name family name first and all others given name
+    /// first, like "Smith, John, and Tom Jones" in Chicago.
+    FirstFamilyFirst,
+    /// Print all names given name first, like "John Smith and Tom Jones".
+    GivenFirst,
+}
+
+impl NameOrder {
+    /// The equivalent `name-as-sort-order` attribute of CSL.
+    pub(super) fn to_sort_order(self) -> Option<NameAsSortOrder> {
+        match self {
+            Self::FamilyFirst => Some(NameAsSortOrder::All),
+            Self::FirstFamilyFirst => Some(NameAsSortOrder::First),
+            Self::GivenFirst => None,
+        }
+    }
+}
+
+/// The capitalization of role labels like "ed." or "trans.".
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum RoleLabelCase {
+    /// Print the label in lowercase, like "(ed.)". A label at the start of a
+    /// sentence is still capitalized, like "Title. Edited by".
+    Lowercase,
+    /// Capitalize the first letter of the label, like "(Ed.)".
+    Capitalized,
+}
+
+/// How the labels of the roles in a list of names are printed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum RoleLabelStyle {
+    /// An abbreviation in parentheses after the names, like "J. Smith
+    /// (Trans.)" in APA.
+    Parenthetical,
+    /// A clause before the names, like "Translated by John Smith" in Chicago.
+    /// The clause is capitalized only at the start of a sentence, like in
+    /// "Edited by Jane Doe, translated by John Smith".
+    SpelledOut,
+}
+
+/// A policy for printing the titles of a multilingual edition.
+///
+/// The primary title is the one in the language of the entry. If the entry
+/// has no language or no title in it, the title in the language of the
+/// bibliography or else the first title is primary.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum TitleLanguages {
+    /// Print only the primary title.
+    Primary,
+    /// Print the primary title followed by a translation in brackets, like
+    /// "Gedichte [Poems]". The translation is the title in the language of
+    /// the bibliography or, if that is the primary title, the next title.
+    WithTranslation,
+    /// Print all titles, starting with the primary one, separated by slashes.
+    All,
+}
+
+/// How to isolate right-to-left text in the output.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum BidiIsolation {
+    /// Surround the text with the Unicode characters FIRST STRONG ISOLATE and
+    /// POP DIRECTIONAL ISOLATE.
+    Characters,
+    /// Put the text into an element with [`ElemMeta::Isolate`] that is written
+    /// as `<bdi>` in HTML.
+    ///
+    /// [`ElemMeta::Isolate`]: crate::ElemMeta::Isolate
+    Markup,
+}
+
+/// A policy for printing the location of the publisher of an entry.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PublisherLocation {
+    /// Never print the location, like APA 7 does for books.
+    Never,
+    /// Print the location before the publisher, like APA 6 and Chicago do.
+    /// Entries without a publisher have no location.
+    WhenPrePublisher,
+    /// Print the location before the publisher and on its own if there is no
+    /// publisher.
+    Always,
+}
+
+impl StyleOptions {
+    /// The delimiter to use for a group with the given delimiter.
+    pub(super) fn group_delimiter<'a>(
+        &'a self,
+        delimiter: Option<&'a str>,
+    ) -> Option<&'a str> {
+        match (delimiter, &self.component_delimiter) {
+            (Some(". "), Some(component)) => Some(component),
+            _ => delimiter,
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use citationberg::Display;
+
+use super::is_url_text;
+
+use crate::csl::{ElemChild, ElemChildren, Formatted, Formatting};
+
+/// How to render links in terminal output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum AnsiLinks {
+    /// Emit OSC 8 hyperlinks that terminals can make clickable.
+    #[default]
+    Hyperlinks,
+    /// Write the target right after the anchor text if they differ.
+    Plain,
+}
+
+/// Options for [`render_ansi`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct AnsiOptions {
+    /// Do not emit any SGR sequences for the text formatting.
+    pub no_color: bool,
+    /// How to render links.
+    pub links: AnsiLinks,
+}
+
+/// Render elements for a terminal with ANSI escape sequences.
+///
+/// The formatting is reset and reapplied at every change, so the output is
+/// correct for nested spans and never leaks formatting past its end.
+pub fn render_ansi(children: &ElemChildren, options: &AnsiOptions) -> String {
+    let mut writer = AnsiWriter {
+        options,
+        buf: String::new(),
+        current: Formatting::default(),
+    };
+    writer.children(children);
+    writer.switch(Formatting::default());
+    writer.buf
+}
+
+struct AnsiWriter<'a> {
+    options: &'a AnsiOptions,
+    buf: String,
+    /// The formatting that is active at the current position.
+    current: Formatting,
+}
+
+impl AnsiWriter<'_> {
+    fn children(&mut self, children: &ElemChildren) {
+        for child in &children.0 {
+            self.child(child);
+        }
+    }
+
+    fn child(&mut self, child: &ElemChild) {
+        match child {
+            ElemChild::Text(t) => self.text(t),
+            ElemChild::Elem(e) => {
+                let block = e.display == Some(Display::Block);
+                if block {
+                    self.buf.push('\n');
+                }
+                self.children(&e.children);
+                if block {
+                    self.buf.push('\n');
+                }
+            }
+            ElemChild::Markup(m) => self.buf.push_str(m),
+            ElemChild::Link { text, url } => match self.options.links {
+                AnsiLinks::Hyperlinks => {
+                    self.buf.push_str("\x1b]8;;");
+                    self.buf.push_str(url);
+                    self.buf.push_str("\x1b\\");
+                    self.text(text);
+                    self.buf.push_str("\x1b]8;;\x1b\\");
+                }
+                AnsiLinks::Plain => {
+                    self.text(text);
+                    if !is_url_text(&text.text, url) {
+                        self.switch(Formatting::default());
+                        self.buf.push_str(" <");
+                        self.buf.push_str(url);
+                        self.buf.push('>');
+                    }
+                }
+            },
+            ElemChild::Transparent { .. } => {}
+        }
+    }
+
+    fn text(&mut self, text: &Formatted) {
+        self.switch(text.formatting);
+        self.buf.push_str(&text.text);
+    }
+
+    fn switch(&mut self, formatting: Formatting) {
+        if self.options.no_color || formatting == self.current {
+            return;
+        }
+
+        if self.current != Formatting::default() {
+            self.buf.push_str("\x1b[0m");
+        }
+
+        formatting.write_vt100(&mut self.buf).unwrap();
+        self.current = formatting;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use citationberg::{FontStyle, FontWeight};
+
+    use super::*;
+
+    #[test]
+    fn nested_bold_italic() {
+        let bold = Formatting {
+            font_weight: FontWeight::Bold,
+            ..Default::default()
+        };
+        let bold_italic = Formatting { font_style: FontStyle::Italic, ..bold };
+        let children = ElemChildren(vec![
+            ElemChild::Text(bold.add_text("Bold ".into())),
+            ElemChild::Text(bold_italic.add_text("both".into())),
+            ElemChild::Text(bold.add_text(" bold".into())),
+            ElemChild::Text(Formatting::default().add_text(", plain ".into())),
+            ElemChild::Link {
+                text: bold_italic.add_text("link".into()),
+                url: "https://example.com".into(),
+            },
+        ]);
+
+        assert_eq!(
+            render_ansi(&children, &AnsiOptions::default()),
+            "\x1b[1mBold \x1b[0m\x1b[3m\x1b[1mboth\x1b[0m\x1b[1m bold\x1b[0m, plain \
+             \x1b]8;;https://example.com\x1b\\\x1b[3m\x1b[1mlink\x1b]8;;\x1b\\\x1b[0m"
+        );
+
+        let options = AnsiOptions { no_color: true, links: AnsiLinks::Plain };
+        assert_eq!(
+            render_ansi(&children, &options),
+            "Bold both bold, plain link <https://example.com>"
+        );
+    }
+}
@@ -0,0 +1,153 @@
+use citationberg::{
+    Display, FontStyle, FontVariant, FontWeight, SecondFieldAlign, TextDecoration,
+    VerticalAlign,
+};
+
+use super::LINK_BREAK;
+use crate::csl::{ElemChild, ElemChildren, Formatted, ItemLayout, RenderedBibliography};
+
+/// Render elements as LaTeX markup.
+///
+/// Links are written with `\href` from the `hyperref` package. Where
+/// [`StyleOptions::link_breaks`](crate::StyleOptions::link_breaks) marked
+/// them, they can be broken across lines by `\allowbreak`.
+pub fn render_latex(children: &ElemChildren) -> String {
+    let mut buf = String::new();
+    write_children(&mut buf, children);
+    buf
+}
+
+/// Render a bibliography as a LaTeX `list` environment with one `\item` per
+/// entry.
+///
+/// The labels of numeric and label styles become the labels of the items and
+/// the list is indented by the widest of them, so that the bodies are aligned.
+/// Styles with a hanging indent indent all lines but the first by `2em`.
+pub fn render_latex_bibliography(bibliography: &RenderedBibliography) -> String {
+    let layout = bibliography.layout();
+    let labels: Vec<String> = bibliography
+        .items
+        .iter()
+        .map(|item| render_latex(&item.prefix()))
+        .collect();
+
+    let mut buf = String::from("\\begin{list}{}{");
+    match layout {
+        ItemLayout::Aligned(align) => {
+            let widest = labels.iter().max_by_key(|l| l.chars().count());
+            buf.push_str("\\settowidth{\\labelwidth}{");
+            buf.push_str(widest.map_or("", String::as_str));
+            buf.push('}');
+            if align == SecondFieldAlign::Margin {
+                buf.push_str("\\setlength{\\leftmargin}{0pt}");
+            } else {
+                buf.push_str("\\setlength{\\leftmargin}{\\labelwidth}");
+                buf.push_str("\\addtolength{\\leftmargin}{\\labelsep}");
+            }
+        }
+        ItemLayout::HangingIndent => {
+            buf.push_str("\\setlength{\\leftmargin}{2em}");
+            buf.push_str("\\setlength{\\itemindent}{-2em}");
+        }
+        ItemLayout::Block => buf.push_str("\\setlength{\\leftmargin}{0pt}"),
+    }
+    buf.push_str("}\n");
+
+    for (item, label) in bibliography.items.iter().zip(labels) {
+        buf.push_str("\\item");
+        if matches!(layout, ItemLayout::Aligned(_)) {
+            buf.push_str("[{");
+            buf.push_str(&label);
+            buf.push_str("}]");
+        }
+        buf.push(' ');
+        write_children(&mut buf, item.body());
+        buf.push('\n');
+    }
+
+    buf.push_str("\\end{list}\n");
+    buf
+}
+
+fn write_children(buf: &mut String, children: &ElemChildren) {
+    for child in &children.0 {
+        write_child(buf, child);
+    }
+}
+
+fn write_child(buf: &mut String, child: &ElemChild) {
+    match child {
+        ElemChild::Text(t) => write_formatted(buf, t, escape),
+        ElemChild::Elem(e) => {
+            write_children(buf, &e.children);
+            if e.display == Some(Display::Block) {
+                buf.push_str("\\par ");
+            }
+        }
+        ElemChild::Markup(m) => escape(buf, m),
+        ElemChild::Link { text, url } => {
+            buf.push_str("\\href{");
+            for c in url.chars() {
+                if matches!(c, '%' | '#' | '\\') {
+                    buf.push('\\');
+                }
+                buf.push(c);
+            }
+            buf.push_str("}{");
+            write_formatted(buf, text, |buf, s| {
+                for (i, segment) in s.split(LINK_BREAK).enumerate() {
+                    if i > 0 {
+                        buf.push_str("\\allowbreak{}");
+                    }
+                    escape(buf, segment);
+                }
+            });
+            buf.push('}');
+        }
+        ElemChild::Transparent { .. } => {}
+    }
+}
+
+/// Wrap the text in the commands for its formatting.
+fn write_formatted(buf: &mut String, text: &Formatted, body: fn(&mut String, &str)) {
+    let f = &text.formatting;
+    let commands = [
+        (f.font_style == FontStyle::Italic, "\\textit{"),
+        (f.font_weight == FontWeight::Bold, "\\textbf{"),
+        (f.font_weight == FontWeight::Light, "\\textlf{"),
+        (f.font_variant == FontVariant::SmallCaps, "\\textsc{"),
+        (f.text_decoration == TextDecoration::Underline, "\\underline{"),
+        (f.vertical_align == VerticalAlign::Sup, "\\textsuperscript{"),
+        (f.vertical_align == VerticalAlign::Sub, "\\textsubscript{"),
+    ];
+
+    let mut open = 0;
+    for (active, command) in commands {
+        if active {
+            buf.push_str(command);
+            open += 1;
+        }
+    }
+
+    body(buf, &text.text);
+    for _ in 0..open {
+        buf.push('}');
+    }
+}
+
+/// Escape the characters that have a special meaning in LaTeX.
+fn escape(buf: &mut String, s: &str) {
+    for c in s.chars() {
+        match c {
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                buf.push('\\');
+                buf.push(c);
+            }
+            '~' => buf.push_str("\\textasciitilde{}"),
+            '^' => buf.push_str("\\textasciicircum{}"),
+            '\\' => buf.push_str("\\textbackslash{}"),
+            '\u{a0}' => buf.push('~'),
+            c => buf.push(c),
+        }
+    }
+}
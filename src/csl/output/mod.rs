@@ -0,0 +1,70 @@
+//! Renderers that turn formatted [elements](super::ElemChildren) into output
+//! formats beyond the ones offered by [`BufWriteFormat`](super::BufWriteFormat).
+
+mod ansi;
+mod latex;
+mod plain;
+mod rtf;
+
+pub use ansi::{render_ansi, AnsiLinks, AnsiOptions};
+pub use latex::{render_latex, render_latex_bibliography};
+pub use plain::{render_plain, PlainItalics, PlainLinks, PlainTextOptions};
+pub use rtf::{render_rtf, render_rtf_bibliography};
+
+/// The zero-width space that marks where a link may be broken across lines.
+pub(super) const LINK_BREAK: &str = "\u{200B}";
+
+/// Whether the text of a link is its URL, ignoring the marks of line breaks.
+fn is_url_text(text: &str, url: &str) -> bool {
+    text.replace(LINK_BREAK, "") == url
+}
+
+/// Get the byte offsets at which a URL or DOI may be broken across lines.
+///
+/// Breaks are allowed after slashes and before periods and hyphens, but never
+/// within the protocol (like `https://`) or at the very start or end.
+pub fn link_break_points(link: &str) -> Vec<usize> {
+    let start = link.find("://").map_or(0, |i| i + 3);
+    let mut points: Vec<usize> = Vec::new();
+    for (i, c) in link[start..].char_indices() {
+        let point = match c {
+            '/' => start + i + 1,
+            '.' | '-' => start + i,
+            _ => continue,
+        };
+
+        if point > start && point < link.len() && points.last() != Some(&point) {
+            points.push(point);
+        }
+    }
+
+    points
+}
+
+/// Split a link text into the segments between its break points.
+pub(super) fn link_segments(link: &str) -> Vec<&str> {
+    let mut segments = Vec::new();
+    let mut last = 0;
+    for point in link_break_points(link) {
+        segments.push(&link[last..point]);
+        last = point;
+    }
+
+    segments.push(&link[last..]);
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn break_points() {
+        assert_eq!(
+            link_segments("https://doi.org/10.1000/ab-c.d/").join("|"),
+            "https://doi|.org/|10|.1000/|ab|-c|.d/"
+        );
+        assert_eq!(link_segments("10.1000/xyz").join("|"), "10|.1000/|xyz");
+        assert_eq!(link_segments("example"), ["example"]);
+    }
+}
@@ -0,0 +1,278 @@
+use citationberg::{Display, FontStyle};
+
+use super::{is_url_text, link_segments, LINK_BREAK};
+use crate::csl::{ElemChild, ElemChildren, Formatted};
+
+/// How to signal italic text in plain-text output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum PlainItalics {
+    /// Drop the italics.
+    #[default]
+    None,
+    /// Surround italic text with underscores like `_this_`.
+    Underscores,
+    /// Replace Latin letters with their counterparts from the Unicode
+    /// Mathematical Alphanumeric Symbols block.
+    Unicode,
+}
+
+/// How to render links in plain-text output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum PlainLinks {
+    /// Write the target right after the anchor text if they differ.
+    #[default]
+    Inline,
+    /// Mark the anchor text with a number and list the targets at the end.
+    Footnotes,
+}
+
+/// Options for [`render_plain`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct PlainTextOptions {
+    /// How to signal italic text.
+    pub italics: PlainItalics,
+    /// How to render links.
+    pub links: PlainLinks,
+    /// Wrap lines that are longer than this many characters. Lines are only
+    /// broken at regular spaces, so words, URLs, and text joined by
+    /// non-breaking spaces will never be split.
+    pub max_width: Option<usize>,
+    /// Insert zero-width spaces where links may be broken across lines.
+    pub link_breaks: bool,
+}
+
+/// Render elements as plain text without any formatting markup.
+pub fn render_plain(children: &ElemChildren, options: &PlainTextOptions) -> String {
+    let mut writer = PlainWriter { options, buf: String::new(), footnotes: Vec::new() };
+    writer.children(children);
+
+    let PlainWriter { mut buf, footnotes, .. } = writer;
+    if let Some(width) = options.max_width {
+        buf = wrap(&buf, width);
+    }
+
+    for (i, url) in footnotes.iter().enumerate() {
+        buf.push_str(&format!("\n[{}] {}", i + 1, url));
+    }
+
+    buf
+}
+
+struct PlainWriter<'a> {
+    options: &'a PlainTextOptions,
+    buf: String,
+    footnotes: Vec<String>,
+}
+
+impl PlainWriter<'_> {
+    fn children(&mut self, children: &ElemChildren) {
+        for child in &children.0 {
+            self.child(child);
+        }
+    }
+
+    fn child(&mut self, child: &ElemChild) {
+        match child {
+            ElemChild::Text(t) => self.text(t),
+            ElemChild::Elem(e) => {
+                let block = e.display == Some(Display::Block);
+                if block {
+                    self.buf.push('\n');
+                }
+                self.children(&e.children);
+                if block {
+                    self.buf.push('\n');
+                }
+            }
+            ElemChild::Markup(m) => self.buf.push_str(m),
+            ElemChild::Link { text, url } => {
+                if self.options.link_breaks {
+                    let unmarked = text.text.replace(LINK_BREAK, "");
+                    let broken = link_segments(&unmarked).join(LINK_BREAK);
+                    self.text(&text.formatting.add_text(broken));
+                } else {
+                    self.text(text);
+                }
+
+                if is_url_text(&text.text, url) {
+                    return;
+                }
+
+                match self.options.links {
+                    PlainLinks::Inline => {
+                        self.buf.push_str(" <");
+                        self.buf.push_str(url);
+                        self.buf.push('>');
+                    }
+                    PlainLinks::Footnotes => {
+                        self.footnotes.push(url.clone());
+                        self.buf.push_str(&format!(" [{}]", self.footnotes.len()));
+                    }
+                }
+            }
+            ElemChild::Transparent { .. } => {}
+        }
+    }
+
+    fn text(&mut self, text: &Formatted) {
+        if text.formatting.font_style != FontStyle::Italic {
+            self.buf.push_str(&text.text);
+            return;
+        }
+
+        match self.options.italics {
+            PlainItalics::None => self.buf.push_str(&text.text),
+            PlainItalics::Underscores => {
+                self.buf.push('_');
+                self.buf.push_str(&text.text);
+                self.buf.push('_');
+            }
+            PlainItalics::Unicode => self.buf.extend(text.text.chars().map(italic)),
+        }
+    }
+}
+
+/// Map a Latin letter to the Unicode mathematical italic letter.
+fn italic(c: char) -> char {
+    let mapped = match c {
+        // The italic small h is not part of the block but lives in the
+        // Letterlike Symbols block.
+        'h' => 0x210E,
+        'A'..='Z' => 0x1D434 + (c as u32 - 'A' as u32),
+        'a'..='z' => 0x1D44E + (c as u32 - 'a' as u32),
+        _ => return c,
+    };
+
+    char::from_u32(mapped).unwrap_or(c)
+}
+
+/// Greedily wrap each line at regular spaces.
+fn wrap(s: &str, width: usize) -> String {
+    let mut res = String::with_capacity(s.len());
+    for (i, line) in s.split('\n').enumerate() {
+        if i > 0 {
+            res.push('\n');
+        }
+
+        let mut len = 0;
+        for word in line.split(' ').filter(|w| !w.is_empty()) {
+            let word_len = word.chars().count();
+            if len > 0 && len + 1 + word_len > width {
+                res.push('\n');
+                len = 0;
+            } else if len > 0 {
+                res.push(' ');
+                len += 1;
+            }
+
+            res.push_str(word);
+            len += word_len;
+        }
+    }
+
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::csl::Formatting;
+
+    fn text(s: &str, italic: bool) -> ElemChild {
+        let mut formatting = Formatting::default();
+        if italic {
+            formatting.font_style = FontStyle::Italic;
+        }
+        ElemChild::Text(Formatted { text: s.to_string(), formatting })
+    }
+
+    fn link(text: &str, url: &str) -> ElemChild {
+        ElemChild::Link {
+            text: Formatted {
+                text: text.to_string(),
+                formatting: Formatting::default(),
+            },
+            url: url.to_string(),
+        }
+    }
+
+    #[test]
+    fn italics() {
+        let children = ElemChildren(vec![
+            text("Kwan, K. (2014). ", false),
+            text("Crazy rich Asians", true),
+            text(".", false),
+        ]);
+
+        let mut options = PlainTextOptions::default();
+        assert_eq!(
+            render_plain(&children, &options),
+            "Kwan, K. (2014). Crazy rich Asians."
+        );
+
+        options.italics = PlainItalics::Underscores;
+        assert_eq!(
+            render_plain(&children, &options),
+            "Kwan, K. (2014). _Crazy rich Asians_."
+        );
+
+        options.italics = PlainItalics::Unicode;
+        assert_eq!(
+            render_plain(&children, &options),
+            "Kwan, K. (2014). 𝐶𝑟𝑎𝑧𝑦 𝑟𝑖𝑐ℎ 𝐴𝑠𝑖𝑎𝑛𝑠."
+        );
+    }
+
+    #[test]
+    fn links() {
+        let children = ElemChildren(vec![
+            text("See ", false),
+            link("the docs", "https://example.com/docs"),
+            text(" or ", false),
+            link("https://example.com", "https://example.com"),
+            text(".", false),
+        ]);
+
+        let mut options = PlainTextOptions::default();
+        assert_eq!(
+            render_plain(&children, &options),
+            "See the docs <https://example.com/docs> or https://example.com."
+        );
+
+        options.links = PlainLinks::Footnotes;
+        assert_eq!(
+            render_plain(&children, &options),
+            "See the docs [1] or https://example.com.\n[1] https://example.com/docs"
+        );
+    }
+
+    #[test]
+    fn link_breaks() {
+        let url = "https://doi.org/10.1000/xyz";
+        let children = ElemChildren(vec![link(url, url)]);
+        let options = PlainTextOptions { link_breaks: true, ..Default::default() };
+        assert_eq!(
+            render_plain(&children, &options),
+            "https://doi\u{200B}.org/\u{200B}10\u{200B}.1000/\u{200B}xyz"
+        );
+    }
+
+    #[test]
+    fn wrap_around_url() {
+        let url = "https://doi.org/10.1103/PhysRevB.102.165126-with-a-very-long-suffix";
+        let children = ElemChildren(vec![
+            text("Doan, T. D. (2020). Kinetics and luminescence. ", false),
+            link(url, url),
+            text(" accessed\u{a0}today.", false),
+        ]);
+
+        let options = PlainTextOptions { max_width: Some(30), ..Default::default() };
+        assert_eq!(
+            render_plain(&children, &options),
+            format!(
+                "Doan, T. D. (2020). Kinetics\nand luminescence.\n{}\naccessed\u{a0}today.",
+                url
+            )
+        );
+    }
+}
@@ -0,0 +1,199 @@
+use citationberg::{
+    Display, FontStyle, FontVariant, FontWeight, TextDecoration, VerticalAlign,
+};
+
+use crate::csl::{ElemChild, ElemChildren, Formatted, Formatting, RenderedBibliography};
+
+/// Render elements as an RTF fragment that can be embedded in a paragraph of
+/// an RTF document.
+pub fn render_rtf(children: &ElemChildren) -> String {
+    let mut writer = RtfWriter::default();
+    writer.children(children);
+    writer.finish()
+}
+
+/// Render a bibliography as a complete RTF document with one paragraph per
+/// entry.
+///
+/// If the style asks for a hanging indent, the paragraphs are indented by half
+/// an inch except for their first line. Annotations follow their entry in a
+/// paragraph that is indented by half an inch.
+pub fn render_rtf_bibliography(bibliography: &RenderedBibliography) -> String {
+    let mut buf = String::from("{\\rtf1\\ansi\\deff0\n");
+    for item in &bibliography.items {
+        buf.push_str("{\\pard ");
+        if bibliography.hanging_indent {
+            buf.push_str("\\fi-720\\li720 ");
+        }
+
+        let mut writer = RtfWriter::default();
+        if let Some(first_field) = &item.first_field {
+            writer.child(first_field);
+            writer.buf.push_str("\\tab ");
+        }
+
+        writer.children(&item.content);
+        buf.push_str(&writer.finish());
+        buf.push_str("\\par}\n");
+
+        if let Some(annotation) = &item.annotation {
+            buf.push_str("{\\pard\\li720 ");
+            let mut writer = RtfWriter::default();
+            writer.children(&annotation.children);
+            buf.push_str(&writer.finish());
+            buf.push_str("\\par}\n");
+        }
+    }
+
+    buf.push('}');
+    buf
+}
+
+#[derive(Default)]
+struct RtfWriter {
+    buf: String,
+    /// The formatting that is active at the current position.
+    current: Formatting,
+}
+
+impl RtfWriter {
+    fn finish(mut self) -> String {
+        self.switch(Formatting::default());
+        self.buf
+    }
+
+    fn children(&mut self, children: &ElemChildren) {
+        for child in &children.0 {
+            self.child(child);
+        }
+    }
+
+    fn child(&mut self, child: &ElemChild) {
+        match child {
+            ElemChild::Text(t) => self.text(t),
+            ElemChild::Elem(e) => {
+                let block = e.display == Some(Display::Block);
+                if block {
+                    self.buf.push_str("\\line ");
+                }
+                self.children(&e.children);
+                if block {
+                    self.buf.push_str("\\line ");
+                }
+            }
+            ElemChild::Markup(m) => escape(&mut self.buf, m),
+            ElemChild::Link { text, url } => {
+                // The field result is a group, so the formatting state outside
+                // of it is unaffected by the toggles within.
+                self.buf.push_str("{\\field{\\*\\fldinst HYPERLINK \"");
+                escape(&mut self.buf, url);
+                self.buf.push_str("\"}{\\fldrslt ");
+                self.buf.push_str(&toggles(&self.current, &text.formatting));
+                escape(&mut self.buf, &text.text);
+                self.buf.push_str("}}");
+            }
+            ElemChild::Transparent { .. } => {}
+        }
+    }
+
+    fn text(&mut self, text: &Formatted) {
+        self.switch(text.formatting);
+        escape(&mut self.buf, &text.text);
+    }
+
+    fn switch(&mut self, formatting: Formatting) {
+        self.buf.push_str(&toggles(&self.current, &formatting));
+        self.current = formatting;
+    }
+}
+
+/// The control words that switch from one formatting to another.
+fn toggles(from: &Formatting, to: &Formatting) -> String {
+    let mut res = String::new();
+    if from.font_style != to.font_style {
+        res.push_str(if to.font_style == FontStyle::Italic { "\\i" } else { "\\i0" });
+    }
+
+    if from.font_weight != to.font_weight {
+        res.push_str(if to.font_weight == FontWeight::Bold { "\\b" } else { "\\b0" });
+    }
+
+    if from.font_variant != to.font_variant {
+        res.push_str(if to.font_variant == FontVariant::SmallCaps {
+            "\\scaps"
+        } else {
+            "\\scaps0"
+        });
+    }
+
+    if from.text_decoration != to.text_decoration {
+        res.push_str(if to.text_decoration == TextDecoration::Underline {
+            "\\ul"
+        } else {
+            "\\ul0"
+        });
+    }
+
+    if from.vertical_align != to.vertical_align {
+        res.push_str(match to.vertical_align {
+            VerticalAlign::Sup => "\\super",
+            VerticalAlign::Sub => "\\sub",
+            _ => "\\nosupersub",
+        });
+    }
+
+    if !res.is_empty() {
+        res.push(' ');
+    }
+
+    res
+}
+
+/// Escape RTF control characters and encode non-ASCII characters as Unicode
+/// control words.
+fn escape(buf: &mut String, s: &str) {
+    for c in s.chars() {
+        match c {
+            '\\' | '{' | '}' => {
+                buf.push('\\');
+                buf.push(c);
+            }
+            '\n' => buf.push_str("\\line "),
+            '\t' => buf.push_str("\\tab "),
+            '\u{200B}' => buf.push_str("\\zwbo "),
+            c if c.is_ascii() => buf.push(c),
+            c => {
+                // RTF takes signed 16-bit code units and a fallback character
+                // for readers that do not understand the control word.
+                let mut units = [0; 2];
+                for &unit in c.encode_utf16(&mut units).iter() {
+                    buf.push_str(&format!("\\u{}?", unit as i16));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nested_formatting() {
+        let italic = Formatting {
+            font_style: FontStyle::Italic,
+            ..Default::default()
+        };
+        let bold_italic = Formatting { font_weight: FontWeight::Bold, ..italic };
+        let children = ElemChildren(vec![
+            ElemChild::Text(italic.add_text("Mit {Günther} ".into())),
+            ElemChild::Text(bold_italic.add_text("\u{1D49C}".into())),
+            ElemChild::Text(Formatting::default().add_text("\\.".into())),
+        ]);
+
+        assert_eq!(
+            render_rtf(&children),
+            "\\i Mit \\{G\\u252?nther\\} \\b \\u-10187?\\u-9060?\\i0\\b0 \\\\."
+        );
+    }
+}
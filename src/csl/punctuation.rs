@@ -0,0 +1,550 @@
+//! A finishing pass that cleans up punctuation in rendered output.
+//!
+//! Styles assemble references from many optional parts. When some of them
+//! are empty, their affixes and delimiters can leave behind artifacts like
+//! "..", ". ." or " ,". This pass removes them from the finished elements.
+
+use citationberg::LocaleCode;
+
+use super::elem::last_text_child;
+use super::{ElemChild, ElemChildren, ElemMeta, Formatted, Formatting, StyleOptions};
+use crate::lang::en::ABBREVIATIONS;
+use crate::types::{ChunkKind, ChunkedString};
+
+/// Collapse repeated periods and commas, remove periods after question and
+/// exclamation marks, remove spaces before periods and commas, and collapse
+/// double spaces.
+///
+/// Links, markup, verbatim parts of fields, and block boundaries are never
+/// modified and separate the text around them, so punctuation is never
+/// merged across them. Ellipses, like "..." and ". . .", are kept.
+pub(super) fn tidy_punctuation(children: &mut ElemChildren) {
+    let mut segments = vec![Vec::new()];
+    collect_texts(children, &mut segments);
+    for segment in segments {
+        tidy_segment(segment);
+    }
+}
+
+/// Collect the text children into segments of adjacent text.
+fn collect_texts<'a>(
+    children: &'a mut ElemChildren,
+    segments: &mut Vec<Vec<&'a mut String>>,
+) {
+    for child in &mut children.0 {
+        match child {
+            ElemChild::Text(t) => segments.last_mut().unwrap().push(&mut t.text),
+            ElemChild::Elem(e) if e.meta == Some(ElemMeta::Verbatim) => {
+                segments.push(Vec::new())
+            }
+            ElemChild::Elem(e) => {
+                let block = e.display.is_some();
+                if block {
+                    segments.push(Vec::new());
+                }
+                collect_texts(&mut e.children, segments);
+                if block {
+                    segments.push(Vec::new());
+                }
+            }
+            ElemChild::Markup(_)
+            | ElemChild::Link { .. }
+            | ElemChild::Transparent { .. } => segments.push(Vec::new()),
+        }
+    }
+}
+
+fn tidy_segment(texts: Vec<&mut String>) {
+    let chars: Vec<char> = texts.iter().flat_map(|t| t.chars()).collect();
+    let mut keep = vec![true; chars.len()];
+    let ellipsis = spaced_ellipses(&chars);
+
+    // The indices of the characters that were kept so far.
+    let mut kept: Vec<usize> = Vec::new();
+    let last =
+        |kept: &[usize], n: usize| kept.len().checked_sub(n).map(|i| chars[kept[i]]);
+
+    for (i, &c) in chars.iter().enumerate() {
+        if ellipsis[i] {
+            kept.push(i);
+            continue;
+        }
+
+        if c == ' ' && last(&kept, 1) == Some(' ') {
+            keep[i] = false;
+            continue;
+        }
+
+        if matches!(c, '.' | ',') && last(&kept, 1) == Some(' ') {
+            keep[kept.pop().unwrap()] = false;
+        }
+
+        let drop = match (c, last(&kept, 1)) {
+            ('.', Some('?' | '!')) => true,
+            ('.', Some('.')) => {
+                // Leave ellipses alone.
+                last(&kept, 2) != Some('.') && chars.get(i + 1) != Some(&'.')
+            }
+            (',', Some(',')) => true,
+            _ => false,
+        };
+
+        if drop {
+            keep[i] = false;
+        } else {
+            kept.push(i);
+        }
+    }
+
+    let mut offset = 0;
+    for text in texts {
+        let len = text.chars().count();
+        let keep = &keep[offset..offset + len];
+        if keep.iter().any(|k| !k) {
+            *text = text.chars().zip(keep).filter(|(_, &k)| k).map(|(c, _)| c).collect();
+        }
+        offset += len;
+    }
+}
+
+/// Mark the periods and spaces of spaced ellipses like ". . .", which
+/// consist of at least three periods.
+fn spaced_ellipses(chars: &[char]) -> Vec<bool> {
+    let mut marks = vec![false; chars.len()];
+    let mut i = 0;
+    while i < chars.len() {
+        let mut end = i;
+        while chars[end] == '.'
+            && chars.get(end + 1) == Some(&' ')
+            && chars.get(end + 2) == Some(&'.')
+        {
+            end += 2;
+        }
+        if end - i >= 4 {
+            marks[i..=end].iter_mut().for_each(|m| *m = true);
+            i = end + 1;
+        } else {
+            i += 1;
+        }
+    }
+    marks
+}
+
+/// Replace the spaces between labels like "p." or "Vol." and the numbers
+/// following them with non-breaking spaces.
+///
+/// A label is a word of up to four letters that starts in lowercase and ends
+/// with a period, "Vol.", "No.", or a section or paragraph sign.
+pub(super) fn bind_labels(children: &mut ElemChildren) {
+    let mut segments = vec![Vec::new()];
+    collect_texts(children, &mut segments);
+    for segment in segments {
+        bind_segment(segment);
+    }
+}
+
+fn bind_segment(texts: Vec<&mut String>) {
+    let chars: Vec<char> = texts.iter().flat_map(|t| t.chars()).collect();
+    let mut bind = vec![false; chars.len()];
+
+    for (i, &c) in chars.iter().enumerate() {
+        if c != ' ' {
+            continue;
+        }
+
+        let start = chars[..i]
+            .iter()
+            .rposition(|&c| c.is_whitespace() || c == '(')
+            .map_or(0, |p| p + 1);
+        let word = &chars[start..i];
+        let next = &chars[i + 1..];
+        bind[i] = if next.first().is_some_and(char::is_ascii_digit) {
+            is_label(word)
+        } else {
+            // Keep a spaced "n. d." together.
+            word == ['n', '.'] && next.starts_with(&['d', '.'])
+        };
+    }
+
+    let mut offset = 0;
+    for text in texts {
+        let len = text.chars().count();
+        let bind = &bind[offset..offset + len];
+        if bind.contains(&true) {
+            *text = text
+                .chars()
+                .zip(bind)
+                .map(|(c, &b)| if b { '\u{a0}' } else { c })
+                .collect();
+        }
+        offset += len;
+    }
+}
+
+fn is_label(word: &[char]) -> bool {
+    match word {
+        ['§' | '¶'] | ['§', '§'] | ['¶', '¶'] => true,
+        ['V', 'o', 'l', '.'] | ['N', 'o', '.'] => true,
+        [first, letters @ .., '.'] => {
+            first.is_lowercase()
+                && letters.len() < 4
+                && letters.iter().all(|c| c.is_alphabetic())
+        }
+        _ => false,
+    }
+}
+
+/// Add or remove the period at the end of a bibliography entry as requested
+/// by the options.
+pub(super) fn apply_period_options(
+    children: &mut ElemChildren,
+    options: &StyleOptions,
+    locale: &LocaleCode,
+) {
+    if options.trailing_period.is_none() && options.period_after_url.is_none() {
+        return;
+    }
+
+    let children = innermost_last(children);
+    // Tidying may have emptied the period of the style after an abbreviation.
+    while matches!(children.0.last(), Some(ElemChild::Text(t)) if t.text.is_empty()) {
+        children.0.pop();
+    }
+    let len = children.0.len();
+    let after_link = len >= 2
+        && matches!(children.0[len - 2], ElemChild::Link { .. })
+        && matches!(&children.0[len - 1], ElemChild::Text(t) if t.text == ".");
+    // The period may be an affix of its own, following the abbreviation.
+    let english = locale.is_english();
+    let abbreviated = match children.0.last() {
+        Some(ElemChild::Text(t)) if t.text == "." && len >= 2 => {
+            last_text_child(&children.0[..len - 1]).is_some_and(|prev| {
+                ends_with_abbreviation(&format!("{}.", prev.text), english)
+            })
+        }
+        Some(ElemChild::Text(t)) => ends_with_abbreviation(&t.text, english),
+        _ => false,
+    };
+
+    match children.0.last_mut() {
+        Some(ElemChild::Link { .. }) if options.period_after_url == Some(true) => {
+            children.0.push(ElemChild::Text(Formatted {
+                text: ".".into(),
+                formatting: Formatting::default(),
+            }));
+        }
+        Some(ElemChild::Text(_))
+            if after_link && options.period_after_url == Some(false) =>
+        {
+            children.0.pop();
+        }
+        Some(ElemChild::Text(t)) if !after_link => match options.trailing_period {
+            Some(false)
+                if t.text.ends_with('.') && !t.text.ends_with("..") && !abbreviated =>
+            {
+                t.text.pop();
+            }
+            Some(true) if !t.text.ends_with(['.', '?', '!']) => t.text.push('.'),
+            _ => {}
+        },
+        _ => {}
+    }
+}
+
+/// Whether the text ends with an abbreviation that keeps its period. These
+/// are initials, like "Smith, J." or "n.d.", and in English, the words in
+/// [`ABBREVIATIONS`], like "Inc." or "3 vols.".
+fn ends_with_abbreviation(text: &str, english: bool) -> bool {
+    let Some(rest) = text.strip_suffix('.') else { return false };
+    let mut words = rest
+        .rsplit(|c: char| c.is_whitespace() || c == '(' || c == '[')
+        .filter(|w| !w.is_empty());
+    let word = words.next().unwrap_or(rest);
+    let is_letter = |s: &str| {
+        let mut chars = s.chars();
+        chars.next().is_some_and(char::is_alphabetic) && chars.next().is_none()
+    };
+    let is_initials = |s: &str| s.split('.').all(is_letter);
+
+    if english && ABBREVIATIONS.binary_search(&word.to_lowercase().as_str()).is_ok() {
+        return true;
+    }
+
+    if word.contains('.') {
+        return is_initials(word);
+    }
+
+    // A single letter is an initial if it follows a comma or another initial,
+    // as in "Smith, J." or "R. J.", but not a word, as in "Part A.".
+    is_letter(word)
+        && words.next().is_none_or(|prev| {
+            prev.ends_with(',') || prev.strip_suffix('.').is_some_and(is_initials)
+        })
+}
+
+/// The children that contain the last leaf of the tree.
+fn innermost_last(children: &mut ElemChildren) -> &mut ElemChildren {
+    let descend = matches!(
+        children.0.last(),
+        Some(ElemChild::Elem(e)) if !e.children.0.is_empty()
+    );
+
+    if !descend {
+        return children;
+    }
+
+    let Some(ElemChild::Elem(e)) = children.0.last_mut() else { unreachable!() };
+    innermost_last(&mut e.children)
+}
+
+/// The quotation marks to use for straight quotes.
+pub(super) struct QuoteMarks<'a> {
+    /// Replaces an opening `"`.
+    pub open: &'a str,
+    /// Replaces a closing `"`.
+    pub close: &'a str,
+    /// Replaces an opening `'`.
+    pub open_inner: &'a str,
+    /// Replaces a closing `'`.
+    pub close_inner: &'a str,
+}
+
+/// Replace straight quotes with typographic quotation marks and apostrophes
+/// and hyphens in numeric ranges with en dashes.
+///
+/// Verbatim and math chunks are left alone. A straight quote opens a
+/// quotation if it follows a space, an opening bracket, a dash, or another
+/// opening quotation mark. A single quote between letters or after a closing
+/// position that has no open single quotation becomes an apostrophe.
+pub(super) fn smarten(string: &mut ChunkedString, marks: &QuoteMarks) {
+    let mut prev: Option<char> = None;
+    let mut after_open = false;
+    let mut open: Vec<char> = Vec::new();
+
+    for chunk in &mut string.0 {
+        if chunk.kind != ChunkKind::Normal {
+            if let Some(last) = chunk.value.chars().next_back() {
+                prev = Some(last);
+                after_open = false;
+            }
+            continue;
+        }
+
+        let chars: Vec<char> = chunk.value.chars().collect();
+        let mut out = String::with_capacity(chunk.value.len());
+        for (i, &c) in chars.iter().enumerate() {
+            let next = chars.get(i + 1).copied();
+            let opening = after_open
+                || prev.is_none_or(|p| {
+                    p.is_whitespace()
+                        || matches!(p, '(' | '[' | '{' | '-' | '–' | '—' | '/')
+                });
+            after_open = false;
+
+            match c {
+                '"' if opening => {
+                    open.push('"');
+                    out.push_str(marks.open);
+                    after_open = true;
+                }
+                '"' => {
+                    if let Some(pos) = open.iter().rposition(|&q| q == '"') {
+                        open.truncate(pos);
+                    }
+                    out.push_str(marks.close);
+                }
+                '\'' if opening && next.is_some_and(|n| !n.is_numeric()) => {
+                    open.push('\'');
+                    out.push_str(marks.open_inner);
+                    after_open = true;
+                }
+                '\'' if open.last() == Some(&'\'')
+                    && next.is_none_or(|n| !n.is_alphanumeric()) =>
+                {
+                    open.pop();
+                    out.push_str(marks.close_inner);
+                }
+                '\'' => out.push('’'),
+                '-' if prev.is_some_and(|p| p.is_ascii_digit())
+                    && next.is_some_and(|n| n.is_ascii_digit()) =>
+                {
+                    out.push('–')
+                }
+                c => out.push(c),
+            }
+
+            prev = Some(c);
+        }
+
+        chunk.value = out;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::csl::{Formatted, Formatting};
+
+    fn tidy(texts: &[&str]) -> String {
+        let mut children = ElemChildren(
+            texts
+                .iter()
+                .map(|t| {
+                    ElemChild::Text(Formatted {
+                        text: t.to_string(),
+                        formatting: Formatting::default(),
+                    })
+                })
+                .collect(),
+        );
+        tidy_punctuation(&mut children);
+
+        let mut buf = String::new();
+        children.write_buf(&mut buf, crate::BufWriteFormat::Plain).unwrap();
+        buf
+    }
+
+    #[test]
+    fn known_failures() {
+        assert_eq!(tidy(&["Smith, J.", ". ", "Title", "."]), "Smith, J. Title.");
+        assert_eq!(tidy(&["Title. ", ". Publisher."]), "Title. Publisher.");
+        assert_eq!(tidy(&["What is it?", ". "]), "What is it? ");
+        assert_eq!(tidy(&["Wow!", "."]), "Wow!");
+        assert_eq!(tidy(&["In ", " ", "Book", " , ", "pp. 5"]), "In Book, pp. 5");
+        assert_eq!(tidy(&["Doe", ",", ", 2020"]), "Doe, 2020");
+        assert_eq!(tidy(&["Trailing ", "."]), "Trailing.");
+        assert_eq!(tidy(&["And then...", " More"]), "And then... More");
+    }
+
+    #[test]
+    fn spaced_ellipses() {
+        assert_eq!(tidy(&["Wait . . . what happened", "."]), "Wait . . . what happened.");
+        assert_eq!(
+            tidy(&["It ended . . . .", " ", "Penguin"]),
+            "It ended . . . . Penguin"
+        );
+        assert_eq!(
+            tidy(&["Wait . . . what", " . ", "Penguin"]),
+            "Wait . . . what. Penguin"
+        );
+    }
+
+    #[test]
+    fn trailing_abbreviations() {
+        let end_in = |locale: &str, texts: &[&str], trailing_period| {
+            let mut children = ElemChildren(
+                texts
+                    .iter()
+                    .map(|t| {
+                        ElemChild::Text(Formatted {
+                            text: t.to_string(),
+                            formatting: Formatting::default(),
+                        })
+                    })
+                    .collect(),
+            );
+            tidy_punctuation(&mut children);
+            let options = StyleOptions { trailing_period, ..Default::default() };
+            apply_period_options(&mut children, &options, &LocaleCode(locale.into()));
+            format!("{:#}", children)
+        };
+        let end =
+            |texts: &[&str], trailing_period| end_in("en-US", texts, trailing_period);
+
+        // The period of the style is not doubled.
+        assert_eq!(end(&["Acme Inc.", "."], None), "Acme Inc.");
+        assert_eq!(end(&["(2nd ed.)", "."], None), "(2nd ed.).");
+        assert_eq!(end(&["3 Vols.", "."], Some(true)), "3 Vols.");
+
+        // Removing the period keeps that of an abbreviation.
+        assert_eq!(end(&["Acme Inc.", "."], Some(false)), "Acme Inc.");
+        assert_eq!(end(&["Smith, J., ed."], Some(false)), "Smith, J., ed.");
+        assert_eq!(
+            end(&["Collected works. 3 vols."], Some(false)),
+            "Collected works. 3 vols."
+        );
+        assert_eq!(end(&["Works (n.d.)."], Some(false)), "Works (n.d.)");
+        assert_eq!(end(&["Penguin", "."], Some(false)), "Penguin");
+        assert_eq!(end(&["Edited works."], Some(false)), "Edited works");
+
+        // Only initials and the listed abbreviations keep their period.
+        assert_eq!(end(&["Smith, J. R."], Some(false)), "Smith, J. R.");
+        assert_eq!(end(&["See Part A."], Some(false)), "See Part A");
+        assert_eq!(end(&["https://example.com."], Some(false)), "https://example.com");
+
+        // The English abbreviations are not recognized in other languages.
+        assert_eq!(end_in("de-DE", &["Acme Inc."], Some(false)), "Acme Inc");
+        assert_eq!(end_in("de-DE", &["Schmidt, J."], Some(false)), "Schmidt, J.");
+    }
+
+    fn smart(s: &str) -> String {
+        let mut string = ChunkedString::from(s.to_string());
+        smarten(
+            &mut string,
+            &QuoteMarks {
+                open: "“",
+                close: "”",
+                open_inner: "‘",
+                close_inner: "’",
+            },
+        );
+        string.to_string()
+    }
+
+    #[test]
+    fn smart_quotes() {
+        assert_eq!(
+            smart(r#"He said "it's 'the end' of it" in '98"#),
+            "He said “it’s ‘the end’ of it” in ’98"
+        );
+        assert_eq!(smart(r#""'Quoted' within""#), "“‘Quoted’ within”");
+        assert_eq!(smart("The writers' room"), "The writers’ room");
+        assert_eq!(smart("A well-known war, 1914-1918"), "A well-known war, 1914–1918");
+    }
+
+    #[test]
+    fn non_breaking_labels() {
+        let mut children = ElemChildren(vec![ElemChild::Text(Formatted {
+            text: "Doe, 2020, p. 5; Vol. 3, No. 2 (pp. 1-9), § 12, in 2020 and 3; \
+                   Roe, n. d., and 3 n. 4"
+                .into(),
+            formatting: Formatting::default(),
+        })]);
+        bind_labels(&mut children);
+
+        let mut buf = String::new();
+        children.write_buf(&mut buf, crate::BufWriteFormat::Plain).unwrap();
+        assert_eq!(
+            buf,
+            "Doe, 2020, p.\u{a0}5; Vol.\u{a0}3, No.\u{a0}2 (pp.\u{a0}1-9), \
+             §\u{a0}12, in 2020 and 3; Roe, n.\u{a0}d., and 3 n.\u{a0}4"
+        );
+    }
+
+    #[test]
+    fn links_are_barriers() {
+        let url = "https://example.com/a..b";
+        let mut children = ElemChildren(vec![
+            ElemChild::Text(Formatted {
+                text: "See ".into(),
+                formatting: Formatting::default(),
+            }),
+            ElemChild::Link {
+                text: Formatted {
+                    text: url.into(),
+                    formatting: Formatting::default(),
+                },
+                url: url.into(),
+            },
+            ElemChild::Text(Formatted {
+                text: ".".into(),
+                formatting: Formatting::default(),
+            }),
+        ]);
+        tidy_punctuation(&mut children);
+
+        let mut buf = String::new();
+        children.write_buf(&mut buf, crate::BufWriteFormat::Plain).unwrap();
+        assert_eq!(buf, "See https://example.com/a..b.");
+    }
+}
@@ -1,5 +1,6 @@
 use std::borrow::Cow;
 use std::fmt::Write;
+use std::mem;
 use std::str::FromStr;
 
 use citationberg::taxonomy::{
@@ -7,17 +8,19 @@ use citationberg::taxonomy::{
 };
 use citationberg::{
     ChooseBranch, CslMacro, DateDayForm, DateMonthForm, DatePartName, DateParts,
-    DateStrongAnyForm, LabelPluralize, LayoutRenderingElement, LongShortForm, NumberForm,
-    TestPosition, TextCase, ToAffixes, ToFormatting,
+    DateStrongAnyForm, LabelPluralize, LayoutRenderingElement, LocaleCode, LongShortForm,
+    NumberForm, TestPosition, TextCase, ToAffixes, ToFormatting,
 };
 use citationberg::{TermForm, TextTarget};
 
 use crate::csl::taxonomy::NumberVariableResult;
 use crate::lang::{Case, SentenceCase, TitleCase};
-use crate::types::{ChunkedString, Date, MaybeTyped, Numeric};
+use crate::types::{
+    self, group_digits, ChunkedString, Era, MaybeTyped, Numeric, StringChunk,
+};
 
 use super::taxonomy::EntryLike;
-use super::{Context, ElemMeta, IbidState, SpecialForm};
+use super::{Context, ElemMeta, IbidState, LocatorPayload, SpecialForm, SpecificLocator};
 
 pub mod names;
 
@@ -46,7 +49,23 @@ impl RenderCsl for citationberg::Text {
             _ => true,
         };
 
-        let affix_loc = print_affixes.then(|| ctx.apply_prefix(&self.affixes));
+        // A DOI that follows a resolver URL is printed as a single link with
+        // the full URL as its label, like "https://doi.org/10.1000/182".
+        let doi_resolver = match &target {
+            ResolvedTextTarget::StandardVariable(StandardVariable::DOI, _) => {
+                self.affixes.prefix.as_deref().and_then(split_doi_resolver)
+            }
+            _ => None,
+        };
+        let affixes = match doi_resolver {
+            Some((before, _)) => Cow::Owned(citationberg::Affixes {
+                prefix: (!before.is_empty()).then(|| before.to_string()),
+                suffix: self.affixes.suffix.clone(),
+            }),
+            None => Cow::Borrowed(&self.affixes),
+        };
+
+        let affix_loc = print_affixes.then(|| ctx.apply_prefix(&affixes));
 
         if self.quotes {
             ctx.push_quotes();
@@ -62,8 +81,20 @@ impl RenderCsl for citationberg::Text {
                     ctx.push_link(&val, str);
                 }
                 StandardVariable::DOI => {
-                    let url = format!("https://doi.org/{}", val.to_str());
-                    ctx.push_link(&val, url);
+                    let doi = doi_case(&val.to_str(), ctx.style.options.lowercase_doi);
+                    match doi_resolver {
+                        Some((_, resolver)) => {
+                            let url = format!("{resolver}{doi}");
+                            ctx.push_link(
+                                &StringChunk::verbatim(url.clone()).into(),
+                                url,
+                            );
+                        }
+                        None => {
+                            let url = format!("https://doi.org/{doi}");
+                            ctx.push_link(&StringChunk::verbatim(doi).into(), url);
+                        }
+                    }
                 }
                 StandardVariable::PMID => {
                     let url =
@@ -77,9 +108,18 @@ impl RenderCsl for citationberg::Text {
                     );
                     ctx.push_link(&val, url);
                 }
-                _ => ctx.push_chunked(&val),
+                _ => {
+                    let val = ctx.smarten_title(var, val);
+                    ctx.push_chunked(&val)
+                }
             },
-            ResolvedTextTarget::NumberVariable(_, n) => match n {
+            ResolvedTextTarget::NumberVariable(var, n) => match n {
+                NumberVariableResult::Regular(MaybeTyped::Typed(n))
+                    if groups_digits(ctx, var) =>
+                {
+                    let separator = digit_group_separator(&ctx.style.locale());
+                    ctx.push_str(&group_digits(&n.to_string(), separator))
+                }
                 NumberVariableResult::Regular(n) => ctx.push_str(&n.to_str()),
                 NumberVariableResult::Transparent(n) => ctx.push_transparent(n),
             },
@@ -107,13 +147,17 @@ impl RenderCsl for citationberg::Text {
         }
 
         if let Some(affix_loc) = affix_loc {
-            ctx.apply_suffix(&self.affixes, affix_loc);
+            ctx.apply_suffix(&affixes, affix_loc);
         }
-        ctx.commit_elem(
-            depth,
-            self.display,
-            matches!(&self.target, TextTarget::Variable { .. }).then_some(ElemMeta::Text),
-        );
+        let meta = match &self.target {
+            TextTarget::Variable {
+                var: Variable::Number(NumberVariable::CitationNumber),
+                ..
+            } => Some(ElemMeta::CitationNumber),
+            TextTarget::Variable { .. } => Some(ElemMeta::Text),
+            _ => None,
+        };
+        ctx.commit_elem(depth, self.display, meta);
     }
 
     fn will_render<T: EntryLike>(&self, ctx: &mut Context<T>, var: Variable) -> bool {
@@ -209,6 +253,39 @@ impl RenderCsl for citationberg::Number {
         let gender = ctx.gender(self.variable.into());
 
         match value {
+            Some(NumberVariableResult::Regular(MaybeTyped::Typed(num)))
+                if self.form == NumberForm::Numeric
+                    && groups_digits(ctx, self.variable) =>
+            {
+                let mut buf = String::new();
+                match num.range() {
+                    Some(range) if self.variable == NumberVariable::Page => ctx
+                        .style
+                        .csl
+                        .settings
+                        .page_range_format
+                        .unwrap_or_default()
+                        .format(
+                            range,
+                            &mut buf,
+                            ctx.term(
+                                OtherTerm::PageRangeDelimiter.into(),
+                                TermForm::default(),
+                                false,
+                            )
+                            .or(Some("–")),
+                        )
+                        .unwrap(),
+                    _ if num.will_transform() => num
+                        .as_ref()
+                        .with_form(&mut buf, self.form, gender, ctx.ordinal_lookup())
+                        .unwrap(),
+                    _ => write!(buf, "{}", num).unwrap(),
+                }
+
+                let separator = digit_group_separator(&ctx.style.locale());
+                ctx.push_str(&group_digits(&buf, separator));
+            }
             Some(NumberVariableResult::Regular(MaybeTyped::Typed(num)))
                 if num.will_transform() =>
             {
@@ -283,6 +360,51 @@ impl RenderCsl for citationberg::Number {
     }
 }
 
+/// Whether the digits of the variable are grouped according to the style
+/// options. Identifiers are never grouped.
+fn groups_digits<T: EntryLike>(ctx: &Context<T>, variable: NumberVariable) -> bool {
+    ctx.style.options.group_digits
+        && matches!(
+            variable,
+            NumberVariable::Page
+                | NumberVariable::PageFirst
+                | NumberVariable::Locator
+                | NumberVariable::Volume
+                | NumberVariable::NumberOfPages
+                | NumberVariable::NumberOfVolumes
+        )
+}
+
+/// Split a prefix that ends with a DOI resolver, like "https://doi.org/", into
+/// the text before the resolver and the resolver.
+fn split_doi_resolver(prefix: &str) -> Option<(&str, &str)> {
+    let start = prefix.rfind("http")?;
+    let resolver = &prefix[start..];
+    resolver.ends_with("doi.org/").then(|| (&prefix[..start], resolver))
+}
+
+/// Lowercase the registrant prefix of a DOI, like "10.ABC" in
+/// "10.ABC/Xyz", or the whole DOI. DOIs are case-insensitive, but the
+/// suffix keeps its case unless `lowercase` is set because publishers often
+/// print it in mixed case.
+fn doi_case(doi: &str, lowercase: bool) -> String {
+    let split = if lowercase { doi.len() } else { doi.find('/').unwrap_or(0) };
+    let mut res = doi[..split].to_lowercase();
+    res.push_str(&doi[split..]);
+    res
+}
+
+/// The separator between groups of three digits in a locale.
+fn digit_group_separator(locale: &LocaleCode) -> &'static str {
+    match locale.0.split('-').next().unwrap_or_default() {
+        "de" | "da" | "el" | "es" | "id" | "it" | "nl" | "pt" | "ro" | "sl" | "tr" => ".",
+        "cs" | "fi" | "fr" | "hu" | "nb" | "nn" | "pl" | "ru" | "sk" | "sv" | "uk" => {
+            "\u{202f}"
+        }
+        _ => ",",
+    }
+}
+
 impl RenderCsl for citationberg::Label {
     fn render<T: EntryLike>(&self, ctx: &mut Context<T>) {
         match ctx.instance.kind {
@@ -297,19 +419,24 @@ impl RenderCsl for citationberg::Label {
             _ => {}
         }
 
-        // Never yield a label if the locator is set to custom.
-        if self.variable == NumberVariable::Locator
-            && ctx
-                .instance
-                .cite_props
-                .speculative
-                .locator
-                .map_or(false, |l| l.0 == Locator::Custom)
-        {
-            return;
+        // Custom locators only yield the label they bring along.
+        let mut custom_label = None;
+        if self.variable == NumberVariable::Locator {
+            match ctx.instance.cite_props.speculative.locator {
+                Some(SpecificLocator(_, LocatorPayload::Labeled { label, .. })) => {
+                    custom_label = Some(label)
+                }
+                Some(SpecificLocator(Locator::Custom, _)) => return,
+                _ => {}
+            }
         }
 
-        let Some(variable) = ctx.resolve_number_variable(self.variable) else {
+        // A label does not render its variable, so a substitute that labels
+        // the number, for example, still renders the number after it.
+        let suppressing = mem::take(&mut ctx.writing.suppress_queried_variables);
+        let variable = ctx.resolve_number_variable(self.variable);
+        ctx.writing.suppress_queried_variables = suppressing;
+        let Some(variable) = variable else {
             return;
         };
 
@@ -326,8 +453,8 @@ impl RenderCsl for citationberg::Label {
             },
         };
 
-        let content = ctx
-            .term(Term::from(self.variable), self.label.form, plural)
+        let content = custom_label
+            .or_else(|| ctx.term(Term::from(self.variable), self.label.form, plural))
             .unwrap_or_default();
 
         render_label_with_var(&self.label, ctx, content);
@@ -438,31 +565,99 @@ impl RenderCsl for citationberg::Date {
 
         let parts = self.parts.or(base.and_then(|b| b.parts)).unwrap_or_default();
 
-        // TODO: Date ranges
+        let date_parts: Vec<_> = base
+            .unwrap_or(self)
+            .date_part
+            .iter()
+            .filter(|part| match part.name {
+                DatePartName::Month => parts.has_month(),
+                DatePartName::Day => parts.has_day(),
+                DatePartName::Year => true,
+            })
+            .collect();
+
+        // A range renders the parts up to the largest one in which its start
+        // and end differ for both of them, joined by the range delimiter of
+        // that part. The other parts are only rendered once.
+        let range = date.end.and_then(|end| {
+            let largest = if end.year != date.year {
+                DatePartName::Year
+            } else if end.month != date.month {
+                DatePartName::Month
+            } else if end.day != date.day {
+                DatePartName::Day
+            } else {
+                return None;
+            };
+            Some((types::DateParts::from(end), largest))
+        });
+        let is_ranged = |part: &&citationberg::DatePart| {
+            range.as_ref().is_some_and(|(_, largest)| {
+                date_part_rank(part.name) <= date_part_rank(*largest)
+            })
+        };
+
+        let split = date_parts.iter().position(is_ranged).unwrap_or(date_parts.len());
+        let (before, after) = date_parts.split_at(split);
+        let (ranged, shared): (Vec<_>, Vec<_>) =
+            after.iter().copied().partition(is_ranged);
+
         let mut last_was_empty = true;
-        for part in &base.unwrap_or(self).date_part {
-            match part.name {
-                DatePartName::Month if !parts.has_month() => continue,
-                DatePartName::Day if !parts.has_day() => continue,
-                _ => {}
-            }
+        let date = date.as_ref();
+        render_date_parts(
+            self,
+            base,
+            before.iter().copied(),
+            date,
+            ctx,
+            first,
+            &mut last_was_empty,
+        );
 
-            let cursor = ctx.writing.len();
-            if !last_was_empty {
-                if let Some(delim) = &self.delimiter {
-                    ctx.push_str(delim);
-                }
+        if let (Some((end, largest)), false) = (&range, ranged.is_empty()) {
+            let delim = self
+                .date_part
+                .iter()
+                .chain(date_parts.iter().copied())
+                .filter(|p| p.name == *largest)
+                .find_map(|p| p.range_delimiter.as_deref())
+                .unwrap_or(citationberg::DatePart::DEFAULT_DELIMITER);
+
+            // The affixes at the join would separate the delimiter from the
+            // parts around it.
+            let mut start_parts: Vec<_> = ranged.iter().map(|&p| p.clone()).collect();
+            let mut end_parts = start_parts.clone();
+            if let Some(part) = start_parts.last_mut() {
+                part.affixes.suffix = None;
+            }
+            if let Some(part) = end_parts.first_mut() {
+                part.affixes.prefix = None;
             }
 
-            let over_ride = base
-                .is_some()
-                .then(|| self.date_part.iter().find(|p| p.name == part.name))
-                .flatten();
-
-            render_date_part(part, &date, ctx, over_ride, first);
-            last_was_empty = cursor == ctx.writing.len();
+            render_date_parts(
+                self,
+                base,
+                &start_parts,
+                date,
+                ctx,
+                false,
+                &mut last_was_empty,
+            );
+            ctx.push_str(delim);
+            last_was_empty = true;
+            render_date_parts(
+                self,
+                base,
+                &end_parts,
+                end,
+                ctx,
+                first,
+                &mut last_was_empty,
+            );
         }
 
+        render_date_parts(self, base, shared, date, ctx, first, &mut last_was_empty);
+
         ctx.pop_case(cidx);
         ctx.apply_suffix(&self.affixes, affix_loc);
         ctx.commit_elem(depth, self.display, Some(ElemMeta::Date));
@@ -484,20 +679,64 @@ impl RenderCsl for citationberg::Date {
     }
 }
 
+/// Render the parts of a date, separated by the delimiter of the date.
+fn render_date_parts<'p, T: EntryLike>(
+    elem: &citationberg::Date,
+    base: Option<&citationberg::Date>,
+    parts: impl IntoIterator<Item = &'p citationberg::DatePart>,
+    date: &types::DateParts,
+    ctx: &mut Context<T>,
+    first: bool,
+    last_was_empty: &mut bool,
+) {
+    for part in parts {
+        let cursor = ctx.writing.len();
+        if !*last_was_empty {
+            if let Some(delim) = &elem.delimiter {
+                ctx.push_str(delim);
+            }
+        }
+
+        let over_ride = base
+            .is_some()
+            .then(|| elem.date_part.iter().find(|p| p.name == part.name))
+            .flatten();
+
+        render_date_part(part, date, ctx, over_ride, first);
+        *last_was_empty = cursor == ctx.writing.len();
+    }
+}
+
+/// The size of a date part, from the day as the smallest to the year.
+fn date_part_rank(name: DatePartName) -> u8 {
+    match name {
+        DatePartName::Day => 0,
+        DatePartName::Month => 1,
+        DatePartName::Year => 2,
+    }
+}
+
 fn render_date_part<T: EntryLike>(
     date_part: &citationberg::DatePart,
-    date: &Date,
+    date: &types::DateParts,
     ctx: &mut Context<T>,
     over_ride: Option<&citationberg::DatePart>,
     first: bool,
 ) {
+    // Dates like "Spring 2020" show their season in place of the month.
+    let season = match date_part.name {
+        DatePartName::Month if date.month.is_none() => date.season,
+        _ => None,
+    };
+
     let Some(val) = (match date_part.name {
         DatePartName::Day => date.day.map(|i| i as i32 + 1),
         DatePartName::Month => date.month.map(|i| i as i32 + 1),
-        DatePartName::Year => {
-            Some(if date.year > 0 { date.year } else { date.year.abs() + 1 })
-        }
+        DatePartName::Year => Some(date.era_year()),
     }) else {
+        if let Some(season) = season {
+            render_season(date_part, season, ctx, over_ride);
+        }
         return;
     };
 
@@ -584,7 +823,10 @@ fn render_date_part<T: EntryLike>(
 
         if let DateStrongAnyForm::Year(_) = form {
             if date.year < 1000 {
-                ctx.push_str(if date.year < 0 { "BC" } else { "AD" });
+                ctx.push_str(match date.era() {
+                    Era::BeforeCommonEra => "BC",
+                    Era::CommonEra => "AD",
+                });
             }
         }
     }
@@ -603,6 +845,40 @@ fn render_date_part<T: EntryLike>(
     ctx.pop_format(idx);
 }
 
+/// Render the season term in place of the month date part.
+fn render_season<T: EntryLike>(
+    date_part: &citationberg::DatePart,
+    season: types::Season,
+    ctx: &mut Context<T>,
+    over_ride: Option<&citationberg::DatePart>,
+) {
+    if ctx.instance.kind == Some(SpecialForm::OnlyYearSuffix) {
+        return;
+    }
+
+    let Some(term) = OtherTerm::season(season as u8)
+        .and_then(|s| ctx.term(s.into(), TermForm::Long, false))
+    else {
+        return;
+    };
+
+    let formatting = over_ride
+        .map(|p| p.formatting.apply(date_part.formatting))
+        .unwrap_or(date_part.formatting);
+
+    let idx = ctx.push_format(formatting);
+    let affix_loc = ctx.apply_prefix(&date_part.affixes);
+    ctx.may_strip_periods(date_part.strip_periods);
+    let cidx = ctx.push_case(over_ride.and_then(|o| o.text_case).or(date_part.text_case));
+
+    ctx.push_str(term);
+
+    ctx.pop_case(cidx);
+    ctx.stop_stripping_periods();
+    ctx.apply_suffix(&date_part.affixes, affix_loc);
+    ctx.pop_format(idx);
+}
+
 /// Render the year suffix if it is set and the style will not render it
 /// explicitly.
 fn render_year_suffix_implicitly<T: EntryLike>(ctx: &mut Context<T>) {
@@ -618,21 +894,12 @@ fn render_year_suffix_implicitly<T: EntryLike>(ctx: &mut Context<T>) {
 
 impl RenderCsl for citationberg::Choose {
     fn render<T: EntryLike>(&self, ctx: &mut Context<T>) {
-        for branch in self.branches() {
-            if branch.match_.test(BranchConditionIter::from_branch(branch, ctx)) {
-                render_with_delimiter(&branch.children, self.delimiter.as_deref(), ctx);
-                return;
-            }
-        }
-
-        if let Some(fallthrough) = &self.otherwise {
-            render_with_delimiter(&fallthrough.children, self.delimiter.as_deref(), ctx);
-        }
+        render_choose(self, self.delimiter.as_deref(), ctx);
     }
 
     fn will_render<T: EntryLike>(&self, ctx: &mut Context<T>, var: Variable) -> bool {
         for branch in self.branches() {
-            if branch.match_.test(BranchConditionIter::from_branch(branch, ctx)) {
+            if branch_matches(branch, ctx) {
                 return branch.children.iter().any(|c| c.will_render(ctx, var));
             }
         }
@@ -645,6 +912,26 @@ impl RenderCsl for citationberg::Choose {
     }
 }
 
+/// Render the matching branch of a choose element, separating its children
+/// with the delimiter of the choose or, failing that, of the enclosing group.
+fn render_choose<T: EntryLike>(
+    choose: &citationberg::Choose,
+    delimiter: Option<&str>,
+    ctx: &mut Context<T>,
+) {
+    let delimiter = choose.delimiter.as_deref().or(delimiter);
+    for branch in choose.branches() {
+        if branch_matches(branch, ctx) {
+            render_with_delimiter(&branch.children, delimiter, ctx);
+            return;
+        }
+    }
+
+    if let Some(fallthrough) = &choose.otherwise {
+        render_with_delimiter(&fallthrough.children, delimiter, ctx);
+    }
+}
+
 fn render_with_delimiter<T: EntryLike>(
     children: &[LayoutRenderingElement],
     delimiter: Option<&str>,
@@ -675,7 +962,9 @@ fn render_with_delimiter<T: EntryLike>(
             LayoutRenderingElement::Label(label) => label.render(ctx),
             LayoutRenderingElement::Date(date) => date.render(ctx),
             LayoutRenderingElement::Names(names) => names.render(ctx),
-            LayoutRenderingElement::Choose(choose) => choose.render(ctx),
+            LayoutRenderingElement::Choose(choose) => {
+                render_choose(choose, delimiter, ctx)
+            }
             LayoutRenderingElement::Group(_group) => _group.render(ctx),
         }
 
@@ -741,6 +1030,21 @@ impl Iterator for BranchConditionPos {
     }
 }
 
+/// Whether the conditions of a branch are met.
+///
+/// Testing a variable does not render it, so a substitute that tests the
+/// title, for example, does not suppress it for the rest of the entry.
+fn branch_matches<T: EntryLike>(branch: &ChooseBranch, ctx: &mut Context<T>) -> bool {
+    let suppressing = mem::take(&mut ctx.writing.suppress_queried_variables);
+    // Testing a variable is not a call of the variable, so the enclosing
+    // group is not suppressed because a tested variable is empty.
+    let info = ctx.writing.push_usage_info();
+    let res = branch.match_.test(BranchConditionIter::from_branch(branch, ctx));
+    ctx.writing.pop_usage_info(info);
+    ctx.writing.suppress_queried_variables = suppressing;
+    res
+}
+
 struct BranchConditionIter<'a, 'b, T: EntryLike> {
     cond: &'a ChooseBranch,
     ctx: &'a mut Context<'b, T>,
@@ -793,10 +1097,21 @@ impl<'a, 'b, T: EntryLike> Iterator for BranchConditionIter<'a, 'b, T> {
                             .resolve_standard_variable(LongShortForm::default(), var)
                             .map(|v| Numeric::from_str(&v.to_string()).is_ok())
                             .unwrap_or_default(),
-                        Variable::Number(var) => matches!(
-                            self.ctx.resolve_number_variable(var),
-                            Some(NumberVariableResult::Regular(MaybeTyped::Typed(_)))
-                        ),
+                        Variable::Number(var) => {
+                            match self.ctx.resolve_number_variable(var) {
+                                Some(NumberVariableResult::Regular(
+                                    MaybeTyped::Typed(_),
+                                )) => true,
+                                // Report and patent numbers are kept verbatim
+                                // but still count as numeric.
+                                Some(NumberVariableResult::Regular(
+                                    MaybeTyped::String(s),
+                                )) if var == NumberVariable::Number => {
+                                    Numeric::from_str(&s).is_ok()
+                                }
+                                _ => false,
+                            }
+                        }
                         _ => false,
                     })
                 } else {
@@ -814,11 +1129,7 @@ impl<'a, 'b, T: EntryLike> Iterator for BranchConditionIter<'a, 'b, T> {
                     let var = vars[self.idx];
                     self.idx += 1;
 
-                    Some(
-                        self.ctx
-                            .resolve_date_variable(var)
-                            .map_or(false, |d| d.approximate),
-                    )
+                    Some(self.ctx.resolve_date_variable(var).map_or(false, |d| d.circa))
                 } else {
                     self.next_case();
                     self.next()
@@ -863,9 +1174,9 @@ impl<'a, 'b, T: EntryLike> Iterator for BranchConditionIter<'a, 'b, T> {
                     Some(match spec_pos {
                         TestPosition::First => props.certain.is_first,
                         TestPosition::Subsequent => !props.certain.is_first,
-                        TestPosition::Ibid => props.speculative.ibid == IbidState::Ibid,
+                        TestPosition::Ibid => props.speculative.ibid.is_ibid(),
                         TestPosition::IbidWithLocator => {
-                            props.speculative.ibid.is_ibid_with_locator()
+                            props.speculative.ibid == IbidState::IbidWithLocator
                         }
                         TestPosition::NearNote => props.certain.is_near_note,
                     })
@@ -933,7 +1244,9 @@ impl RenderCsl for citationberg::Group {
 
         let affix_loc = ctx.apply_prefix(&affixes);
 
-        render_with_delimiter(&self.children, self.delimiter.as_deref(), ctx);
+        let style = ctx.style;
+        let delimiter = style.options.group_delimiter(self.delimiter.as_deref());
+        render_with_delimiter(&self.children, delimiter, ctx);
 
         ctx.apply_suffix(&affixes, affix_loc);
 
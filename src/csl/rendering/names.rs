@@ -4,18 +4,118 @@ use std::fmt::Write;
 
 use citationberg::taxonomy::{NameVariable, OtherTerm, Term, Variable};
 use citationberg::{
-    DelimiterBehavior, DemoteNonDroppingParticle, LayoutRenderingElement, NameAnd,
-    NameAsSortOrder, NameForm, NameLabelPosition, NameOptions, Names, ToAffixes,
-    ToFormatting,
+    Affixes, DelimiterBehavior, DemoteNonDroppingParticle, LayoutRenderingElement,
+    NameAnd, NameAsSortOrder, NameForm, NameLabelPosition, NameOptions, Names, TextCase,
+    ToAffixes, ToFormatting, VariablelessLabel,
 };
 use citationberg::{DisambiguationRule, TermForm};
 
 use crate::csl::taxonomy::EntryLike;
-use crate::csl::{Context, DisambiguateState, ElemMeta, SpecialForm};
+use crate::csl::terms::ExtraTerm;
+use crate::csl::{
+    Context, DisambiguateState, ElemMeta, NameOrder, RoleLabelCase, RoleLabelStyle,
+    SpecialForm,
+};
 use crate::types::Person;
 
 use super::{render_label_with_var, RenderCsl};
 
+/// The conventional order of the roles in a list of names with several roles,
+/// like "(E. Edit, Ed.; T. Trans, Trans.)".
+const ROLE_ORDER: [NameVariable; 14] = [
+    NameVariable::Author,
+    NameVariable::Editor,
+    NameVariable::EditorTranslator,
+    NameVariable::EditorialDirector,
+    NameVariable::CollectionEditor,
+    NameVariable::Translator,
+    NameVariable::Compiler,
+    NameVariable::Illustrator,
+    NameVariable::Narrator,
+    NameVariable::Composer,
+    NameVariable::Director,
+    NameVariable::Producer,
+    NameVariable::ExecutiveProducer,
+    NameVariable::Performer,
+];
+
+/// The position of a role in the conventional order. Roles without a
+/// position follow all others.
+fn role_rank(variable: NameVariable) -> usize {
+    ROLE_ORDER
+        .iter()
+        .position(|&v| v == variable)
+        .unwrap_or(ROLE_ORDER.len())
+}
+
+/// Replace the form and position of the label of a list of names with those
+/// of the role label style. A label that moves gets the affixes of the style,
+/// while one that stays keeps those of the style of the citation, like the
+/// comma in "(J. Smith, Trans.)".
+fn styled_label(
+    label: Option<(&VariablelessLabel, NameLabelPosition)>,
+    style: Option<RoleLabelStyle>,
+) -> Option<(Cow<'_, VariablelessLabel>, NameLabelPosition)> {
+    let (label, pos) = label?;
+    let (form, prefix, suffix, new_pos) = match style {
+        None => return Some((Cow::Borrowed(label), pos)),
+        Some(RoleLabelStyle::Parenthetical) => {
+            (TermForm::Short, " (", ")", NameLabelPosition::AfterName)
+        }
+        Some(RoleLabelStyle::SpelledOut) => {
+            (TermForm::Verb, "", " ", NameLabelPosition::BeforeName)
+        }
+    };
+
+    let affixes = if pos == new_pos {
+        label.affixes.clone()
+    } else {
+        Affixes {
+            prefix: Some(prefix.to_string()),
+            suffix: Some(suffix.to_string()),
+        }
+    };
+    Some((Cow::Owned(VariablelessLabel { form, affixes, ..label.clone() }), new_pos))
+}
+
+/// Apply the capitalization of role labels requested by the style options to
+/// the label of a list of names.
+fn role_label<'l, T: EntryLike>(
+    label: &'l VariablelessLabel,
+    ctx: &Context<'_, T>,
+) -> Cow<'l, VariablelessLabel> {
+    // Each role label style has its own default capitalization.
+    let case =
+        ctx.style
+            .options
+            .role_label_case
+            .or(match ctx.style.options.role_label_style {
+                Some(RoleLabelStyle::Parenthetical) => Some(RoleLabelCase::Capitalized),
+                Some(RoleLabelStyle::SpelledOut) => Some(RoleLabelCase::Lowercase),
+                None => None,
+            });
+    let case = match case {
+        None => return Cow::Borrowed(label),
+        Some(RoleLabelCase::Capitalized) => TextCase::CapitalizeFirst,
+        Some(RoleLabelCase::Lowercase) => {
+            let prefix = label.affixes.prefix.as_deref().unwrap_or_default().trim_end();
+            let initial = if prefix.is_empty() {
+                ctx.writing.at_sentence_start()
+            } else {
+                prefix.ends_with(['.', '!', '?'])
+            };
+
+            if initial {
+                TextCase::CapitalizeFirst
+            } else {
+                TextCase::Lowercase
+            }
+        }
+    };
+
+    Cow::Owned(VariablelessLabel { text_case: Some(case), ..label.clone() })
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum DisambiguatedNameForm {
     /// Count the names.
@@ -33,6 +133,12 @@ pub enum DisambiguatedNameForm {
 impl DisambiguatedNameForm {
     fn from<T: EntryLike>(option: &citationberg::NameOptions, ctx: &Context<T>) -> Self {
         if ctx.instance.sorting {
+            if ctx.style.options.sort_by_initials
+                && option.initialize_with.is_some()
+                && option.initialize
+            {
+                return Self::LongInitialized;
+            }
             return Self::LongFull;
         }
 
@@ -126,25 +232,46 @@ impl NameDisambiguationProperties {
         false
     }
 
-    /// Disambuiguate a list of identical names.
-    ///
-    /// TODO: Use for disambiguation of identical names.
-    #[allow(dead_code)]
-    pub fn disambiguate_list(&mut self, variable: NameVariable, items: &[usize]) -> bool {
+    /// The variables whose names are rendered.
+    pub fn variables(&self) -> &[NameVariable] {
+        &self.variables
+    }
+
+    /// Whether the name at the position of a variable is rendered.
+    pub fn is_visible(&self, variable: NameVariable, idx: usize) -> bool {
+        self.variables
+            .iter()
+            .position(|v| v == &variable)
+            .and_then(|outer| self.name_forms.get(outer))
+            .and_then(|list| list.get(idx))
+            .is_some_and(Option::is_some)
+    }
+
+    /// Disambiguate the visible names at the given positions of a variable
+    /// by one step. Return whether any name changed.
+    pub fn disambiguate_list(
+        &mut self,
+        variable: NameVariable,
+        items: &[usize],
+        allow_full_first_name: bool,
+    ) -> bool {
+        let Some(list) = self
+            .variables
+            .iter()
+            .position(|v| v == &variable)
+            .and_then(|outer| self.name_forms.get_mut(outer))
+        else {
+            return false;
+        };
+
         let mut change = false;
         for &idx in items {
-            let Some(outer) = self.variables.iter().position(|v| v == &variable) else {
+            let Some(Some(form)) = list.get_mut(idx) else {
                 continue;
             };
 
-            let form = &mut self.name_forms[outer][idx];
-            if let Some(form) = form {
-                if let Some(new_form) = form.disambiguate(true) {
-                    *form = new_form;
-                    change = true;
-                }
-            } else {
-                *form = Some(self.default_name_form);
+            if let Some(new_form) = form.disambiguate(allow_full_first_name) {
+                *form = new_form;
                 change = true;
             }
         }
@@ -174,7 +301,7 @@ impl RenderCsl for Names {
     fn render<T: EntryLike>(&self, ctx: &mut Context<T>) {
         // The editor and translator variables need to be merged if they are
         // both present and identical.
-        let people: Vec<(Vec<Cow<'_, Person>>, NameVariable)> = if self.variable.len()
+        let mut people: Vec<(Vec<Cow<'_, Person>>, NameVariable)> = if self.variable.len()
             == 2
             && self.variable.contains(&NameVariable::Editor)
             && self.variable.contains(&NameVariable::Translator)
@@ -206,44 +333,71 @@ impl RenderCsl for Names {
                 .collect()
         };
 
+        // Styles that do not name illustrators can list them after the
+        // translators, as in "(T. Trans, Trans.; I. Ill, Ill.)".
+        if ctx.style.options.list_illustrators
+            && self.variable.contains(&NameVariable::Translator)
+            && !self.variable.contains(&NameVariable::Illustrator)
+        {
+            let illustrators = ctx.resolve_name_variable(NameVariable::Illustrator);
+            if !illustrators.is_empty() {
+                let pos = people
+                    .iter()
+                    .position(|&(_, v)| {
+                        matches!(
+                            v,
+                            NameVariable::Translator | NameVariable::EditorTranslator
+                        )
+                    })
+                    .map_or(people.len(), |i| i + 1);
+                people.insert(pos, (illustrators, NameVariable::Illustrator));
+            }
+        }
+
+        // The sort is stable, so roles without a conventional position keep
+        // the order of the style.
+        if ctx.style.options.canonical_role_order {
+            people.sort_by_key(|&(_, variable)| role_rank(variable));
+        }
+
         // Push to the name options stack.
         ctx.writing.push_name_options(&self.options());
 
         // Write the substitute if all variables are empty.
         let is_empty = people.iter().all(|(p, _)| p.is_empty());
+        // Whether this element renders the variable, either directly or with
+        // its substitute.
+        let renders = |var: NameVariable| {
+            let contains_v = self.variable.contains(&var);
+            let substitute_will_render_v = is_empty
+                && self.substitute().map_or(false, |s| {
+                    s.children
+                        .iter()
+                        .filter_map(|c| match c {
+                            LayoutRenderingElement::Names(n) => Some(n.variable.iter()),
+                            _ => None,
+                        })
+                        .flatten()
+                        .any(|v| var == *v)
+                });
+            contains_v || substitute_will_render_v
+        };
+
         // Suppress this variable if we are in a special form.
         match &ctx.instance.kind {
-            Some(SpecialForm::VarOnly(Variable::Name(var))) => {
-                // Skip if none of the variables are the author and the supplement does not contain the author either.
-                let contains_v = self.variable.iter().any(|v| var == v);
-                let substitute_will_render_v = is_empty
-                    && self.substitute().map_or(false, |s| {
-                        s.children
-                            .iter()
-                            .filter_map(|c| match c {
-                                LayoutRenderingElement::Names(n) => {
-                                    Some(n.variable.iter())
-                                }
-                                _ => None,
-                            })
-                            .flatten()
-                            .any(|v| var == v)
-                    });
-                if !contains_v && !substitute_will_render_v {
-                    return;
-                }
-            }
+            // Skip if none of the variables are the author and the supplement
+            // does not contain the author either.
+            Some(SpecialForm::VarOnly(Variable::Name(var))) if !renders(*var) => return,
+            Some(SpecialForm::VarOnly(Variable::Name(_))) => {}
             Some(
                 SpecialForm::VarOnly(_)
                 | SpecialForm::OnlyFirstDate
                 | SpecialForm::OnlyYearSuffix,
             ) => return,
-            Some(SpecialForm::SuppressAuthor) => {
-                if self.variable.iter().any(|v| &NameVariable::Author == v) {
-                    return;
-                }
-            }
-            None => {}
+            // The substitute of the author, like the title, is suppressed
+            // with it.
+            Some(SpecialForm::SuppressAuthor) if renders(NameVariable::Author) => return,
+            Some(SpecialForm::SuppressAuthor) | None => {}
         }
 
         if is_empty {
@@ -326,14 +480,15 @@ impl RenderCsl for Names {
             .enumerate()
         {
             let plural = persons.len() != 1;
-            let label = self.label();
+            let label = styled_label(self.label(), ctx.style.options.role_label_style);
             let do_label = |requested_pos: NameLabelPosition,
                             ctx: &mut Context<'_, T>| {
                 if !ctx.instance.sorting {
-                    if let Some((label, pos)) = label {
-                        if pos == requested_pos {
+                    if let Some((label, pos)) = &label {
+                        if *pos == requested_pos {
+                            let label = role_label(label, ctx);
                             render_label_with_var(
-                                label,
+                                &label,
                                 ctx,
                                 ctx.term(variable.into(), label.form, plural)
                                     .unwrap_or_default(),
@@ -343,10 +498,6 @@ impl RenderCsl for Names {
                 }
             };
 
-            do_label(NameLabelPosition::BeforeName, ctx);
-            add_names(self, ctx, persons, &cs_name, forms, variable);
-            do_label(NameLabelPosition::AfterName, ctx);
-
             if i > 0 {
                 let delim = self.delimiter(ctx.writing.name_options.last());
                 if !delim.is_empty() {
@@ -354,6 +505,13 @@ impl RenderCsl for Names {
                     ctx.push_str(&delim);
                 }
             }
+
+            do_label(NameLabelPosition::BeforeName, ctx);
+            add_names(self, ctx, persons, &cs_name, forms, variable, false);
+            if variable == NameVariable::Author {
+                add_with_contributors(self, ctx, &cs_name, default_form);
+            }
+            do_label(NameLabelPosition::AfterName, ctx);
         }
 
         // TODO Compare each elem with a name meta and run
@@ -392,6 +550,52 @@ enum EndDelim {
     DelimAnd(NameAnd),
 }
 
+/// Add the persons who wrote the item together with the authors or assisted
+/// them, as in "Author, A. (with Helper, B.)" or "Author, A. (with the
+/// assistance of B. Helper)". They are only shown with long name forms and in
+/// languages whose phrase for the role hayagriva knows.
+fn add_with_contributors<T: EntryLike>(
+    names: &citationberg::Names,
+    ctx: &mut Context<T>,
+    cs_name: &citationberg::Name,
+    form: DisambiguatedNameForm,
+) {
+    if ctx.instance.sorting
+        || !matches!(
+            form,
+            DisambiguatedNameForm::LongFull | DisambiguatedNameForm::LongInitialized
+        )
+    {
+        return;
+    }
+
+    let locale = ctx.style.locale();
+    let with = ctx.instance.entry.resolve_with_contributors();
+    if let Some(term) = ExtraTerm::With.localize(&locale).filter(|_| !with.is_empty()) {
+        let forms = vec![Some(form); with.len()];
+        ctx.push_str(" (");
+        ctx.push_str(term);
+        ctx.push_str(" ");
+        add_names(names, ctx, with, cs_name, &forms, NameVariable::Author, false);
+        ctx.push_str(")");
+    }
+
+    // Assistants are not part of the authorship, so their names are not
+    // inverted like those of the authors.
+    let assistants = ctx.instance.entry.resolve_assistants();
+    if let Some(term) = ExtraTerm::WithAssistanceOf
+        .localize(&locale)
+        .filter(|_| !assistants.is_empty())
+    {
+        let forms = vec![Some(form); assistants.len()];
+        ctx.push_str(" (");
+        ctx.push_str(term);
+        ctx.push_str(" ");
+        add_names(names, ctx, assistants, cs_name, &forms, NameVariable::Author, true);
+        ctx.push_str(")");
+    }
+}
+
 fn add_names<T: EntryLike>(
     names: &citationberg::Names,
     ctx: &mut Context<T>,
@@ -399,14 +603,71 @@ fn add_names<T: EntryLike>(
     cs_name: &citationberg::Name,
     forms: &[Option<DisambiguatedNameForm>],
     variable: NameVariable,
+    given_first: bool,
 ) {
     let has_et_al = forms.iter().any(|f| f.is_none());
     let take = forms.iter().position(|f| f.is_none()).unwrap_or(persons.len());
     let names_opts = ctx.writing.name_options.last().clone();
-    let name_opts = cs_name.options(&names_opts);
+    let mut name_opts = cs_name.options(&names_opts);
+    let style = ctx.style;
+    if let Some(delimiter) = &style.options.name_delimiter {
+        name_opts.delimiter = delimiter;
+    }
+    if let Some(and) = style.options.and {
+        name_opts.and = Some(and);
+    } else if name_opts.and == Some(NameAnd::Symbol)
+        && matches!(ctx.instance.kind, Some(SpecialForm::VarOnly(_)))
+    {
+        // Names that are part of the running text are joined with the word
+        // instead of the symbol, as in "Smith and Jones (2020)".
+        name_opts.and = Some(NameAnd::Text);
+    }
+    // Sort keys keep the order of the style so that the bibliography is still
+    // sorted by family name.
+    let order = if given_first {
+        Some(NameOrder::GivenFirst)
+    } else {
+        style
+            .options
+            .name_order
+            .filter(|_| variable == NameVariable::Author && !ctx.instance.sorting)
+    };
+    if let Some(order) = order {
+        name_opts.name_as_sort_order = order.to_sort_order();
+        // Styles put a comma before "and" after an inverted name, which lists
+        // of two names given first do not have.
+        if order == NameOrder::GivenFirst {
+            for behavior in [
+                &mut name_opts.delimiter_precedes_last,
+                &mut name_opts.delimiter_precedes_et_al,
+            ] {
+                if *behavior == DelimiterBehavior::Always {
+                    *behavior = DelimiterBehavior::Contextual;
+                }
+            }
+        }
+    }
     let et_al_use_last = has_et_al.then(|| forms.last().copied().flatten()).flatten();
     let mut last_inverted = false;
 
+    // The delimiter before the "and" or "et al." that directly follows an
+    // institution at the start of the list.
+    let after_institution = style
+        .options
+        .delimiter_after_institution
+        .filter(|_| persons.first().is_some_and(|p| p.is_institutional()));
+    let delimiter_after =
+        |behavior: DelimiterBehavior, i: usize, last_inverted: bool| match (
+            after_institution,
+            behavior,
+        ) {
+            (Some(delimit), _) if i == 1 => delimit,
+            (_, DelimiterBehavior::Always) => true,
+            (_, DelimiterBehavior::Contextual) => i >= 2,
+            (_, DelimiterBehavior::AfterInvertedName) => last_inverted,
+            (_, DelimiterBehavior::Never) => false,
+        };
+
     let demote_non_dropping = match ctx.style.csl.settings.demote_non_dropping_particle {
         DemoteNonDroppingParticle::Never => false,
         DemoteNonDroppingParticle::SortOnly => ctx.instance.sorting,
@@ -423,14 +684,15 @@ fn add_names<T: EntryLike>(
             let mut delim = EndDelim::Delim;
             if last && !has_et_al {
                 if let Some(d) = name_opts.and {
-                    delim = match name_opts.delimiter_precedes_last {
-                        DelimiterBehavior::Contextual if i >= 2 => EndDelim::DelimAnd(d),
-                        DelimiterBehavior::AfterInvertedName if last_inverted => {
-                            EndDelim::DelimAnd(d)
-                        }
-                        DelimiterBehavior::Always => EndDelim::DelimAnd(d),
-                        _ => EndDelim::And(d),
-                    }
+                    delim = if delimiter_after(
+                        name_opts.delimiter_precedes_last,
+                        i,
+                        last_inverted,
+                    ) {
+                        EndDelim::DelimAnd(d)
+                    } else {
+                        EndDelim::And(d)
+                    };
                 }
             }
 
@@ -477,7 +739,8 @@ fn add_names<T: EntryLike>(
             i,
         );
 
-        last_inverted = reverse;
+        // The name of an institution is never inverted.
+        last_inverted = reverse && !name.is_institutional();
         first = false;
     }
 
@@ -500,12 +763,8 @@ fn add_names<T: EntryLike>(
     } else if has_et_al {
         let cs_et_al = names.et_al().cloned().unwrap_or_default();
         if let Some(term) = ctx.term(cs_et_al.term.into(), TermForm::default(), false) {
-            let delim = match name_opts.delimiter_precedes_et_al {
-                DelimiterBehavior::Always => true,
-                DelimiterBehavior::Contextual if take >= 2 => true,
-                DelimiterBehavior::AfterInvertedName if last_inverted => true,
-                _ => false,
-            };
+            let delim =
+                delimiter_after(name_opts.delimiter_precedes_et_al, take, last_inverted);
 
             if delim {
                 ctx.push_str(name_opts.delimiter);
@@ -552,7 +811,14 @@ fn write_name<T: EntryLike>(
     let first_name = |ctx: &mut Context<T>| {
         if let Some(first) = &name.given_name {
             if let Some(initialize_with) = name_opts.initialize_with {
-                if form == DisambiguatedNameForm::LongInitialized {
+                if form == DisambiguatedNameForm::LongInitialized
+                    && ctx.style.options.non_breaking_spaces
+                {
+                    let mut initials = String::new();
+                    name.initials(&mut initials, Some(initialize_with), hyphen_init)
+                        .unwrap();
+                    ctx.push_str(&bind_initials(&initials));
+                } else if form == DisambiguatedNameForm::LongInitialized {
                     name.initials(ctx, Some(initialize_with), hyphen_init).unwrap();
                 } else {
                     name.first_name_with_delimiter(ctx, Some(initialize_with)).unwrap();
@@ -776,3 +1042,12 @@ fn write_name<T: EntryLike>(
     }
     ctx.commit_elem(elem_idx, None, Some(ElemMeta::Name(variable, name_idx)))
 }
+
+/// Replace the spaces between initials with non-breaking spaces. Trailing
+/// spaces, which separate the initials from the next name part, are kept.
+fn bind_initials(initials: &str) -> String {
+    let trimmed = initials.trim_end();
+    let mut res = trimmed.replace(' ', "\u{a0}");
+    res.push_str(&initials[trimmed.len()..]);
+    res
+}
@@ -1,6 +1,6 @@
 use std::cmp::Ordering;
 
-use citationberg::taxonomy::Variable;
+use citationberg::taxonomy::{NumberVariable, Variable};
 use citationberg::{
     DemoteNonDroppingParticle, InheritableNameOptions, LocaleCode, LongShortForm, Sort,
     SortDirection, SortKey,
@@ -9,6 +9,7 @@ use citationberg::{
 use crate::csl::rendering::RenderCsl;
 use crate::csl::BufWriteFormat;
 
+use super::incremental::RenderCache;
 use super::taxonomy::EntryLike;
 use super::{CitationItem, InstanceContext, StyleContext};
 
@@ -82,48 +83,52 @@ impl<'a> StyleContext<'a> {
                     (None, None) => Ordering::Equal,
                 }
             }
-            SortKey::MacroName {
-                name,
-                names_min,
-                names_use_first,
-                names_use_last,
-                ..
-            } => {
-                let render = |entry: &CitationItem<T>, idx: usize| {
-                    let mut ctx =
-                        self.sorting_ctx(entry, idx, entry.locale.as_ref(), term_locale);
-                    ctx.writing.name_options.push(InheritableNameOptions {
-                        et_al_min: *names_min,
-                        et_al_subsequent_min: *names_min,
-                        et_al_use_first: *names_use_first,
-                        et_al_subsequent_use_first: *names_use_first,
-                        et_al_use_last: *names_use_last,
-                        ..Default::default()
-                    });
-
-                    self.get_macro(name).map(|m| {
-                        for child in &m.children {
-                            child.render(&mut ctx)
-                        }
-                        ctx.flush().0.into_iter().fold(String::new(), |mut s, f| {
-                            f.write_buf(&mut s, BufWriteFormat::Plain).unwrap();
-                            s.to_lowercase()
-                        })
-                    })
-                };
-
-                let a_rendered = render(a, a_idx);
-                let b_rendered = render(b, b_idx);
+            SortKey::MacroName { .. } => {
+                let a_rendered = self.render_sort_macro(a, a_idx, key, term_locale);
+                let b_rendered = self.render_sort_macro(b, b_idx, key, term_locale);
 
                 a_rendered.cmp(&b_rendered)
             }
         };
 
-        if key.sort_direction() == SortDirection::Descending {
-            ordering.reverse()
-        } else {
-            ordering
-        }
+        directed(ordering, key)
+    }
+
+    /// Render the macro of a sort key for an entry. Returns `None` if the key
+    /// is not a macro or the macro does not exist.
+    fn render_sort_macro<T: EntryLike>(
+        &self,
+        entry: &CitationItem<T>,
+        idx: usize,
+        key: &SortKey,
+        term_locale: Option<&LocaleCode>,
+    ) -> Option<String> {
+        let SortKey::MacroName {
+            name, names_min, names_use_first, names_use_last, ..
+        } = key
+        else {
+            return None;
+        };
+
+        let mut ctx = self.sorting_ctx(entry, idx, entry.locale.as_ref(), term_locale);
+        ctx.writing.name_options.push(InheritableNameOptions {
+            et_al_min: *names_min,
+            et_al_subsequent_min: *names_min,
+            et_al_use_first: *names_use_first,
+            et_al_subsequent_use_first: *names_use_first,
+            et_al_use_last: *names_use_last,
+            ..Default::default()
+        });
+
+        self.get_macro(name).map(|m| {
+            for child in &m.children {
+                child.render(&mut ctx)
+            }
+            ctx.flush().0.into_iter().fold(String::new(), |mut s, f| {
+                f.write_buf(&mut s, BufWriteFormat::Plain).unwrap();
+                s.to_lowercase()
+            })
+        })
     }
 
     /// Sorts the given citation items by the style's sort keys.
@@ -133,17 +138,154 @@ impl<'a> StyleContext<'a> {
         sort: Option<&Sort>,
         term_locale: Option<&LocaleCode>,
     ) {
-        if let Some(sort) = sort {
-            cites.sort_by(|a, b| {
-                let mut ordering = Ordering::Equal;
-                for key in &sort.keys {
-                    ordering = self.cmp_entries(a, 0, b, 0, key, term_locale);
-                    if ordering != Ordering::Equal {
-                        break;
+        self.sort_cached(cites, sort, term_locale, None, false)
+    }
+
+    /// Sorts the items like [`sort`](Self::sort) and takes the rendered
+    /// macros of each entry from the cache, if there is one.
+    ///
+    /// With `by_key`, items that the style cannot tell apart are sorted by
+    /// the keys of their entries, so that their order does not depend on the
+    /// order in which they were cited. This is skipped if the style sorts by
+    /// citation number, which is the order of citation.
+    pub(super) fn sort_cached<T: EntryLike>(
+        &self,
+        cites: &mut [CitationItem<T>],
+        sort: Option<&Sort>,
+        term_locale: Option<&LocaleCode>,
+        cache: Option<&RenderCache>,
+        by_key: bool,
+    ) {
+        let Some(sort) = sort.filter(|_| cites.len() > 1) else { return };
+        let by_key = by_key
+            && !sort.keys.iter().any(|key| {
+                matches!(
+                    key,
+                    SortKey::Variable {
+                        variable: Variable::Number(NumberVariable::CitationNumber),
+                        ..
                     }
+                )
+            });
+
+        // Rendering a macro is by far the most expensive sort key, so the
+        // macros are rendered once for each item instead of in every
+        // comparison.
+        let render = |item: &CitationItem<T>| -> Vec<Option<String>> {
+            sort.keys
+                .iter()
+                .map(|key| self.render_sort_macro(item, 0, key, term_locale))
+                .collect()
+        };
+        let macros: Vec<Vec<Option<String>>> = cites
+            .iter()
+            .map(|item| match cache {
+                Some(cache) => cache.sort_keys(&item.entry.key(), || render(item)),
+                None => render(item),
+            })
+            .collect();
+
+        let mut order: Vec<usize> = (0..cites.len()).collect();
+        order.sort_by(|&a, &b| {
+            let mut ordering = Ordering::Equal;
+            for (i, key) in sort.keys.iter().enumerate() {
+                ordering = match key {
+                    SortKey::MacroName { .. } => {
+                        directed(macros[a][i].cmp(&macros[b][i]), key)
+                    }
+                    _ => self.cmp_entries(&cites[a], 0, &cites[b], 0, key, term_locale),
+                };
+                if ordering != Ordering::Equal {
+                    break;
                 }
-                ordering
+            }
+            if by_key {
+                ordering = ordering
+                    .then_with(|| cites[a].entry.key().cmp(&cites[b].entry.key()));
+            }
+            ordering
+        });
+
+        // Move the items into the sorted order. The item for position `i` is
+        // where `order[i]` was, unless that place has already been swapped
+        // with an earlier position.
+        for i in 0..order.len() {
+            let mut source = order[i];
+            while source < i {
+                source = order[source];
+            }
+            cites.swap(i, source);
+        }
+    }
+}
+
+/// Reverse an ordering for keys that sort in descending order.
+fn directed(ordering: Ordering, key: &SortKey) -> Ordering {
+    if key.sort_direction() == SortDirection::Descending {
+        ordering.reverse()
+    } else {
+        ordering
+    }
+}
+
+#[cfg(all(test, feature = "archive"))]
+mod tests {
+    use citationberg::Style;
+
+    use super::*;
+    use crate::csl::archive::{locales, style_by_name};
+    use crate::csl::StyleOptions;
+    use crate::io::from_yaml_str;
+    use crate::Entry;
+
+    #[test]
+    fn cached_macros() {
+        // Entries with shared authors and years, editors instead of authors,
+        // and missing dates, so that every sort key is needed.
+        let mut yaml = String::new();
+        let mut seed: u32 = 11;
+        for i in 0..200 {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            let family =
+                ["Adams", "Baker", "Clark", "de Vries"][(seed >> 8) as usize % 4];
+            let role = if seed >> 12 & 3 == 0 { "editor" } else { "author" };
+            yaml.push_str(&format!(
+                "e{i}:\n    type: Book\n    title: Book {}\n    {role}: {family}, Ann\n",
+                (seed >> 16) % 10,
+            ));
+            if seed >> 20 & 7 != 0 {
+                yaml.push_str(&format!("    date: {}\n", 2000 + (seed >> 24) % 5));
+            }
+        }
+        let library = from_yaml_str(&yaml).unwrap();
+        let locales = locales();
+
+        for name in ["apa", "chicago-author-date", "modern-language-association"] {
+            let Some(Style::Independent(style)) = style_by_name(name) else {
+                panic!("{name} is not an independent style");
+            };
+            let sort = style.bibliography.as_ref().unwrap().sort.as_ref().unwrap();
+            let ctx = StyleContext::new(&style, None, &locales, StyleOptions::default());
+
+            let mut cached: Vec<_> =
+                library.iter().map(CitationItem::with_entry).collect();
+            let mut uncached = cached.clone();
+            ctx.sort(&mut cached, Some(sort), None);
+            uncached.sort_by(|a, b| {
+                sort.keys
+                    .iter()
+                    .map(|key| ctx.cmp_entries(a, 0, b, 0, key, None))
+                    .find(|ordering| ordering.is_ne())
+                    .unwrap_or(Ordering::Equal)
             });
+
+            let keys = |items: &[CitationItem<Entry>]| {
+                items
+                    .iter()
+                    .map(|item| item.entry.key().to_string())
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(keys(&cached), keys(&uncached), "{name}");
         }
     }
 }
@@ -2,12 +2,14 @@ use std::borrow::Cow;
 use std::cmp;
 use std::str::FromStr;
 
+use crate::normalize::bare_doi;
 use crate::types::{
-    ChunkedString, Date, EntryType, MaybeTyped, Numeric, Person, PersonRole, StringChunk,
+    ChunkedString, Date, DateParts, EntryType, MaybeTyped, Numeric, Person, PersonRole,
+    StringChunk, TitleSeparator,
 };
 use crate::Entry;
 use citationberg::taxonomy::{
-    DateVariable, Kind, NameVariable, NumberVariable, StandardVariable,
+    DateVariable, Kind, Locator, NameVariable, NumberVariable, StandardVariable,
 };
 use citationberg::{taxonomy, LongShortForm};
 use unic_langid::LanguageIdentifier;
@@ -15,7 +17,18 @@ use unic_langid::LanguageIdentifier;
 #[cfg(feature = "csl-json")]
 use citationberg::json as csl_json;
 
-use super::{DisambiguateState, InstanceContext, LocatorPayload};
+use super::{DisambiguateState, InstanceContext, LocatorPayload, SpecificLocator};
+
+/// The parts of a conference from which the title of its proceedings is
+/// assembled.
+pub struct Conference<'a> {
+    /// The position of the conference in its series, like 5 for the fifth.
+    pub number: Option<MaybeTyped<Cow<'a, Numeric>>>,
+    /// The name of the conference.
+    pub name: &'a ChunkedString,
+    /// The place where the conference was held.
+    pub location: Option<&'a ChunkedString>,
+}
 
 pub trait EntryLike {
     fn resolve_number_variable(
@@ -27,11 +40,48 @@ pub trait EntryLike {
         form: LongShortForm,
         variable: StandardVariable,
     ) -> Option<Cow<'_, ChunkedString>>;
+    /// The title joined with its subtitle by the separator.
+    fn resolve_title(&self, separator: TitleSeparator) -> Option<Cow<'_, ChunkedString>>;
     fn resolve_name_variable(&self, variable: NameVariable) -> Vec<Cow<'_, Person>>;
-    fn resolve_date_variable(&self, variable: DateVariable) -> Option<Cow<'_, Date>>;
+    /// The persons who wrote the item together with the authors.
+    fn resolve_with_contributors(&self) -> Vec<Cow<'_, Person>>;
+    /// The persons who assisted the authors. Items have none unless the
+    /// implementor says otherwise.
+    fn resolve_assistants(&self) -> Vec<Cow<'_, Person>> {
+        Vec::new()
+    }
+    /// The place where the item was published, even if it has no publisher.
+    fn resolve_location(&self, form: LongShortForm) -> Option<Cow<'_, ChunkedString>>;
+    /// The conference whose proceedings contain the item if the proceedings
+    /// have no title of their own.
+    fn resolve_conference(&self, form: LongShortForm) -> Option<Conference<'_>>;
+    /// The titles of a multilingual edition with the base language of each.
+    fn resolve_localized_titles(
+        &self,
+        form: LongShortForm,
+    ) -> Vec<(&str, &ChunkedString)>;
+    /// The base language of the item, like `en`.
+    fn base_language(&self) -> Option<Cow<'_, str>>;
+    fn resolve_date_variable(&self, variable: DateVariable)
+        -> Option<Cow<'_, DateParts>>;
     fn matches_entry_type(&self, kind: taxonomy::Kind) -> bool;
     fn is_english(&self) -> Option<bool>;
     fn key(&self) -> Cow<'_, str>;
+    /// How often the item was cited by other works.
+    fn citation_count(&self) -> Option<u32>;
+}
+
+/// Join the ends of a timestamp range like `4:32-5:10` with the delimiter.
+/// Other timestamps are kept as they are.
+pub(super) fn timestamp_range(timestamp: &str, delimiter: &str) -> String {
+    let is_timestamp =
+        |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit() || c == ':');
+    match timestamp.split_once(['-', '–']) {
+        Some((start, end)) if is_timestamp(start.trim()) && is_timestamp(end.trim()) => {
+            format!("{}{delimiter}{}", start.trim(), end.trim())
+        }
+        _ => timestamp.to_owned(),
+    }
 }
 
 impl<'a, T: EntryLike> InstanceContext<'a, T> {
@@ -52,15 +102,25 @@ impl<'a, T: EntryLike> InstanceContext<'a, T> {
                     )))
                 })
             }
-            NumberVariable::Locator => match self.cite_props.speculative.locator?.1 {
-                LocatorPayload::Str(l) => Some(NumberVariableResult::from_regular(
+            NumberVariable::Locator => match self.cite_props.speculative.locator? {
+                // Timestamps like `01:02:03` are not numbers and keep their
+                // leading zeros. The context joins the ends of ranges.
+                SpecificLocator(Locator::Timestamp, LocatorPayload::Str(l)) => Some(
+                    NumberVariableResult::from_regular(MaybeTyped::String(l.to_owned())),
+                ),
+                SpecificLocator(
+                    _,
+                    LocatorPayload::Str(l) | LocatorPayload::Labeled { value: l, .. },
+                ) => Some(NumberVariableResult::from_regular(
                     Numeric::from_str(l)
                         .map(|n| MaybeTyped::Typed(Cow::Owned(n)))
                         .unwrap_or_else(|_| MaybeTyped::String(l.to_owned())),
                 )),
-                LocatorPayload::Transparent => Some(NumberVariableResult::Transparent(
-                    self.cite_props.certain.initial_idx,
-                )),
+                SpecificLocator(_, LocatorPayload::Transparent) => {
+                    Some(NumberVariableResult::Transparent(
+                        self.cite_props.certain.initial_idx,
+                    ))
+                }
             },
             _ => self
                 .entry
@@ -90,6 +150,17 @@ impl<'a, T: EntryLike> InstanceContext<'a, T> {
     }
 }
 
+/// Whether the processor sets a variable while disambiguating cites instead
+/// of taking it from the entry or the cite, like the year suffix.
+///
+/// Such a variable only counts as called by a group if it is set, since it
+/// is only set if other cites need to be told apart. Otherwise, a group like
+/// the "(n.d.)" of APA, which is followed by the year suffix of undated cites,
+/// would vanish whenever the cite needs no disambiguation.
+pub(super) fn is_disambiguating(variable: StandardVariable) -> bool {
+    matches!(variable, StandardVariable::YearSuffix)
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub(super) enum NumberVariableResult<'a> {
     Regular(MaybeTyped<Cow<'a, Numeric>>),
@@ -137,17 +208,40 @@ impl EntryLike for Entry {
             NumberVariable::CollectionNumber => {
                 self.get_collection().and_then(Entry::volume).map(MaybeTyped::to_cow)
             }
-            NumberVariable::Edition => self.map(|e| e.edition()).map(MaybeTyped::to_cow),
+            // Software has versions instead of editions, everything else has
+            // editions instead of versions.
+            NumberVariable::Edition if self.entry_type() == &EntryType::Repository => {
+                None
+            }
+            NumberVariable::Edition => self
+                .map(|e| e.edition())
+                .map(MaybeTyped::to_cow)
+                .or_else(|| version(self))
+                .map(edition_as_version),
             NumberVariable::FirstReferenceNoteNumber => {
                 panic!("processor must resolve this")
             }
+            // Items that are not yet published have not been assigned to an
+            // issue.
+            NumberVariable::Issue
+            | NumberVariable::Page
+            | NumberVariable::PageFirst
+            | NumberVariable::Volume
+                if self.status().is_some() =>
+            {
+                None
+            }
             NumberVariable::Issue => self.map(|e| e.issue()).map(MaybeTyped::to_cow),
             NumberVariable::Locator => panic!("processor must resolve this"),
+            // Report and patent numbers like "123-4" or "5,678,901" are
+            // identifiers, not ranges or lists.
             NumberVariable::Number => {
                 return self.serial_number().and_then(|s| s.0.get("serial")).map(|s| {
                     Numeric::from_str(s)
+                        .ok()
+                        .filter(|n| n.single_number().is_some())
                         .map(|n| MaybeTyped::Typed(Cow::Owned(n)))
-                        .unwrap_or_else(|_| MaybeTyped::String(s.to_owned()))
+                        .unwrap_or_else(|| MaybeTyped::String(s.to_owned()))
                 })
             }
             NumberVariable::NumberOfPages => {
@@ -161,8 +255,12 @@ impl EntryLike for Entry {
             }
             NumberVariable::PageFirst => self
                 .page_range()
-                .and_then(|r| r.range())
-                .map(|r| MaybeTyped::Typed(Cow::Owned(Numeric::from(r.start)))),
+                .and_then(|r| {
+                    r.range()
+                        .map(|r| r.start)
+                        .or_else(|| r.will_transform().then(|| r.nth(0)).flatten())
+                })
+                .map(|n| MaybeTyped::Typed(Cow::Owned(Numeric::from(n)))),
             NumberVariable::PartNumber => self
                 .bound_select(
                     &select!(
@@ -180,18 +278,13 @@ impl EntryLike for Entry {
                         .map(|n| MaybeTyped::Typed(Cow::Owned(n)))
                         .unwrap_or_else(|_| MaybeTyped::String(s.to_owned()))
                 }),
-            NumberVariable::Section => None,
+            NumberVariable::Section => self.section().map(MaybeTyped::to_cow),
             NumberVariable::SupplementNumber => None,
-            NumberVariable::Version => self
-                .bound_select(&select!(("e":Repository)), "e")
-                .and_then(Entry::serial_number)
-                .and_then(|s| s.0.get("version"))
-                .map(|s| {
-                    Numeric::from_str(s)
-                        .map(|n| MaybeTyped::Typed(Cow::Owned(n)))
-                        .unwrap_or_else(|_| MaybeTyped::String(s.to_owned()))
-                }),
-            NumberVariable::Volume => self.volume().map(MaybeTyped::to_cow),
+            NumberVariable::Version if self.entry_type() == &EntryType::Repository => {
+                version(self).or_else(|| self.edition().map(MaybeTyped::to_cow))
+            }
+            NumberVariable::Version => None,
+            NumberVariable::Volume => self.map(|e| e.volume()).map(MaybeTyped::to_cow),
         }
     }
 
@@ -203,7 +296,9 @@ impl EntryLike for Entry {
     ) -> Option<Cow<'_, ChunkedString>> {
         let entry = self;
         match variable {
-            StandardVariable::Abstract => None,
+            StandardVariable::Abstract => {
+                entry.abstract_().map(|f| f.select(form)).map(Cow::Borrowed)
+            }
             StandardVariable::Annote => None,
             StandardVariable::Archive => {
                 entry.map(|e| e.archive()).map(|f| f.select(form)).map(Cow::Borrowed)
@@ -252,6 +347,7 @@ impl EntryLike for Entry {
             StandardVariable::ContainerTitle => entry
                 .get_container()
                 .and_then(|e| e.title())
+                .filter(|f| !is_site_of_author(entry, &f.value))
                 .map(|f| f.select(form))
                 .map(Cow::Borrowed),
             StandardVariable::ContainerTitleShort => entry
@@ -263,9 +359,12 @@ impl EntryLike for Entry {
                 .runtime()
                 .map(|r| Cow::Owned(StringChunk::normal(r.to_string()).into())),
             StandardVariable::Division => None,
-            StandardVariable::DOI => {
-                entry.doi().map(|d| Cow::Owned(StringChunk::verbatim(d).into()))
-            }
+            // Chapters without their own DOI can be found with the DOI of
+            // their book.
+            StandardVariable::DOI => entry
+                .doi()
+                .or_else(|| entry.get_book().and_then(Entry::doi))
+                .map(|d| Cow::Owned(StringChunk::verbatim(bare_doi(d)).into())),
             StandardVariable::Event | StandardVariable::EventTitle => entry
                 .bound_select(&select!(* > ("p":(Exhibition | Conference | Misc))), "p")
                 .and_then(Entry::title)
@@ -276,7 +375,9 @@ impl EntryLike for Entry {
                 .and_then(Entry::location)
                 .map(|f| f.select(form))
                 .map(Cow::Borrowed),
-            StandardVariable::Genre => None,
+            StandardVariable::Genre => {
+                entry.genre().map(|f| f.select(form)).map(Cow::Borrowed)
+            }
             StandardVariable::ISBN => {
                 entry.isbn().map(|d| Cow::Owned(StringChunk::verbatim(d).into()))
             }
@@ -289,7 +390,9 @@ impl EntryLike for Entry {
                 .map(|e| e.language())
                 .map(|l| Cow::Owned(StringChunk::normal(csl_language(l)).into())),
             StandardVariable::License => None,
-            StandardVariable::Medium => None,
+            StandardVariable::Medium => {
+                entry.medium().map(|f| f.select(form)).map(Cow::Borrowed)
+            }
             StandardVariable::Note => {
                 entry.note().map(|f| f.select(form)).map(Cow::Borrowed)
             }
@@ -315,8 +418,23 @@ impl EntryLike for Entry {
             StandardVariable::PMID => {
                 entry.pmid().map(|d| Cow::Owned(StringChunk::verbatim(d).into()))
             }
+            // The institution of an unpublished manuscript, like "Department
+            // of Psychology, University of Nowhere", takes the place of the
+            // publisher. A manuscript in an archive, like a preprint server,
+            // is found there instead.
             StandardVariable::Publisher => entry
                 .map(|e| e.publisher())
+                .or_else(|| {
+                    let institution = match entry.entry_type() {
+                        EntryType::Manuscript => entry.archive().is_none(),
+                        // The organization of a web page without a parent
+                        // site is the name of its site.
+                        EntryType::Web => entry.get_container().is_none(),
+                        _ => false,
+                    };
+                    institution.then(|| entry.organization()).flatten()
+                })
+                .filter(|f| !is_site_of_author(entry, &f.value))
                 .map(|f| f.select(form))
                 .map(Cow::Borrowed),
             StandardVariable::PublisherPlace => entry
@@ -333,11 +451,10 @@ impl EntryLike for Entry {
                 .and_then(Entry::title)
                 .map(|f| f.select(form))
                 .map(Cow::Borrowed),
-            StandardVariable::Status => None,
-            StandardVariable::Title => entry
-                .title()
-                .map(|f| f.select(LongShortForm::Short))
-                .map(Cow::Borrowed),
+            StandardVariable::Status => entry
+                .status()
+                .map(|s| Cow::Owned(StringChunk::normal(s.name()).into())),
+            StandardVariable::Title => entry.resolve_title(TitleSeparator::default()),
             StandardVariable::TitleShort => {
                 entry.title().map(|f| f.select(form)).map(Cow::Borrowed)
             }
@@ -361,9 +478,44 @@ impl EntryLike for Entry {
         }
     }
 
-    fn resolve_date_variable(&self, variable: DateVariable) -> Option<Cow<'_, Date>> {
+    fn resolve_with_contributors(&self) -> Vec<Cow<'_, Person>> {
+        self.affiliated_with_role(PersonRole::With)
+            .into_iter()
+            .map(Cow::Borrowed)
+            .collect()
+    }
+
+    fn resolve_assistants(&self) -> Vec<Cow<'_, Person>> {
+        self.affiliated_with_role(PersonRole::Assistant)
+            .into_iter()
+            .map(Cow::Borrowed)
+            .collect()
+    }
+
+    fn resolve_title(&self, separator: TitleSeparator) -> Option<Cow<'_, ChunkedString>> {
+        match self.full_title_cow(separator)? {
+            Cow::Borrowed(f) => Some(Cow::Borrowed(f.select(LongShortForm::Short))),
+            Cow::Owned(f) => Some(Cow::Owned(f.select(LongShortForm::Short).clone())),
+        }
+    }
+
+    fn resolve_location(&self, form: LongShortForm) -> Option<Cow<'_, ChunkedString>> {
+        self.map(|e| e.publisher().map(|_| e))
+            .and_then(Entry::location)
+            .or_else(|| self.location())
+            .map(|f| f.select(form))
+            .map(Cow::Borrowed)
+    }
+
+    fn resolve_date_variable(
+        &self,
+        variable: DateVariable,
+    ) -> Option<Cow<'_, DateParts>> {
         Some(Cow::Borrowed(match variable {
-            DateVariable::Accessed => self.url_any().and_then(|u| u.visit_date.as_ref()),
+            DateVariable::Accessed => {
+                let date = self.url_any().and_then(|u| u.visit_date)?;
+                return Some(Cow::Owned(date.into()));
+            }
             DateVariable::AvailableDate => None,
             DateVariable::EventDate => self
                 .bound_select(&select!(* > ("p":(Exhibition | Conference | Misc))), "p")
@@ -379,7 +531,10 @@ impl EntryLike for Entry {
         variable: taxonomy::NameVariable,
     ) -> Vec<Cow<'_, Person>> {
         match variable {
-            NameVariable::Author => self.authors().map(|a| a.iter().collect()),
+            NameVariable::Author => self
+                .authors()
+                .or_else(|| self.get_book().and_then(Entry::authors))
+                .map(|a| a.iter().collect()),
             NameVariable::Chair => self
                 .bound_select(
                     &select!(
@@ -398,8 +553,11 @@ impl EntryLike for Entry {
             NameVariable::Composer => {
                 self.map(|e| Some(e.affiliated_with_role(PersonRole::Composer)))
             }
+            // A chapter without own authors takes the authors of its book, so
+            // they are not repeated as container authors.
             NameVariable::ContainerAuthor => self
                 .get_container()
+                .filter(|_| self.authors().is_some() || self.get_book().is_none())
                 .and_then(|e| e.authors())
                 .map(|a| a.iter().collect()),
             NameVariable::Contributor => {
@@ -413,6 +571,11 @@ impl EntryLike for Entry {
                     "p",
                 )
                 .map(|e| e.affiliated_with_role(PersonRole::Organizer)),
+            // A performance is directed by its own director, or conducted by
+            // them for a concert.
+            NameVariable::Director if self.entry_type() == &EntryType::Performance => {
+                Some(self.affiliated_with_role(PersonRole::Director))
+            }
             NameVariable::Director => self
                 .bound_select(
                     &select!(
@@ -421,7 +584,17 @@ impl EntryLike for Entry {
                     "p",
                 )
                 .map(|e| e.affiliated_with_role(PersonRole::Director)),
-            NameVariable::Editor => self.editors().map(|a| a.iter().collect()),
+            // An entry of a reference work, like an encyclopedia, is edited
+            // by the editors of the work.
+            NameVariable::Editor => self
+                .editors()
+                .or_else(|| self.get_book().and_then(Entry::editors))
+                .or_else(|| {
+                    self.get_container()
+                        .filter(|_| self.entry_type() == &EntryType::Entry)
+                        .and_then(Entry::editors)
+                })
+                .map(|a| a.iter().collect()),
             NameVariable::EditorialDirector => None,
             NameVariable::EditorTranslator => {
                 let translator = self.affiliated_with_role(PersonRole::Translator);
@@ -438,9 +611,13 @@ impl EntryLike for Entry {
             }
             NameVariable::Guest => None,
             NameVariable::Host => None,
-            NameVariable::Illustrator => {
-                self.map(|e| Some(e.affiliated_with_role(PersonRole::Illustrator)))
-            }
+            // An author who illustrated their own work is only named once.
+            NameVariable::Illustrator => self.map(|e| {
+                let authors = e.authors().unwrap_or_default();
+                let mut illustrators = e.affiliated_with_role(PersonRole::Illustrator);
+                illustrators.retain(|p| !authors.contains(p));
+                Some(illustrators)
+            }),
             NameVariable::Interviewer => None,
             NameVariable::Narrator => {
                 self.map(|e| Some(e.affiliated_with_role(PersonRole::Narrator)))
@@ -580,7 +757,8 @@ impl EntryLike for Entry {
                 self.entry_type() == &EntryType::Entry && !is_encyclopedia
             }
             Kind::Event => self.entry_type() == &EntryType::Exhibition,
-            Kind::Hearing | Kind::Interview | Kind::Performance | Kind::Speech => false,
+            Kind::Performance => self.entry_type() == &EntryType::Performance,
+            Kind::Hearing | Kind::Interview | Kind::Speech => false,
             Kind::Broadcast | Kind::MotionPicture | Kind::MusicalScore | Kind::Song => {
                 let is_music_score =
                     select!(Audio > (Book | Periodical | Reference | Misc | Blog | Web))
@@ -609,7 +787,9 @@ impl EntryLike for Entry {
                     return false;
                 }
 
-                let is_published = self.publisher().is_some();
+                // Statutes are published by a publisher or in a code.
+                let is_published =
+                    self.publisher().is_some() || self.get_container().is_some();
                 if kind == Kind::Bill {
                     return !is_published;
                 }
@@ -655,9 +835,44 @@ impl EntryLike for Entry {
         }
     }
 
+    fn resolve_conference(&self, form: LongShortForm) -> Option<Conference<'_>> {
+        let proceedings = self.get_container().filter(|e| {
+            e.entry_type() == &EntryType::Proceedings && e.title().is_none()
+        })?;
+        let conference = proceedings
+            .parents()
+            .iter()
+            .find(|e| e.entry_type() == &EntryType::Conference)?;
+
+        Some(Conference {
+            number: conference.edition().map(MaybeTyped::to_cow),
+            name: conference.title()?.select(form),
+            location: conference.location().map(|l| l.select(form)),
+        })
+    }
+
+    fn resolve_localized_titles(
+        &self,
+        form: LongShortForm,
+    ) -> Vec<(&str, &ChunkedString)> {
+        self.titles()
+            .unwrap_or_default()
+            .iter()
+            .map(|t| (t.language.language.as_str(), t.title.select(form)))
+            .collect()
+    }
+
+    fn base_language(&self) -> Option<Cow<'_, str>> {
+        self.language().map(|l| Cow::Borrowed(l.language.as_str()))
+    }
+
     fn is_english(&self) -> Option<bool> {
         self.language().map(|l| l.language.as_str() == "en")
     }
+
+    fn citation_count(&self) -> Option<u32> {
+        Entry::citation_count(self).copied()
+    }
 }
 
 #[cfg(feature = "csl-json")]
@@ -678,21 +893,36 @@ impl EntryLike for citationberg::json::Item {
         }
     }
 
-    fn resolve_date_variable(&self, variable: DateVariable) -> Option<Cow<'_, Date>> {
+    fn resolve_with_contributors(&self) -> Vec<Cow<'_, Person>> {
+        Vec::new()
+    }
+
+    fn resolve_title(&self, _: TitleSeparator) -> Option<Cow<'_, ChunkedString>> {
+        self.resolve_standard_variable(LongShortForm::Long, StandardVariable::Title)
+    }
+
+    fn resolve_location(&self, form: LongShortForm) -> Option<Cow<'_, ChunkedString>> {
+        self.resolve_standard_variable(form, StandardVariable::PublisherPlace)
+    }
+
+    fn resolve_date_variable(
+        &self,
+        variable: DateVariable,
+    ) -> Option<Cow<'_, DateParts>> {
         match self.0.get(&variable.to_string())? {
             csl_json::Value::Date(d) => {
                 let Ok(d) = csl_json::FixedDateRange::try_from(d.clone()) else {
                     return None;
                 };
-                if d.end.is_some() {
-                    panic!("ranges are not supported")
-                }
-                let d = d.start;
-                Some(Cow::Owned(Date {
+                let date = |d: csl_json::FixedDate| Date {
                     year: d.year as i32,
                     month: d.month,
                     day: d.day,
                     approximate: false,
+                };
+                Some(Cow::Owned(DateParts {
+                    end: d.end.map(date),
+                    ..date(d.start).into()
                 }))
             }
             _ => None,
@@ -783,6 +1013,19 @@ impl EntryLike for citationberg::json::Item {
         Kind::from_str(&string) == Ok(kind)
     }
 
+    fn resolve_conference(&self, _: LongShortForm) -> Option<Conference<'_>> {
+        None
+    }
+
+    fn resolve_localized_titles(&self, _: LongShortForm) -> Vec<(&str, &ChunkedString)> {
+        Vec::new()
+    }
+
+    fn base_language(&self) -> Option<Cow<'_, str>> {
+        let language = self.0.get("language")?.to_str()?;
+        Some(Cow::Owned(language.split('-').next()?.to_string()))
+    }
+
     fn is_english(&self) -> Option<bool> {
         self.0
             .get("language")
@@ -793,6 +1036,10 @@ impl EntryLike for citationberg::json::Item {
     fn key(&self) -> Cow<'_, str> {
         self.id().unwrap_or_default()
     }
+
+    fn citation_count(&self) -> Option<u32> {
+        None
+    }
 }
 
 pub(super) fn csl_language(lang_id: &LanguageIdentifier) -> String {
@@ -805,8 +1052,46 @@ pub(super) fn csl_language(lang_id: &LanguageIdentifier) -> String {
     buf
 }
 
+/// Whether the name is the name of the site of a web page that is also its
+/// only author, like "World Health Organization". The site is then not
+/// named again after the title.
+fn is_site_of_author(entry: &Entry, name: &ChunkedString) -> bool {
+    if entry.entry_type() != &EntryType::Web {
+        return false;
+    }
+
+    match entry.authors() {
+        Some([author]) => author.is_institutional() && author.name == name.to_string(),
+        _ => false,
+    }
+}
+
+/// The version in the serial numbers of an entry.
+fn version(entry: &Entry) -> Option<MaybeTyped<Cow<'_, Numeric>>> {
+    entry.serial_number().and_then(|s| s.0.get("version")).map(|s| {
+        Numeric::from_str(s)
+            .map(|n| MaybeTyped::Typed(Cow::Owned(n)))
+            .unwrap_or_else(|_| MaybeTyped::String(s.to_owned()))
+    })
+}
+
+/// Turn editions like "Version 2" or "v2" into strings, so that styles print
+/// them as they are instead of as an ordinal like "2nd ed.".
+fn edition_as_version(
+    edition: MaybeTyped<Cow<'_, Numeric>>,
+) -> MaybeTyped<Cow<'_, Numeric>> {
+    let MaybeTyped::Typed(numeric) = &edition else { return edition };
+    let Some(prefix) = numeric.prefix.as_deref() else { return edition };
+    if !matches!(prefix.trim().to_lowercase().as_str(), "v" | "v." | "version") {
+        return edition;
+    }
+
+    let bare = Numeric { prefix: None, ..numeric.as_ref().clone() };
+    MaybeTyped::String(format!("Version {}", bare))
+}
+
 // A function that takes a usize value and returns a String
-fn letter(val: u8) -> String {
+pub(super) fn letter(val: u8) -> String {
     let mut result = String::with_capacity(1);
     let mut current = val;
 
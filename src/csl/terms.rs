@@ -0,0 +1,144 @@
+//! Phrases that hayagriva prints although CSL locales have no term for them.
+
+use citationberg::LocaleCode;
+
+use super::Signal;
+
+/// A phrase that is not a CSL term.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub(super) enum ExtraTerm {
+    /// The start of an assembled proceedings title, like "Proceedings of the"
+    /// in "Proceedings of the 5th Conference on Digital Libraries".
+    ProceedingsOf,
+    /// The label of the number of a thesis in a database, like "Publication
+    /// No." in "Publication No. 12345".
+    PublicationNumber,
+    /// The description of a manuscript that was submitted to a journal or
+    /// publisher, like "Manuscript submitted for publication".
+    ManuscriptSubmitted,
+    /// The phrase before the persons who wrote an item together with its
+    /// authors, like "with" in "Gretzky, W. (with Reilly, R.)".
+    With,
+    /// The phrase before the persons who assisted the authors, like "with the
+    /// assistance of" in "Roe, R. (with the assistance of H. Helper)".
+    WithAssistanceOf,
+    /// The sentence that says that other works cited an item once, with `{}`
+    /// in place of the count, like "Cited {} time.".
+    CitedOnce,
+    /// The sentence that says how often other works cited an item, with `{}`
+    /// in place of the count, like "Cited {} times.".
+    CitedTimes,
+    /// The label of a note below a figure or table, like "Note.".
+    NoteLabel,
+    /// The phrase before the source of an adapted figure or table, like
+    /// "Adapted from".
+    AdaptedFrom,
+    /// The phrase before the source of a reprinted figure or table, like
+    /// "Reprinted from".
+    ReprintedFrom,
+    /// The word before the authors of a work or the holder of a copyright,
+    /// like "by" in "Copyright 2020 by A. Author".
+    By,
+    /// The word before the year and holder of a copyright, like "Copyright".
+    Copyright,
+    /// A signal before a cite. English has no entry here because the signal
+    /// itself is in English.
+    Signal(Signal),
+}
+
+impl ExtraTerm {
+    /// The phrase in the language of the locale, or `None` if hayagriva does
+    /// not know it in that language.
+    pub(super) fn localize(self, locale: &LocaleCode) -> Option<&'static str> {
+        let language = locale.0.split('-').next().unwrap_or_default();
+        Some(match (self, language) {
+            (Self::ProceedingsOf, "en") => "Proceedings of the",
+            (Self::ProceedingsOf, "de") => "Tagungsband der",
+            (Self::ProceedingsOf, "es") => "Actas de la",
+            (Self::ProceedingsOf, "fr") => "Actes de la",
+            (Self::PublicationNumber, "en") => "Publication No.",
+            (Self::PublicationNumber, "de") => "Publikationsnr.",
+            (Self::PublicationNumber, "es") => "Publicación n.º",
+            (Self::PublicationNumber, "fr") => "Publication nº",
+            (Self::ManuscriptSubmitted, "en") => "Manuscript submitted for publication",
+            (Self::ManuscriptSubmitted, "de") => {
+                "Zur Veröffentlichung eingereichtes Manuskript"
+            }
+            (Self::ManuscriptSubmitted, "es") => "Manuscrito enviado para publicación",
+            (Self::ManuscriptSubmitted, "fr") => "Manuscrit soumis pour publication",
+            (Self::With, "en") => "with",
+            (Self::With, "de") => "mit",
+            (Self::With, "es") => "con",
+            (Self::With, "fr") => "avec",
+            (Self::WithAssistanceOf, "en") => "with the assistance of",
+            (Self::WithAssistanceOf, "de") => "unter Mitarbeit von",
+            (Self::WithAssistanceOf, "es") => "con la colaboración de",
+            (Self::WithAssistanceOf, "fr") => "avec la collaboration de",
+            (Self::CitedOnce, "en") => "Cited {} time.",
+            (Self::CitedOnce, "de") => "{}-mal zitiert.",
+            (Self::CitedOnce, "es") => "Citado {} vez.",
+            (Self::CitedOnce, "fr") => "Cité {} fois.",
+            (Self::CitedTimes, "en") => "Cited {} times.",
+            (Self::CitedTimes, "de") => "{}-mal zitiert.",
+            (Self::CitedTimes, "es") => "Citado {} veces.",
+            (Self::CitedTimes, "fr") => "Cité {} fois.",
+            (Self::NoteLabel, "en") => "Note.",
+            (Self::NoteLabel, "de") => "Anmerkung.",
+            (Self::NoteLabel, "es") => "Nota.",
+            (Self::NoteLabel, "fr") => "Note.",
+            (Self::AdaptedFrom, "en") => "Adapted from",
+            (Self::AdaptedFrom, "de") => "Adaptiert nach",
+            (Self::AdaptedFrom, "es") => "Adaptado de",
+            (Self::AdaptedFrom, "fr") => "Adapté de",
+            (Self::ReprintedFrom, "en") => "Reprinted from",
+            (Self::ReprintedFrom, "de") => "Nachdruck aus",
+            (Self::ReprintedFrom, "es") => "Reimpreso de",
+            (Self::ReprintedFrom, "fr") => "Reproduit de",
+            (Self::By, "en") => "by",
+            (Self::By, "de") => "von",
+            (Self::By, "es") => "por",
+            (Self::By, "fr") => "par",
+            (Self::Copyright, "en" | "de" | "es") => "Copyright",
+            (Self::Copyright, "fr") => "Droit d’auteur",
+            (Self::Signal(Signal::Eg), "de") => "z.\u{a0}B.",
+            (Self::Signal(Signal::See), "de") => "siehe",
+            (Self::Signal(Signal::SeeAlso), "de") => "siehe auch",
+            (Self::Signal(Signal::Cf), "de") => "vgl.",
+            (Self::Signal(Signal::Contra), "de") => "a.\u{a0}A.",
+            (Self::Signal(Signal::Eg), "es") => "p.\u{a0}ej.,",
+            (Self::Signal(Signal::See), "es") => "véase",
+            (Self::Signal(Signal::SeeAlso), "es") => "véase también",
+            (Self::Signal(Signal::Cf), "es") => "cf.",
+            (Self::Signal(Signal::Contra), "es") => "en contra",
+            (Self::Signal(Signal::Eg), "fr") => "p.\u{a0}ex.,",
+            (Self::Signal(Signal::See), "fr") => "voir",
+            (Self::Signal(Signal::SeeAlso), "fr") => "voir aussi",
+            (Self::Signal(Signal::Cf), "fr") => "cf.",
+            (Self::Signal(Signal::Contra), "fr") => "contra",
+            _ => return None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn localize() {
+        let locale = |code: &str| LocaleCode(code.into());
+        assert_eq!(
+            ExtraTerm::ProceedingsOf.localize(&locale("en-GB")),
+            Some("Proceedings of the")
+        );
+        assert_eq!(
+            ExtraTerm::ProceedingsOf.localize(&locale("de-AT")),
+            Some("Tagungsband der")
+        );
+        assert_eq!(ExtraTerm::ProceedingsOf.localize(&locale("ja-JP")), None);
+        assert_eq!(
+            ExtraTerm::ManuscriptSubmitted.localize(&locale("fr-CA")),
+            Some("Manuscrit soumis pour publication")
+        );
+    }
+}
@@ -79,6 +79,28 @@ impl From<tex::Date> for Date {
     }
 }
 
+impl From<tex::Date> for DateParts {
+    fn from(date: tex::Date) -> Self {
+        let approximate = date.uncertain || date.approximate;
+        let date_time = |x: tex::Datetime| Date {
+            year: x.year,
+            month: x.month,
+            day: x.day,
+            approximate,
+        };
+
+        match date.value {
+            DateValue::At(x) | DateValue::After(x) | DateValue::Before(x) => {
+                Self::from(date_time(x))
+            }
+            DateValue::Between(start, end) => Self {
+                end: Some(date_time(end)),
+                ..Self::from(date_time(start))
+            },
+        }
+    }
+}
+
 impl From<&[Spanned<Chunk>]> for ChunkedString {
     fn from(chunks: &[Spanned<Chunk>]) -> Self {
         let mut res = Self::new();
@@ -280,7 +302,11 @@ impl TryFrom<&tex::Entry> for Entry {
             item.set_title(title);
         }
 
-        // NOTE: Ignoring subtitle and titleaddon for now
+        if let Some(subtitle) = map_res(entry.subtitle())?.map(Into::into) {
+            item.set_subtitle(subtitle);
+        }
+
+        // NOTE: Ignoring titleaddon for now
 
         if let Some(parent) = mv(&mut item, parent, mv_parent) {
             if let Some(title) = map_res(entry.main_title())?.map(Into::into) {
@@ -513,6 +539,15 @@ impl TryFrom<&tex::Entry> for Entry {
             }
         }
 
+        if let Some(status) = map_res(entry.pubstate())? {
+            match status.format_verbatim().to_lowercase().as_str() {
+                "inpress" => item.set_status(PublicationStatus::InPress),
+                "prepublished" => item.set_status(PublicationStatus::AdvanceOnline),
+                "submitted" => item.set_status(PublicationStatus::Submitted),
+                _ => {}
+            }
+        }
+
         if let Some(note) = map_res(entry.annotation())?
             .or_else(|| entry.addendum().ok())
             .map(|d| d.format_verbatim())
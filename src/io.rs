@@ -1,9 +1,20 @@
 //! Reading and writing YAML bibliographies.
 
 #[cfg(feature = "biblatex")]
-use biblatex::{Bibliography, TypeError};
+use std::collections::HashMap;
+use std::fmt;
+#[cfg(feature = "biblatex")]
+use std::io::BufRead;
+use std::ops::Range;
+#[cfg(feature = "biblatex")]
+use std::sync::Arc;
+
+#[cfg(feature = "biblatex")]
+use biblatex::{Bibliography, Pair, RawBibliography, RawChunk, Spanned, TypeError};
 
-use crate::{Entry, Library};
+#[cfg(feature = "biblatex")]
+use crate::Entry;
+use crate::Library;
 
 /// Parse a bibliography from a YAML string.
 ///
@@ -26,6 +37,117 @@ pub fn from_yaml_str(s: &str) -> Result<Library, serde_yaml::Error> {
     serde_yaml::from_str(s)
 }
 
+/// Parse a bibliography from a YAML string and report the errors with their
+/// location in the source.
+///
+/// With [`ErrorMode::CollectAll`], every entry is checked and each malformed
+/// entry produces its own diagnostic.
+///
+/// ```
+/// use hayagriva::io::{from_yaml_str_diagnostics, ErrorMode};
+///
+/// let yaml = r#"
+/// crazy-rich:
+///     type: Book
+///     date: soon
+/// "#;
+/// let errors = from_yaml_str_diagnostics(yaml, ErrorMode::FailFast).unwrap_err();
+/// assert_eq!((errors[0].line, errors[0].column), (4, 11));
+/// assert_eq!(errors[0].key.as_deref(), Some("crazy-rich"));
+/// assert_eq!(errors[0].field.as_deref(), Some("date"));
+/// ```
+pub fn from_yaml_str_diagnostics(
+    s: &str,
+    mode: ErrorMode,
+) -> Result<Library, Vec<ParseDiagnostic>> {
+    let err = match from_yaml_str(s) {
+        Ok(library) => return Ok(library),
+        Err(err) => err,
+    };
+
+    let segments = yaml_segments(s);
+    let whole = yaml_diagnostic(s, &segments, 0, err);
+    if mode == ErrorMode::FailFast {
+        return Err(vec![whole]);
+    }
+
+    // Parse every entry on its own so that one malformed entry does not hide
+    // the errors in the entries after it.
+    let mut errors = vec![];
+    for (i, (_, range)) in segments.iter().enumerate() {
+        if let Err(err) = from_yaml_str(&s[range.clone()]) {
+            errors.push(yaml_diagnostic(s, &segments[i..=i], range.start, err));
+        }
+    }
+
+    if errors.is_empty() {
+        errors.push(whole);
+    }
+
+    Err(errors)
+}
+
+/// Split a YAML bibliography into the keys and byte ranges of its top-level
+/// entries.
+fn yaml_segments(s: &str) -> Vec<(&str, Range<usize>)> {
+    let mut segments: Vec<(&str, Range<usize>)> = vec![];
+    let mut offset = 0;
+    for line in s.split_inclusive('\n') {
+        let is_key = line.starts_with(|c: char| !c.is_whitespace() && c != '#')
+            && !line.starts_with("---");
+        if let Some(key) = line.split_once(':').map(|(k, _)| k).filter(|_| is_key) {
+            if let Some((_, last)) = segments.last_mut() {
+                last.end = offset;
+            }
+            let key = key.trim().trim_matches(['"', '\'']);
+            segments.push((key, offset..s.len()));
+        }
+        offset += line.len();
+    }
+    segments
+}
+
+/// Convert a YAML error in the slice starting at `offset` into a diagnostic
+/// for the full source.
+fn yaml_diagnostic(
+    s: &str,
+    segments: &[(&str, Range<usize>)],
+    offset: usize,
+    err: serde_yaml::Error,
+) -> ParseDiagnostic {
+    let start = offset + err.location().map_or(0, |l| l.index());
+    let line_end = s[start..].find('\n').map_or(s.len(), |i| start + i);
+    let end = start + s[start..line_end].trim_end().len();
+
+    // The message is prefixed with the path to the value and suffixed with its
+    // location, both of which are reported separately.
+    let mut message = err.to_string();
+    if let Some(location) = err.location() {
+        let suffix = format!(" at line {} column {}", location.line(), location.column());
+        if message.ends_with(&suffix) {
+            message.truncate(message.len() - suffix.len());
+        }
+    }
+
+    let key = segments
+        .iter()
+        .find(|(_, range)| range.contains(&start))
+        .map(|(key, _)| key.to_string());
+    let mut field = None;
+    if let Some(key) = &key {
+        if let Some(rest) = message.strip_prefix(key.as_str()) {
+            if let Some((path, msg)) = rest.split_once(": ") {
+                field = path.strip_prefix('.').map(ToString::to_string);
+                message = msg.to_string();
+            }
+        }
+    }
+
+    ParseDiagnostic::new(s, start..end, message)
+        .with_key(key)
+        .with_field(field)
+}
+
 /// Serialize a bibliography to a YAML string.
 pub fn to_yaml_str(entries: &Library) -> Result<String, serde_yaml::Error> {
     serde_yaml::to_string(&entries)
@@ -39,6 +161,8 @@ pub enum BibLaTeXError {
     Parse(biblatex::ParseError),
     /// One of the BibLaTeX fields was malformed for its type.
     Type(biblatex::TypeError),
+    /// The source could not be read.
+    Io(Arc<std::io::Error>),
 }
 
 #[cfg(feature = "biblatex")]
@@ -47,6 +171,7 @@ impl std::fmt::Display for BibLaTeXError {
         match self {
             Self::Parse(err) => write!(f, "biblatex parse error: {}", err),
             Self::Type(err) => write!(f, "biblatex type error: {}", err),
+            Self::Io(err) => write!(f, "could not read biblatex source: {}", err),
         }
     }
 }
@@ -61,6 +186,53 @@ pub fn from_biblatex_str(biblatex: &str) -> Result<Library, Vec<BibLaTeXError>>
         .map_err(|e| e.into_iter().map(BibLaTeXError::Type).collect())
 }
 
+/// Parse a bibliography from a BibLaTeX source string and report the errors
+/// with their location in the source.
+///
+/// Syntax errors always stop parsing. With [`ErrorMode::CollectAll`], every
+/// malformed field of every entry produces its own diagnostic.
+#[cfg(feature = "biblatex")]
+pub fn from_biblatex_str_diagnostics(
+    biblatex: &str,
+    mode: ErrorMode,
+) -> Result<Library, Vec<ParseDiagnostic>> {
+    let bibliography = Bibliography::parse(biblatex)
+        .map_err(|e| vec![ParseDiagnostic::new(biblatex, e.span, e.kind.to_string())])?;
+
+    let mut entries = vec![];
+    let mut errors = vec![];
+    for entry in bibliography.iter() {
+        match Entry::try_from(entry) {
+            Ok(entry) => entries.push(entry),
+            Err(err) => {
+                let field = entry
+                    .fields
+                    .iter()
+                    .find(|(_, chunks)| {
+                        chunks.iter().any(|c| {
+                            c.span.start <= err.span.start && err.span.end <= c.span.end
+                        })
+                    })
+                    .map(|(name, _)| name.clone());
+                errors.push(
+                    ParseDiagnostic::new(biblatex, err.span, err.kind.to_string())
+                        .with_key(Some(entry.key.clone()))
+                        .with_field(field),
+                );
+                if mode == ErrorMode::FailFast {
+                    break;
+                }
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(entries.into_iter().collect())
+    } else {
+        Err(errors)
+    }
+}
+
 /// Parse a bibliography from a BibLaTeX [`Bibliography`].
 #[cfg(feature = "biblatex")]
 pub fn from_biblatex(bibliography: &Bibliography) -> Result<Library, Vec<TypeError>> {
@@ -81,6 +253,458 @@ pub fn from_biblatex(bibliography: &Bibliography) -> Result<Library, Vec<TypeErr
     }
 }
 
+/// Parse the entries of a BibLaTeX source one at a time while reading it.
+///
+/// Only the entry that is being parsed and the `@string` definitions are kept
+/// in memory, so the caller can filter the entries of a very large file before
+/// storing them. The entries are the same as those of [`from_biblatex_str`],
+/// except that `crossref` and `xdata` fields are not resolved and duplicate
+/// keys are not detected. After an error, parsing continues with the next
+/// entry.
+///
+/// Items may be delimited by braces or parentheses. An `@` that is not
+/// followed by the kind of an item and its opening delimiter is part of a
+/// comment.
+///
+/// ```
+/// use hayagriva::io::from_biblatex_reader;
+///
+/// let bib = "@book{a, title = {A}}\n@article(b, title = {B})";
+/// let keys: Vec<_> = from_biblatex_reader(bib.as_bytes())
+///     .map(|entry| entry.unwrap().key().to_string())
+///     .collect();
+/// assert_eq!(keys, ["a", "b"]);
+/// ```
+#[cfg(feature = "biblatex")]
+pub fn from_biblatex_reader<R: BufRead>(reader: R) -> BibLaTeXReader<R> {
+    BibLaTeXReader {
+        reader,
+        buf: String::new(),
+        offset: 0,
+        scanned: 0,
+        item: None,
+        strings: HashMap::new(),
+        source: String::new(),
+        done: false,
+    }
+}
+
+/// An iterator over the entries of a BibLaTeX source that is read
+/// incrementally. Created by [`from_biblatex_reader`].
+#[cfg(feature = "biblatex")]
+#[derive(Debug)]
+pub struct BibLaTeXReader<R> {
+    reader: R,
+    /// Source that was read but not parsed yet. If an item was found, it
+    /// starts the buffer.
+    buf: String,
+    /// The position of `buf` in the source in bytes.
+    offset: usize,
+    /// How much of `buf` was scanned already.
+    scanned: usize,
+    /// The item whose end is searched for.
+    item: Option<BibLaTeXItem>,
+    /// The `@string` definitions read so far by their names.
+    strings: HashMap<String, BibLaTeXString>,
+    /// The item that is parsed, delimited by braces. The buffer is reused for
+    /// all entries.
+    source: String,
+    /// Whether the reader is exhausted.
+    done: bool,
+}
+
+/// The header of a BibLaTeX item at the start of the buffer and the state of
+/// its delimiters at the scanned position.
+#[cfg(feature = "biblatex")]
+#[derive(Debug)]
+struct BibLaTeXItem {
+    /// The kind of the item, like `article` or `string`.
+    kind: String,
+    /// The position of the opening delimiter.
+    open: usize,
+    /// Whether the item is delimited by parentheses instead of braces.
+    parens: bool,
+    /// The number of braces that are open within the item.
+    depth: usize,
+    /// Whether the scanned position is in a quoted value at the top level of
+    /// an item delimited by parentheses.
+    quoted: bool,
+}
+
+/// The result of looking for the header of an item at an `@`.
+#[cfg(feature = "biblatex")]
+enum BibLaTeXHeader {
+    /// The kind of the item, the position of its opening delimiter, and
+    /// whether that is a parenthesis.
+    Item(String, usize, bool),
+    /// More source is needed to decide.
+    Incomplete,
+    /// The `@` belongs to a comment.
+    Comment,
+}
+
+/// A `@string` definition whose spans refer to the whole source.
+#[cfg(feature = "biblatex")]
+#[derive(Debug)]
+struct BibLaTeXString {
+    key: Range<usize>,
+    value: Range<usize>,
+    chunks: Vec<(BibLaTeXChunk, Range<usize>)>,
+}
+
+/// An owned [`RawChunk`].
+#[cfg(feature = "biblatex")]
+#[derive(Debug)]
+enum BibLaTeXChunk {
+    Normal(String),
+    Abbreviation(String),
+}
+
+#[cfg(feature = "biblatex")]
+impl<R: BufRead> BibLaTeXReader<R> {
+    /// Continue scanning the buffer and return the end of the next complete
+    /// item. Text before the item is dropped.
+    fn scan(&mut self) -> Option<usize> {
+        loop {
+            let Some(item) = &mut self.item else {
+                let Some(at) = self.buf[self.scanned..].find('@') else {
+                    self.drain(self.buf.len());
+                    return None;
+                };
+
+                let at = self.scanned + at;
+                match biblatex_header(&self.buf[at..]) {
+                    BibLaTeXHeader::Item(kind, open, parens) => {
+                        self.drain(at);
+                        self.scanned = open + 1;
+                        self.item = Some(BibLaTeXItem {
+                            kind,
+                            open,
+                            parens,
+                            depth: 0,
+                            quoted: false,
+                        });
+                    }
+                    BibLaTeXHeader::Incomplete => {
+                        self.drain(at);
+                        return None;
+                    }
+                    BibLaTeXHeader::Comment => self.scanned = at + 1,
+                }
+                continue;
+            };
+
+            let bytes = self.buf.as_bytes();
+            let mut i = self.scanned;
+            while i < bytes.len() {
+                match bytes[i] {
+                    // Wait for the escaped character.
+                    b'\\' if i + 1 == bytes.len() => break,
+                    b'\\' => i += 1,
+                    b'{' => item.depth += 1,
+                    b'}' if item.depth > 0 => item.depth -= 1,
+                    b'}' if !item.parens => return Some(i + 1),
+                    b'"' if item.parens && item.depth == 0 => item.quoted = !item.quoted,
+                    b')' if item.parens && item.depth == 0 && !item.quoted => {
+                        return Some(i + 1)
+                    }
+                    _ => {}
+                }
+                i += 1;
+            }
+
+            self.scanned = i;
+            return None;
+        }
+    }
+
+    /// Drop the start of the buffer.
+    fn drain(&mut self, len: usize) {
+        self.buf.drain(..len);
+        self.offset += len;
+        self.scanned = 0;
+    }
+
+    /// Parse the item that ends at `end` of the buffer and drop it. Returns
+    /// `None` for items that are not entries.
+    fn parse(
+        &mut self,
+        end: usize,
+        closed: bool,
+    ) -> Option<Result<Entry, BibLaTeXError>> {
+        let item = self.item.take()?;
+        self.source.clear();
+        self.source.push_str(&self.buf[..end]);
+        if item.parens {
+            self.source.replace_range(item.open..item.open + 1, "{");
+            if closed {
+                self.source.replace_range(end - 1..end, "}");
+            }
+        }
+
+        let offset = self.offset;
+        self.drain(end);
+
+        let kind = item.kind.to_ascii_lowercase();
+        if kind == "comment" || kind == "preamble" {
+            return None;
+        }
+
+        let shift = |span: &mut Range<usize>| {
+            *span = span.start + offset..span.end + offset;
+        };
+
+        let mut raw = match RawBibliography::parse(&self.source) {
+            Ok(raw) => raw,
+            Err(mut err) => {
+                shift(&mut err.span);
+                return Some(Err(BibLaTeXError::Parse(err)));
+            }
+        };
+
+        if kind == "string" {
+            for mut pair in raw.abbreviations {
+                shift(&mut pair.key.span);
+                shift(&mut pair.value.span);
+                let chunks = pair
+                    .value
+                    .v
+                    .into_iter()
+                    .map(|mut chunk| {
+                        shift(&mut chunk.span);
+                        let owned = match chunk.v {
+                            RawChunk::Normal(s) => BibLaTeXChunk::Normal(s.into()),
+                            RawChunk::Abbreviation(s) => {
+                                BibLaTeXChunk::Abbreviation(s.into())
+                            }
+                        };
+                        (owned, chunk.span)
+                    })
+                    .collect();
+
+                // The first definition of a name is used.
+                self.strings.entry(pair.key.v.into()).or_insert(BibLaTeXString {
+                    key: pair.key.span,
+                    value: pair.value.span,
+                    chunks,
+                });
+            }
+            return None;
+        }
+
+        for entry in &mut raw.entries {
+            shift(&mut entry.span);
+            shift(&mut entry.v.key.span);
+            shift(&mut entry.v.kind.span);
+            for pair in &mut entry.v.fields {
+                shift(&mut pair.key.span);
+                shift(&mut pair.value.span);
+                for chunk in &mut pair.value.v {
+                    shift(&mut chunk.span);
+                }
+            }
+        }
+
+        // Only pass the definitions that the entry uses.
+        let mut names: Vec<&str> = raw
+            .entries
+            .iter()
+            .flat_map(|entry| &entry.v.fields)
+            .flat_map(|pair| &pair.value.v)
+            .filter_map(|chunk| match chunk.v {
+                RawChunk::Abbreviation(name) => Some(name),
+                RawChunk::Normal(_) => None,
+            })
+            .collect();
+        let mut abbreviations: Vec<Pair> = vec![];
+        while let Some(name) = names.pop() {
+            if abbreviations.iter().any(|pair| pair.key.v == name) {
+                continue;
+            }
+
+            let Some((name, string)) = self.strings.get_key_value(name) else {
+                continue;
+            };
+            let chunks = string
+                .chunks
+                .iter()
+                .map(|(chunk, span)| {
+                    let chunk = match chunk {
+                        BibLaTeXChunk::Normal(s) => RawChunk::Normal(s),
+                        BibLaTeXChunk::Abbreviation(s) => {
+                            names.push(s);
+                            RawChunk::Abbreviation(s)
+                        }
+                    };
+                    Spanned::new(chunk, span.clone())
+                })
+                .collect();
+            abbreviations.push(Pair::new(
+                Spanned::new(name, string.key.clone()),
+                Spanned::new(chunks, string.value.clone()),
+            ));
+        }
+        raw.abbreviations = abbreviations;
+
+        let span = raw.entries.first().map(|entry| entry.span.clone());
+        let res = Bibliography::from_raw(raw).map_err(BibLaTeXError::Parse).and_then(
+            |bibliography| {
+                let entry =
+                    bibliography.into_vec().into_iter().next().ok_or_else(|| {
+                        BibLaTeXError::Parse(biblatex::ParseError {
+                            span: span.unwrap_or(offset..offset + end),
+                            kind: biblatex::ParseErrorKind::Expected(
+                                biblatex::Token::Identifier,
+                            ),
+                        })
+                    })?;
+                Entry::try_from(&entry).map_err(BibLaTeXError::Type)
+            },
+        );
+
+        Some(res)
+    }
+}
+
+#[cfg(feature = "biblatex")]
+impl<R: BufRead> Iterator for BibLaTeXReader<R> {
+    type Item = Result<Entry, BibLaTeXError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(end) = self.scan() {
+                match self.parse(end, true) {
+                    Some(res) => return Some(res),
+                    None => continue,
+                }
+            }
+
+            if self.done {
+                // Report an unterminated item.
+                let len = self.buf.len();
+                self.item.as_ref()?;
+                return self.parse(len, false);
+            }
+
+            match self.reader.read_line(&mut self.buf) {
+                Ok(0) => self.done = true,
+                Ok(_) => {}
+                Err(err) => {
+                    self.done = true;
+                    return Some(Err(BibLaTeXError::Io(Arc::new(err))));
+                }
+            }
+        }
+    }
+}
+
+/// Look for the header of a BibLaTeX item, like `@article{`, at the start of
+/// the source.
+#[cfg(feature = "biblatex")]
+fn biblatex_header(src: &str) -> BibLaTeXHeader {
+    let rest = &src[1..];
+    let len = rest
+        .find(|c: char| !c.is_ascii_alphanumeric() && c != '_' && c != '-')
+        .unwrap_or(rest.len());
+    if len == rest.len() {
+        return BibLaTeXHeader::Incomplete;
+    } else if !rest.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return BibLaTeXHeader::Comment;
+    }
+
+    let kind = &rest[..len];
+    let after = rest[len..].trim_start();
+    let open = src.len() - after.len();
+    match after.chars().next() {
+        None => BibLaTeXHeader::Incomplete,
+        Some('{') => BibLaTeXHeader::Item(kind.into(), open, false),
+        Some('(') => BibLaTeXHeader::Item(kind.into(), open, true),
+        Some(_) => BibLaTeXHeader::Comment,
+    }
+}
+
+/// Whether parsing stops at the first error.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ErrorMode {
+    /// Report only the first error.
+    #[default]
+    FailFast,
+    /// Continue after an error and report the errors of all entries.
+    CollectAll,
+}
+
+/// An error in a bibliography file with its location in the source.
+///
+/// Its [`Display`](fmt::Display) implementation prints the offending line
+/// and marks the span with carets.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ParseDiagnostic {
+    /// The byte range of the offending source.
+    pub span: Range<usize>,
+    /// The line of the start of the span, starting at 1.
+    pub line: usize,
+    /// The column of the start of the span in characters, starting at 1.
+    pub column: usize,
+    /// The key of the entry that was being parsed, if known.
+    pub key: Option<String>,
+    /// The field that was being parsed, if known.
+    pub field: Option<String>,
+    /// What went wrong.
+    pub message: String,
+    /// The source line with the start of the span.
+    source_line: String,
+}
+
+impl ParseDiagnostic {
+    fn new(src: &str, span: Range<usize>, message: String) -> Self {
+        let start = span.start.min(src.len());
+        let line_start = src[..start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = src[start..].find('\n').map_or(src.len(), |i| start + i);
+        Self {
+            line: src[..start].matches('\n').count() + 1,
+            column: src[line_start..start].chars().count() + 1,
+            span,
+            key: None,
+            field: None,
+            message,
+            source_line: src[line_start..line_end].trim_end_matches('\r').to_string(),
+        }
+    }
+
+    fn with_key(mut self, key: Option<String>) -> Self {
+        self.key = key;
+        self
+    }
+
+    fn with_field(mut self, field: Option<String>) -> Self {
+        self.field = field;
+        self
+    }
+}
+
+impl fmt::Display for ParseDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "error: {}", self.message)?;
+        let gutter = " ".repeat(self.line.to_string().len());
+        write!(f, "{gutter}--> {}:{}", self.line, self.column)?;
+        match (&self.key, &self.field) {
+            (Some(key), Some(field)) => write!(f, " (entry `{key}`, field `{field}`)")?,
+            (Some(key), None) => write!(f, " (entry `{key}`)")?,
+            _ => {}
+        }
+
+        // Mark the span up to the end of the first line.
+        let width = self.source_line.chars().count().saturating_sub(self.column - 1);
+        let carets = self.span.len().clamp(1, width.max(1));
+        writeln!(f)?;
+        writeln!(f, "{gutter} |")?;
+        writeln!(f, "{} | {}", self.line, self.source_line)?;
+        write!(f, "{gutter} | {}{}", " ".repeat(self.column - 1), "^".repeat(carets))
+    }
+}
+
+impl core::error::Error for ParseDiagnostic {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -101,4 +725,173 @@ mod tests {
             assert_eq!(match_e, &entry);
         }
     }
+
+    #[test]
+    fn yaml_diagnostics() {
+        let yaml = r#"a:
+    type: Book
+    title: A
+    date: soon
+b:
+    type: Book
+    title: B
+c:
+    type: Book
+    author: 3
+d:
+    type: Book
+    date: 2020-13-45
+"#;
+
+        let errors = from_yaml_str_diagnostics(yaml, ErrorMode::FailFast).unwrap_err();
+        assert_eq!(errors.len(), 1);
+
+        let errors = from_yaml_str_diagnostics(yaml, ErrorMode::CollectAll).unwrap_err();
+        let found: Vec<_> = errors
+            .iter()
+            .map(|e| (e.line, e.column, e.key.as_deref(), e.field.as_deref()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (4, 11, Some("a"), Some("date")),
+                (10, 13, Some("c"), Some("author")),
+                (13, 11, Some("d"), Some("date")),
+            ]
+        );
+        assert_eq!(&yaml[errors[0].span.clone()], "soon");
+        assert_eq!(
+            errors[0].to_string(),
+            "error: date format unknown\n \
+             --> 4:11 (entry `a`, field `date`)\n  \
+             |\n\
+             4 |     date: soon\n  \
+             |           ^^^^"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "biblatex")]
+    fn biblatex_diagnostics() {
+        let bib = r#"@book{a,
+    title = {A},
+    editor = {Doe, Jane},
+    editortype = {bogus},
+}
+
+@book{b,
+    title = {B},
+}
+
+@book{c,
+    title = {C},
+    editor = {Doe, Jane},
+    editortype = {nobody},
+}
+"#;
+
+        let errors =
+            from_biblatex_str_diagnostics(bib, ErrorMode::CollectAll).unwrap_err();
+        let found: Vec<_> = errors
+            .iter()
+            .map(|e| (e.line, e.column, e.key.as_deref(), e.field.as_deref()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (4, 19, Some("a"), Some("editortype")),
+                (14, 19, Some("c"), Some("editortype"))
+            ]
+        );
+
+        let errors = from_biblatex_str_diagnostics(bib, ErrorMode::FailFast).unwrap_err();
+        assert_eq!(errors.len(), 1);
+
+        let errors = from_biblatex_str_diagnostics(
+            "@book{a,\n  title = {A\n",
+            ErrorMode::CollectAll,
+        )
+        .unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!((errors[0].line, errors[0].key.as_deref()), (3, None));
+    }
+
+    #[test]
+    #[cfg(feature = "biblatex")]
+    fn biblatex_streaming() {
+        let mut bib = String::from(
+            "This file was exported.\n\
+             @string{acm = {Association for Computing Machinery}}\n\
+             @comment{Generated entries follow.}\n",
+        );
+        for i in 0..2000 {
+            bib.push_str(&format!(
+                "@article{{entry{i},\n  \
+                   title = {{Title {{With}} Braces {i}}},\n  \
+                   author = {{Doe, Jane and Roe, Richard}},\n  \
+                   publisher = acm,\n  \
+                   date = {{{}}},\n\
+                 }}\n",
+                1900 + i % 100
+            ));
+        }
+
+        let batch = from_biblatex_str(&bib).unwrap();
+        let streamed: Library = from_biblatex_reader(bib.as_bytes())
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(streamed.len(), 2000);
+        assert_eq!(streamed, batch);
+
+        // Entries can be filtered while reading.
+        let first = from_biblatex_reader(bib.as_bytes())
+            .filter_map(Result::ok)
+            .find(|entry| entry.key() == "entry1999")
+            .unwrap();
+        assert_eq!(&first, batch.get("entry1999").unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "biblatex")]
+    fn biblatex_streaming_errors() {
+        let bib = "@book{a, title = {A}}\n\
+                   @book{b, title = {B}, editor = {Doe, Jane}, editortype = {bogus}}\n\
+                   @book{c, title = {C}}\n\
+                   @book{d, title = {D";
+        let res: Vec<_> = from_biblatex_reader(bib.as_bytes()).collect();
+        assert_eq!(res.len(), 4);
+        assert_eq!(res[0].as_ref().unwrap().key(), "a");
+        assert_eq!(res[2].as_ref().unwrap().key(), "c");
+
+        // Spans refer to the whole source.
+        let Err(BibLaTeXError::Type(err)) = &res[1] else { panic!() };
+        assert_eq!(&bib[err.span.clone()], "bogus");
+        assert!(matches!(res[3], Err(BibLaTeXError::Parse(_))));
+    }
+
+    #[test]
+    #[cfg(feature = "biblatex")]
+    fn biblatex_streaming_delimiters() {
+        let bib = "Send corrections to jane@example.com.\n\
+                   @string(acm = \"Association for \" # mach)\n\
+                   @string{mach = {Computing Machinery}}\n\
+                   @book(a,\n  title = {Parentheses (Nested)},\n  publisher = acm,\n)\n\
+                   @book\n{b, title = {B}, note = {An @ in a field}}\n\
+                   @misc(c, title = \"Closing ) in quotes\", publisher = nope)\n";
+        let res: Vec<_> = from_biblatex_reader(bib.as_bytes()).collect();
+        assert_eq!(res.len(), 3);
+
+        let batch = from_biblatex_str(
+            "@string{mach = {Computing Machinery}}\n\
+             @string{acm = \"Association for \" # mach}\n\
+             @book{a, title = {Parentheses (Nested)}, publisher = acm}\n\
+             @book{b, title = {B}, note = {An @ in a field}}",
+        )
+        .unwrap();
+        assert_eq!(res[0].as_ref().unwrap(), batch.get("a").unwrap());
+        assert_eq!(res[1].as_ref().unwrap(), batch.get("b").unwrap());
+
+        let Err(BibLaTeXError::Parse(err)) = &res[2] else { panic!() };
+        assert_eq!(&bib[err.span.clone()], "nope");
+    }
 }
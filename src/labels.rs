@@ -0,0 +1,284 @@
+//! Labels for bibliographies that identify entries by a short code.
+//!
+//! These functions only compute the labels, like `[Knu84]` or `[12]`, so that
+//! typesetters can place them next to a bibliography entry that was formatted
+//! with any style.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use crate::types::PersonView;
+use crate::Entry;
+
+/// Words that are skipped when abbreviating the name of an institution.
+const MINOR_WORDS: &[&str] = &["and", "for", "of", "on", "the"];
+
+/// Compute alphabetic labels like `[Knu84]` or `[ABC+20]` for the entries.
+///
+/// The label of each entry is made of the first three letters of the family
+/// name of a single author or the initials of up to four authors, followed by
+/// a plus if there are more, and the last two digits of the year. Entries
+/// without authors use their editors or the start of their title.
+///
+/// Entries with the same label get the suffixes `a`, `b`, and so on in the
+/// order in which they appear in `entries`. Use [`alpha_by`] to make the
+/// suffixes independent from that order.
+pub fn alpha(entries: &[Entry]) -> Vec<String> {
+    alpha_with(entries, |a, b| a.cmp(&b))
+}
+
+/// Compute alphabetic labels like [`alpha`], but assign the suffixes of
+/// entries with the same label in the order given by `compare`.
+///
+/// Entries that `compare` considers equal are ordered by their key. Hence, the
+/// label of an entry does not depend on its position in `entries`.
+pub fn alpha_by<F>(entries: &[Entry], mut compare: F) -> Vec<String>
+where
+    F: FnMut(&Entry, &Entry) -> Ordering,
+{
+    alpha_with(entries, |a, b| {
+        compare(&entries[a], &entries[b])
+            .then_with(|| entries[a].key().cmp(entries[b].key()))
+    })
+}
+
+/// Compute alphabetic labels and order the entries with the same label by
+/// comparing their indices with `compare`.
+fn alpha_with<F>(entries: &[Entry], mut compare: F) -> Vec<String>
+where
+    F: FnMut(usize, usize) -> Ordering,
+{
+    let mut labels: Vec<String> = entries.iter().map(base_label).collect();
+
+    let mut collisions: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (i, label) in labels.iter().enumerate() {
+        collisions.entry(label.as_str()).or_default().push(i);
+    }
+
+    let mut suffixes = vec![String::new(); entries.len()];
+    for mut indices in collisions.into_values().filter(|indices| indices.len() > 1) {
+        indices.sort_by(|&a, &b| compare(a, b));
+        for (n, i) in indices.into_iter().enumerate() {
+            suffixes[i] = suffix(n);
+        }
+    }
+
+    for (label, suffix) in labels.iter_mut().zip(suffixes) {
+        label.push_str(&suffix);
+        label.insert(0, '[');
+        label.push(']');
+    }
+
+    labels
+}
+
+/// Compute numeric labels like `[1]` for the entries.
+///
+/// The entry at position `k` of the bibliography has the index `order[k]`, so
+/// `order` must contain each index of the entries exactly once. The label of
+/// the entry with index `i` is at position `i` of the result.
+pub fn numeric(order: &[usize]) -> Vec<String> {
+    let mut labels = vec![String::new(); order.len()];
+    for (k, &i) in order.iter().enumerate() {
+        labels[i] = format!("[{}]", k + 1);
+    }
+    labels
+}
+
+/// The label of an entry before collisions are resolved.
+fn base_label(entry: &Entry) -> String {
+    let names = entry
+        .authors()
+        .filter(|a| !a.is_empty())
+        .or_else(|| entry.editors().filter(|e| !e.is_empty()));
+
+    let mut label = match names {
+        Some([single]) => match single.view() {
+            PersonView::Individual { family, .. } => family.chars().take(3).collect(),
+            PersonView::Institution(name) => institution_label(name),
+        },
+        Some(names) => {
+            let mut label: String = names
+                .iter()
+                .take(if names.len() > 4 { 3 } else { 4 })
+                .filter_map(|person| match person.view() {
+                    PersonView::Individual { family, .. } => family.chars().next(),
+                    PersonView::Institution(name) => name.chars().next(),
+                })
+                .collect();
+            if names.len() > 4 {
+                label.push('+');
+            }
+            label
+        }
+        None => entry
+            .title()
+            .map(|title| {
+                title
+                    .value
+                    .to_str()
+                    .chars()
+                    .filter(|c| c.is_alphanumeric())
+                    .take(3)
+                    .collect()
+            })
+            .unwrap_or_else(|| entry.key().chars().take(3).collect()),
+    };
+
+    if let Some(date) = entry.date_any() {
+        label.push_str(&format!("{:02}", date.year.rem_euclid(100)));
+    }
+
+    label
+}
+
+/// Abbreviate an institution by the initials of its significant words or by
+/// its first three letters if it is a single word.
+fn institution_label(name: &str) -> String {
+    let initials: String = name
+        .split_whitespace()
+        .filter(|word| !MINOR_WORDS.contains(&word.to_lowercase().as_str()))
+        .filter_map(|word| word.chars().next())
+        .take(4)
+        .collect();
+
+    if initials.chars().count() > 1 {
+        initials
+    } else {
+        name.chars().take(3).collect()
+    }
+}
+
+/// The suffix for the `n`th entry with the same label: `a`, …, `z`, `aa`, ….
+fn suffix(mut n: usize) -> String {
+    let mut suffix = String::new();
+    loop {
+        suffix.insert(0, (b'a' + (n % 26) as u8) as char);
+        if n < 26 {
+            break;
+        }
+        n = n / 26 - 1;
+    }
+    suffix
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::from_yaml_str;
+
+    fn labels(yaml: &str) -> Vec<String> {
+        let library = from_yaml_str(yaml).unwrap();
+        let entries: Vec<Entry> = library.iter().cloned().collect();
+        alpha(&entries)
+    }
+
+    #[test]
+    fn single_author() {
+        assert_eq!(
+            labels(
+                r#"
+                taocp:
+                    type: book
+                    title: The Art of Computer Programming
+                    author: Knuth, Donald
+                    date: 1968
+                ox:
+                    type: book
+                    title: A Book
+                    author: Ox, Jane
+                    date: 2005
+                "#
+            ),
+            vec!["[Knu68]", "[Ox05]"]
+        );
+    }
+
+    #[test]
+    fn multiple_authors() {
+        assert_eq!(
+            labels(
+                r#"
+                two:
+                    type: article
+                    title: Two
+                    author: ["Aho, Alfred", "Ullman, Jeffrey"]
+                    date: 1977
+                five:
+                    type: article
+                    title: Five
+                    author: ["Ant, A", "Bee, B", "Cat, C", "Dog, D", "Eel, E"]
+                    date: 2020
+                "#
+            ),
+            vec!["[AU77]", "[ABC+20]"]
+        );
+    }
+
+    #[test]
+    fn institutions() {
+        assert_eq!(
+            labels(
+                r#"
+                who:
+                    type: report
+                    title: World Health Statistics
+                    author: World Health Organization
+                    date: 2019
+                nasa:
+                    type: report
+                    title: Annual Report
+                    author: NASA
+                    date: 2001
+                untitled:
+                    type: misc
+                "#
+            ),
+            vec!["[WHO19]", "[NAS01]", "[unt]"]
+        );
+    }
+
+    #[test]
+    fn collisions() {
+        let library = from_yaml_str(
+            r#"
+            b:
+                type: article
+                title: Second
+                author: Smith, Jane
+                date: 2020
+            a:
+                type: article
+                title: First
+                author: Smith, John
+                date: 2020
+            other:
+                type: article
+                title: Other
+                author: Smith, Jane
+                date: 2021
+            "#,
+        )
+        .unwrap();
+        let mut entries: Vec<Entry> = library.iter().cloned().collect();
+
+        assert_eq!(alpha(&entries), vec!["[Smi20a]", "[Smi20b]", "[Smi21]"]);
+
+        let by_title = |a: &Entry, b: &Entry| {
+            a.title()
+                .map(|t| t.value.to_str())
+                .cmp(&b.title().map(|t| t.value.to_str()))
+        };
+        assert_eq!(alpha_by(&entries, by_title), vec!["[Smi20b]", "[Smi20a]", "[Smi21]"]);
+        entries.reverse();
+        assert_eq!(alpha_by(&entries, by_title), vec!["[Smi21]", "[Smi20a]", "[Smi20b]"]);
+    }
+
+    #[test]
+    fn numeric_labels() {
+        assert_eq!(numeric(&[2, 0, 1]), vec!["[2]", "[3]", "[1]"]);
+        assert_eq!(suffix(0), "a");
+        assert_eq!(suffix(25), "z");
+        assert_eq!(suffix(26), "aa");
+    }
+}
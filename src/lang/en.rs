@@ -481,3 +481,10 @@ pub const ALWAYS_CAPITALIZE: [&str; 426] = [
     "zambia",
     "zimbabwe",
 ];
+
+/// Lowercase abbreviations that end in a period, like "ed." or "Inc.", sorted
+/// for binary search.
+pub const ABBREVIATIONS: [&str; 22] = [
+    "al", "bros", "ch", "chap", "co", "corp", "ed", "eds", "etc", "inc", "jr", "ltd",
+    "no", "nos", "p", "pp", "sr", "st", "supp", "trans", "vol", "vols",
+];
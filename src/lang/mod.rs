@@ -3,12 +3,17 @@
 pub(crate) mod en;
 pub(crate) mod name;
 
-use std::{fmt::Write, mem};
+use alloc::sync::Arc;
+use core::{fmt::Write, mem};
+#[cfg(feature = "std")]
+use std::cell::RefCell;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
 
 use crate::types::{FoldableKind, FoldableStringChunk};
 
 /// Rules for the title case transformation.
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 #[non_exhaustive]
 pub struct TitleCase {
     /// Always capitalize after a full stop, question or exclamation mark, and
@@ -58,7 +63,7 @@ impl TitleCase {
 }
 
 /// Rules for the sentence case transformation.
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 #[non_exhaustive]
 pub struct SentenceCase {
     /// Capitalize words that contain caps
@@ -98,8 +103,43 @@ impl SentenceCase {
     }
 }
 
+/// Words and phrases whose spelling is kept by the sentence case
+/// transformation.
+///
+/// They are matched without regard to case at word boundaries and may consist
+/// of several words.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct CaseExceptions(Option<Arc<[String]>>);
+
+impl CaseExceptions {
+    /// Create a list of exceptions from words and phrases.
+    pub fn new(words: &[&str]) -> Self {
+        let mut words: Vec<String> = words
+            .iter()
+            .map(|word| word.trim())
+            .filter(|word| !word.is_empty())
+            .map(ToString::to_string)
+            .collect();
+        // Try longer phrases first, so that "Large Hadron Collider" wins over
+        // "Collider".
+        words.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+        words.dedup();
+        Self((!words.is_empty()).then(|| words.into()))
+    }
+
+    /// Iterate over the words and phrases, longest first.
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.0.iter().flat_map(|words| words.iter()).map(String::as_str)
+    }
+
+    /// Whether there are no exceptions.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_none()
+    }
+}
+
 /// Which case to transform to
-#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
 pub enum Case {
     /// Capitalize all words except for some words using customary English rules.
     Title(TitleCase),
@@ -217,6 +257,8 @@ pub struct CaseFolder {
     last_reconfig: usize,
     /// Index of the last word in the buffer.
     last_word: Option<WordData>,
+    /// Words and phrases whose spelling sentence case keeps.
+    exceptions: CaseExceptions,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -310,6 +352,26 @@ impl WordData {
         matches!(self.case, WordCase::AllUpper) && self.end - self.start > 1
     }
 
+    /// Move the word by `offset` bytes.
+    #[cfg(feature = "std")]
+    fn shifted(self, offset: usize) -> Self {
+        Self {
+            start: self.start + offset,
+            end: self.end + offset,
+            ..self
+        }
+    }
+
+    /// Move the word back by `offset` bytes.
+    #[cfg(feature = "std")]
+    fn unshifted(self, offset: usize) -> Self {
+        Self {
+            start: self.start - offset,
+            end: self.end - offset,
+            ..self
+        }
+    }
+
     fn is_continuing_word(self, other: Option<Self>) -> bool {
         match other {
             Some(other) => self.start == other.start,
@@ -318,6 +380,32 @@ impl WordData {
     }
 }
 
+/// The result of pushing a string to a fresh [`CaseFolder`], with the last
+/// word relative to the start of the string.
+#[cfg(feature = "std")]
+struct Folded {
+    text: String,
+    char_class: CharClass,
+    last_word: Option<WordData>,
+}
+
+/// The maximum number of cached strings per thread. The cache is cleared when
+/// it is full.
+#[cfg(feature = "std")]
+const FOLD_CACHE_CAPACITY: usize = 1024;
+
+/// The maximum length of a cached string in bytes.
+#[cfg(feature = "std")]
+const FOLD_CACHE_MAX_LEN: usize = 512;
+
+#[cfg(feature = "std")]
+thread_local! {
+    /// Case-transformed strings, such as the titles of periodicals shared by
+    /// many entries.
+    static FOLD_CACHE: RefCell<HashMap<Case, HashMap<String, Folded>>> =
+        RefCell::default();
+}
+
 enum WordVerdict {
     AllUpper,
     Capitalize,
@@ -334,6 +422,7 @@ impl Default for CaseFolder {
             buf: String::new(),
             last_reconfig: 0,
             last_word: None,
+            exceptions: CaseExceptions::default(),
         }
     }
 }
@@ -364,16 +453,66 @@ impl CaseFolder {
                 self.buf.extend(s.chars().flat_map(char::to_lowercase));
                 self.char_class = CharClass::default();
             }
-            _ => {
-                for c in s.chars() {
-                    self.push(c);
-                }
-            }
+            _ => self.push_cached(s),
         }
 
         self.pristine = false;
     }
 
+    /// Add a string that is transformed character by character to the buffer,
+    /// reusing the result of an earlier call with the same string.
+    ///
+    /// Only strings pushed to a folder that has not seen any text since it was
+    /// created or reconfigured are cached because the result does not depend
+    /// on the text before them.
+    #[cfg(feature = "std")]
+    fn push_cached(&mut self, s: &str) {
+        let fresh = self.pristine
+            && self.last_word.is_none()
+            && self.char_class == CharClass::default();
+        // The cache does not know about the exceptions.
+        if !fresh || s.len() > FOLD_CACHE_MAX_LEN || !self.exceptions.is_empty() {
+            s.chars().for_each(|c| self.push(c));
+            return;
+        }
+
+        let start = self.buf.len();
+        let hit = FOLD_CACHE.with(|cache| {
+            let cache = cache.borrow();
+            let folded = cache.get(&self.case)?.get(s)?;
+            self.buf.push_str(&folded.text);
+            self.char_class = folded.char_class;
+            self.last_word = folded.last_word.map(|word| word.shifted(start));
+            Some(())
+        });
+
+        if hit.is_some() {
+            return;
+        }
+
+        s.chars().for_each(|c| self.push(c));
+        let folded = Folded {
+            text: self.buf[start..].to_string(),
+            char_class: self.char_class,
+            last_word: self.last_word.map(|word| word.unshifted(start)),
+        };
+
+        FOLD_CACHE.with(|cache| {
+            let mut cache = cache.borrow_mut();
+            if cache.values().map(HashMap::len).sum::<usize>() >= FOLD_CACHE_CAPACITY {
+                cache.clear();
+            }
+            cache.entry(self.case).or_default().insert(s.to_string(), folded);
+        });
+    }
+
+    /// Add a string that is transformed character by character to the buffer.
+    /// Without the standard library, there is no cache to reuse results from.
+    #[cfg(not(feature = "std"))]
+    fn push_cached(&mut self, s: &str) {
+        s.chars().for_each(|c| self.push(c));
+    }
+
     /// Add a string chunk to the buffer.
     pub fn push_verbatim(&mut self, value: &str) {
         let conf = mem::replace(&mut self.case, Case::NoTransform);
@@ -392,6 +531,18 @@ impl CaseFolder {
         }
     }
 
+    /// Yield the text so far for a verbatim chunk that is kept elsewhere. The
+    /// text after it is transformed as if it started a new sentence, and the
+    /// words before it can no longer be changed, like the last word in title
+    /// case.
+    pub(crate) fn take_before_verbatim(&mut self) -> String {
+        self.char_class = CharClass::default();
+        self.pristine = false;
+        self.last_word = None;
+        self.last_reconfig = 0;
+        mem::take(&mut self.buf)
+    }
+
     /// Add a character to the buffer.
     pub fn push(&mut self, c: char) {
         let prev_class = self.char_class;
@@ -571,6 +722,14 @@ impl CaseFolder {
             },
             WordVerdict::Keep => {}
         }
+
+        if matches!(self.case, Case::Sentence(_)) && !self.exceptions.is_empty() {
+            let end = data.start
+                + self.buf[data.start..data.end]
+                    .trim_end_matches(|c: char| !c.is_alphanumeric())
+                    .len();
+            keep_exception(&mut self.buf, end, self.last_reconfig, &self.exceptions);
+        }
     }
 
     fn may_trim_end(&mut self) {
@@ -613,6 +772,17 @@ impl CaseFolder {
         self.case
     }
 
+    /// Retrieve the words and phrases whose spelling sentence case keeps.
+    pub fn exceptions(&self) -> &CaseExceptions {
+        &self.exceptions
+    }
+
+    /// Keep the spelling of these words and phrases in sentence case. They
+    /// apply to all text that is added in sentence case from now on.
+    pub fn set_exceptions(&mut self, exceptions: CaseExceptions) {
+        self.exceptions = exceptions;
+    }
+
     /// Change the configuration of the CaseFolder.
     pub fn reconfigure(&mut self, case: Case) {
         if self.case == case {
@@ -647,6 +817,11 @@ impl CaseFolder {
         !self.buf.chars().all(char::is_whitespace)
     }
 
+    /// Yield the buffer as a string.
+    pub fn as_str(&self) -> &str {
+        &self.buf
+    }
+
     /// Yield the buffer as a mutable string. Must call [`Self::mark_changed`]
     /// if the length of the string is changed.
     pub(crate) fn as_string_mut(&mut self) -> &mut String {
@@ -723,6 +898,29 @@ fn capitalize_char(buf: &mut String, start: usize) {
     }
 }
 
+/// Restore the spelling of an exception that ends at `end` in the buffer and
+/// does not start before `min_start`.
+fn keep_exception(
+    buf: &mut String,
+    end: usize,
+    min_start: usize,
+    exceptions: &CaseExceptions,
+) {
+    for exception in exceptions.iter() {
+        let Some(start) = end.checked_sub(exception.len()) else { continue };
+        if start < min_start || !buf.is_char_boundary(start) {
+            continue;
+        }
+
+        let at_boundary =
+            buf[..start].chars().next_back().is_none_or(|c| !c.is_alphanumeric());
+        if at_boundary && buf[start..end].to_lowercase() == exception.to_lowercase() {
+            buf.replace_range(start..end, exception);
+            return;
+        }
+    }
+}
+
 /// Check if a character is a CJK character.
 pub(crate) fn is_cjk(c: char) -> bool {
     let cp: u32 = c.into();
@@ -995,6 +1193,72 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "std")]
+    #[test]
+    fn cached_folding() {
+        let fold = |case: Case, parts: &[&str]| {
+            let mut folder = CaseFolder::new();
+            folder.push_str("In: ");
+            folder.reconfigure(case);
+            for part in parts {
+                folder.push_str(part);
+            }
+            folder.finish()
+        };
+
+        for case in [TitleCase::new().into(), SentenceCase::new().into()] {
+            for title in [
+                "journal of the american society for information science",
+                "  the NASA report on iPhones of",
+                "a study of",
+            ] {
+                FOLD_CACHE.with(|cache| cache.borrow_mut().clear());
+                let expected = fold(case, &[title]);
+                let continued = fold(case, &[&format!("{title}s")]);
+
+                // Words at the end of a cached string are finished by the text
+                // pushed after it.
+                assert_eq!(fold(case, &[title]), expected);
+                assert_eq!(fold(case, &[title, "s"]), continued);
+            }
+        }
+    }
+
+    #[test]
+    fn sentence_case_exceptions() {
+        let exceptions = CaseExceptions::new(&[
+            "iPhone",
+            "BRCA1",
+            "Large Hadron Collider",
+            "Collider",
+        ]);
+        let transform = |s: &str| {
+            let mut folder = CaseFolder::with_config(SentenceCase::new().into());
+            folder.set_exceptions(exceptions.clone());
+            folder.push_str(s);
+            folder.finish()
+        };
+
+        assert_eq!(
+            transform("iPhone Sales and the Large Hadron Collider"),
+            "iPhone sales and the Large Hadron Collider"
+        );
+        assert_eq!(
+            transform("Expression of Brca1 in the large hadron collider, Revisited"),
+            "Expression of BRCA1 in the Large Hadron Collider, revisited"
+        );
+
+        // Parts of words and of other phrases are not exceptions.
+        assert_eq!(
+            transform("The Supercollider and the Small Hadron Collider"),
+            "The supercollider and the small hadron Collider"
+        );
+        assert_eq!(
+            Case::from(SentenceCase::new()).transform("iPhone Sales"),
+            "Iphone sales"
+        );
+    }
+
     #[test]
     fn sentence_case_no_transform() {
         let case: Case = SentenceCase::new().into();
@@ -38,8 +38,8 @@ assert_eq!(bib.get("crazy-rich").unwrap().date().unwrap().year, 2014);
 // Format the reference
 use std::fs;
 use hayagriva::{
-    BibliographyDriver, BibliographyRequest, BufWriteFormat,
-    CitationItem, CitationRequest,
+    BibliographyDriver, BibliographyRequest, BufWriteFormat, CitationItem,
+    CitationRequest,
 };
 use hayagriva::citationberg::{LocaleFile, IndependentStyle};
 
@@ -56,11 +56,7 @@ for entry in bib.iter() {
     driver.citation(CitationRequest::from_items(items, &style, &locales));
 }
 
-let result = driver.finish(BibliographyRequest {
-    style: &style,
-    locale: None,
-    locale_files: &locales,
-});
+let result = driver.finish(BibliographyRequest::new(&style, None, &locales));
 
 for cite in result.citations {
     println!("{}", cite.citation.to_string())
@@ -94,6 +90,19 @@ default features by writing this in your `Cargo.toml`:
 hayagriva = { version = "0.2", default-features = false }
 ```
 
+This also leaves out the bundled styles and locales of the `archive` feature,
+so only the YAML format and the CSL processor are compiled, for example for
+WebAssembly. The features `biblatex` and `archive` can be enabled on their own.
+
+With the `rayon` feature, `BibliographyDriver::finish_par` formats the
+items of large bibliographies in parallel.
+
+The default `std` feature provides the parts of the crate that need the
+standard library, like writing a bibliography to an [`std::io::Write`] and
+the cache for case-transformed strings. Disabling it leaves these parts
+out. Hayagriva itself and some of its dependencies still need the
+standard library, so it cannot be used from `#![no_std]` crates.
+
 # Selectors
 
 Hayagriva uses a custom selector language that enables you to filter
@@ -132,11 +141,28 @@ You should use [`Selector::matches`] if you just want to know if an item
 matches a selector and [`Selector::apply`] to continue to work with the data from
 parents of a matching entry. Keep in mind that the latter function will
 return `Some` even if no sub-entry was bound / if the hash map is empty.
+
+# Deterministic output
+
+The formatted citations and bibliographies only depend on the entries, the
+order in which they are cited, the style, and the options. They are the same
+in every run and on every platform, so they can be committed next to a
+document. In particular, they do not depend on the order in which entries were
+added to a [`Library`]: Items that the style sorts equally are sorted by
+their keys, and year suffixes are assigned in the sorted order. Styles that
+sort the bibliography by citation number keep the order of first citation.
+
+The order of the library only matters where it is the order of citation, that
+is for entries added with `CitationEngine::nocite_all` and for the
+citations of an `IncrementalEngine`, and for the groups of
+[`Library::grouped`].
 */
 
 #![warn(missing_docs)]
 #![allow(clippy::comparison_chain)]
 
+extern crate alloc;
+
 #[macro_use]
 mod selectors;
 #[cfg(feature = "biblatex")]
@@ -144,26 +170,39 @@ mod interop;
 
 mod csl;
 pub mod io;
+pub mod labels;
 pub mod lang;
+mod normalize;
 pub mod types;
 mod util;
 
-use std::collections::BTreeMap;
+use alloc::borrow::Cow;
+use alloc::collections::BTreeMap;
+use core::fmt::Write;
+use std::collections::HashSet;
 
-#[cfg(feature = "rkyv")]
+#[cfg(feature = "archive")]
 pub use crate::csl::archive;
 pub use citationberg;
 pub use csl::{
-    standalone_citation, BibliographyDriver, BibliographyRequest, Brackets,
-    BufWriteFormat, CitationItem, CitationRequest, CitePurpose, Elem, ElemChild,
-    ElemChildren, ElemMeta, Formatted, Formatting, LocatorPayload, Rendered,
-    RenderedBibliography, RenderedCitation, SpecificLocator,
+    link_break_points, render_ansi, render_latex, render_latex_bibliography,
+    render_plain, render_rtf, render_rtf_bibliography, standalone_citation, AnsiLinks,
+    AnsiOptions, BibliographyDriver, BibliographyRequest, BibliographyStream,
+    BidiIsolation, Brackets, BufWriteFormat, CitationEngine, CitationItem, CitationLinks,
+    CitationRequest, CitationTarget, CitePurpose, Elem, ElemChild, ElemChildren,
+    ElemMeta, Formatted, FormattedNote, Formatting, IncrementalEngine, ItemLayout,
+    KeyedCite, LocatorPayload, NameOrder, NoteStream, PlainItalics, PlainLinks,
+    PlainTextOptions, PublisherLocation, Rendered, RenderedBibliography,
+    RenderedCitation, ReproductionNote, RoleLabelCase, RoleLabelStyle, Signal,
+    SpecificLocator, StreamedRendered, StyleOptions, TitleLanguages, UncitedPlacement,
+    UnknownKeyError, UnknownKeysError,
 };
 pub use selectors::{Selector, SelectorError};
 
 use indexmap::IndexMap;
 use paste::paste;
 use serde::{de::Visitor, Deserialize, Serialize};
+use thiserror::Error;
 use types::*;
 use unic_langid::LanguageIdentifier;
 use util::{
@@ -171,6 +210,10 @@ use util::{
     OneOrMany,
 };
 
+/// The maximum number of characters of a title in
+/// [`Library::hover_form`].
+pub const HOVER_TITLE_LENGTH: usize = 60;
+
 /// A collection of bibliographic entries.
 #[derive(Debug, Clone, Default, PartialEq, Serialize)]
 pub struct Library(IndexMap<String, Entry>);
@@ -181,11 +224,83 @@ impl Library {
         Self(IndexMap::new())
     }
 
-    /// Add an entry to the library.
+    /// Add an entry to the library. An entry with the same key is replaced.
+    /// Use [`insert`](Self::insert) to detect duplicate keys.
     pub fn push(&mut self, entry: &Entry) {
         self.0.insert(entry.key.clone(), entry.clone());
     }
 
+    /// Add an entry to the library unless its key is already taken.
+    ///
+    /// With [`DuplicateKeys::Rename`], an entry with a taken key is added
+    /// under the first free key with the suffix "-1", "-2", and so on, and
+    /// the new key is returned.
+    pub fn insert(
+        &mut self,
+        mut entry: Entry,
+        policy: DuplicateKeys,
+    ) -> Result<Option<String>, DuplicateKeyError> {
+        if !self.0.contains_key(entry.key()) {
+            self.0.insert(entry.key.clone(), entry);
+            return Ok(None);
+        }
+
+        match policy {
+            DuplicateKeys::Reject => Err(DuplicateKeyError(entry.key)),
+            DuplicateKeys::Rename => {
+                let key = self.free_key(entry.key(), |_| false);
+                entry.key = key.clone();
+                self.0.insert(key.clone(), entry);
+                Ok(Some(key))
+            }
+        }
+    }
+
+    /// Add all entries of another library, like one imported from a second
+    /// BibLaTeX file.
+    ///
+    /// With [`DuplicateKeys::Reject`], nothing is added if any key is taken.
+    /// With [`DuplicateKeys::Rename`], the entries with taken keys are
+    /// renamed like in [`insert`](Self::insert) and the returned map has the
+    /// new key for each renamed original key, so that documents citing the
+    /// other library can be updated.
+    pub fn merge(
+        &mut self,
+        other: Library,
+        policy: DuplicateKeys,
+    ) -> Result<IndexMap<String, String>, DuplicateKeyError> {
+        let mut renamed = IndexMap::new();
+        if policy == DuplicateKeys::Reject {
+            if let Some(key) = other.keys().find(|key| self.0.contains_key(*key)) {
+                return Err(DuplicateKeyError(key.to_string()));
+            }
+        }
+
+        // Renamed entries must not take the keys of entries that are yet to
+        // be added.
+        let reserved: Vec<String> = other.keys().map(String::from).collect();
+        for (original, mut entry) in other.0 {
+            if self.0.contains_key(&original) {
+                let key =
+                    self.free_key(&original, |key| reserved.iter().any(|r| r == key));
+                entry.key = key.clone();
+                renamed.insert(original, key);
+            }
+            self.0.insert(entry.key.clone(), entry);
+        }
+
+        Ok(renamed)
+    }
+
+    /// The first key of the form `key-n` that is not in the library and not
+    /// reserved.
+    fn free_key(&self, key: &str, reserved: impl Fn(&str) -> bool) -> String {
+        (1..)
+            .map(|n| format!("{key}-{n}"))
+            .find(|key| !self.0.contains_key(key) && !reserved(key))
+            .unwrap()
+    }
+
     /// Retrieve an entry from the library.
     pub fn get(&self, key: &str) -> Option<&Entry> {
         self.0.get(key)
@@ -220,6 +335,249 @@ impl Library {
     pub fn nth(&self, n: usize) -> Option<&Entry> {
         self.0.get_index(n).map(|(_, v)| v)
     }
+
+    /// Get the name components of the authors of the nth entry in the library.
+    ///
+    /// Returns an empty list if there is no such entry or it has no authors.
+    pub fn author_parts(&self, index: usize) -> Vec<PersonView<'_>> {
+        self.nth(index)
+            .and_then(Entry::authors)
+            .map(|authors| authors.iter().map(Person::view).collect())
+            .unwrap_or_default()
+    }
+
+    /// A compact one-line description of the nth entry in the library, like
+    /// "Smith and Jones, 2020, A Study of Things", for tooltips in reading
+    /// apps.
+    ///
+    /// The entry is named by the family names of its authors or, if it has
+    /// none, its editors, with "et al." for more than two. Titles longer
+    /// than [`HOVER_TITLE_LENGTH`] characters are shortened at a word
+    /// boundary and end with an ellipsis. Parts the entry does not have are
+    /// omitted. Returns an empty string if there is no such entry.
+    pub fn hover_form(&self, index: usize) -> String {
+        let Some(entry) = self.nth(index) else { return String::new() };
+        let mut res = String::with_capacity(HOVER_TITLE_LENGTH + 64);
+
+        let separate = |res: &mut String| {
+            if !res.is_empty() {
+                res.push_str(", ");
+            }
+        };
+        let family = |res: &mut String, person: &Person| {
+            if let Some(prefix) = &person.prefix {
+                res.push_str(prefix);
+                res.push(' ');
+            }
+            res.push_str(&person.name);
+        };
+        match entry.authors().or_else(|| entry.editors()) {
+            Some([]) | None => {}
+            Some([one]) => family(&mut res, one),
+            Some([first, second]) => {
+                family(&mut res, first);
+                res.push_str(" and ");
+                family(&mut res, second);
+            }
+            Some([first, ..]) => {
+                family(&mut res, first);
+                res.push_str(" et al.");
+            }
+        }
+
+        if let Some(date) = entry.resolve_date() {
+            separate(&mut res);
+            write!(res, "{}", date.year).unwrap();
+        }
+
+        if let Some(title) = entry.truncate_title(HOVER_TITLE_LENGTH) {
+            separate(&mut res);
+            res.push_str(&title);
+        }
+
+        res
+    }
+
+    /// Split the library into sections, like "Journal articles" and
+    /// "Conference papers" in a CV.
+    ///
+    /// The groups are ordered as described by [`GroupBy`]. Within each group,
+    /// the most recent entries come first and entries without a date come
+    /// last. Entries with the same date are sorted by their keys. Entries that
+    /// belong to no group are collected in a last group with the name `other`
+    /// if there are any.
+    pub fn grouped(&self, group_by: &GroupBy, other: &str) -> Vec<Group<'_>> {
+        let mut groups: Vec<Group> = match group_by {
+            GroupBy::Selectors(selectors) => selectors
+                .iter()
+                .map(|(name, _)| Group { name: name.clone(), entries: Vec::new() })
+                .collect(),
+            GroupBy::Year | GroupBy::Custom(_) => Vec::new(),
+        };
+        let mut rest = Vec::new();
+
+        for entry in self.iter() {
+            let name = match group_by {
+                GroupBy::Selectors(selectors) => {
+                    match selectors.iter().position(|(_, s)| s.matches(entry)) {
+                        Some(i) => {
+                            groups[i].entries.push(entry);
+                            continue;
+                        }
+                        None => None,
+                    }
+                }
+                GroupBy::Year => entry.date_any().map(|d| d.year.to_string()),
+                GroupBy::Custom(f) => f(entry),
+            };
+
+            let Some(name) = name else {
+                rest.push(entry);
+                continue;
+            };
+
+            match groups.iter_mut().find(|g| g.name == name) {
+                Some(group) => group.entries.push(entry),
+                None => groups.push(Group { name, entries: vec![entry] }),
+            }
+        }
+
+        match group_by {
+            GroupBy::Selectors(_) => groups.retain(|g| !g.entries.is_empty()),
+            GroupBy::Year => groups.sort_by_key(|g| {
+                core::cmp::Reverse(g.entries[0].date_any().map(|d| d.year))
+            }),
+            GroupBy::Custom(_) => {}
+        }
+
+        if !rest.is_empty() {
+            groups.push(Group { name: other.to_string(), entries: rest });
+        }
+
+        for group in &mut groups {
+            group.entries.sort_by(|a, b| {
+                let by_date = match (a.date_any(), b.date_any()) {
+                    (Some(a), Some(b)) => b.csl_cmp(a),
+                    (Some(_), None) => core::cmp::Ordering::Less,
+                    (None, Some(_)) => core::cmp::Ordering::Greater,
+                    (None, None) => core::cmp::Ordering::Equal,
+                };
+                by_date.then_with(|| a.key().cmp(b.key()))
+            });
+        }
+
+        groups
+    }
+
+    /// Count the entries of the library by their type and decade, the
+    /// distinct authors, and the entries with a DOI.
+    ///
+    /// The decade of an entry is that of its own date or, like for a chapter,
+    /// the date of its parent. Entries without a date are only counted in
+    /// [`BibStatistics::undated`].
+    pub fn statistics(&self) -> BibStatistics {
+        let mut stats = BibStatistics { entries: self.len(), ..Default::default() };
+        let mut authors = HashSet::new();
+        for entry in self.iter() {
+            *stats.by_type.entry(*entry.entry_type()).or_default() += 1;
+            match entry.date_any() {
+                Some(date) => {
+                    let decade = date.year.div_euclid(10) * 10;
+                    *stats.by_decade.entry(decade).or_default() += 1;
+                }
+                None => stats.undated += 1,
+            }
+            authors.extend(entry.authors().into_iter().flatten());
+            if entry.doi().is_some() {
+                stats.with_doi += 1;
+            }
+        }
+
+        stats.authors = authors.len();
+        stats
+    }
+}
+
+/// What to do when an entry is added to a [`Library`] that already has an
+/// entry with its key.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum DuplicateKeys {
+    /// Do not add the entry and return a [`DuplicateKeyError`].
+    #[default]
+    Reject,
+    /// Add the entry under a new key with a numeric suffix, like `smith-1`.
+    Rename,
+}
+
+/// An entry could not be added to a [`Library`] because its key is taken.
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+#[error("the key `{0}` is already in the library")]
+pub struct DuplicateKeyError(pub String);
+
+/// How to split a [`Library`] into groups with [`Library::grouped`].
+pub enum GroupBy {
+    /// Put each entry into the first group whose selector matches it. The
+    /// groups appear in the given order.
+    Selectors(Vec<(String, Selector)>),
+    /// Group the entries by the year of their publication, the most recent
+    /// year first.
+    Year,
+    /// Group the entries by the name returned from the function or put them
+    /// into the other group if it returns `None`. The groups appear in the
+    /// order of their first entry.
+    #[allow(clippy::type_complexity)]
+    Custom(Box<dyn Fn(&Entry) -> Option<String>>),
+}
+
+impl core::fmt::Debug for GroupBy {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Selectors(selectors) => {
+                f.debug_tuple("Selectors").field(selectors).finish()
+            }
+            Self::Year => f.write_str("Year"),
+            Self::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
+/// A named group of entries from [`Library::grouped`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Group<'a> {
+    /// The name of the group.
+    pub name: String,
+    /// The entries in the group.
+    pub entries: Vec<&'a Entry>,
+}
+
+/// Counts about the entries of a [`Library`] from [`Library::statistics`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BibStatistics {
+    /// The number of entries.
+    pub entries: usize,
+    /// The number of entries of each type.
+    pub by_type: BTreeMap<EntryType, usize>,
+    /// The number of entries by the first year of their decade, like 1990 for
+    /// the years 1990 to 1999.
+    pub by_decade: BTreeMap<i32, usize>,
+    /// The number of entries without a date.
+    pub undated: usize,
+    /// The number of distinct authors of all entries.
+    pub authors: usize,
+    /// The number of entries with a DOI.
+    pub with_doi: usize,
+}
+
+impl BibStatistics {
+    /// The percentage of entries with a DOI, from 0 to 100. It is zero for an
+    /// empty library.
+    pub fn doi_percentage(&self) -> f64 {
+        if self.entries == 0 {
+            return 0.0;
+        }
+
+        self.with_doi as f64 * 100.0 / self.entries as f64
+    }
 }
 
 impl<'a> IntoIterator for &'a Library {
@@ -233,7 +591,7 @@ impl<'a> IntoIterator for &'a Library {
 
 impl IntoIterator for Library {
     type Item = Entry;
-    type IntoIter = std::iter::Map<
+    type IntoIter = core::iter::Map<
         indexmap::map::IntoIter<String, Entry>,
         fn((String, Entry)) -> Entry,
     >;
@@ -401,8 +759,8 @@ macro_rules! entry {
                 impl<'de> Visitor<'de> for MyVisitor {
                     type Value = Library;
 
-                    fn expecting(&self, formatter: &mut std::fmt::Formatter)
-                        -> std::fmt::Result
+                    fn expecting(&self, formatter: &mut core::fmt::Formatter)
+                        -> core::fmt::Result
                     {
                         formatter.write_str(
                             "a map between cite keys and entries"
@@ -490,12 +848,19 @@ macro_rules! entry {
 entry! {
     /// Title of the item.
     "title" => title: FormatString,
+    /// Subtitle of the item.
+    "subtitle" => subtitle: FormatString,
+    /// Titles of a multilingual edition of the item in each of its languages.
+    "titles" => titles: Vec<LocalizedTitle> | [LocalizedTitle],
     /// Persons primarily responsible for creating the item.
     #[serde(serialize_with = "serialize_one_or_many_opt")]
     #[serde(deserialize_with = "deserialize_one_or_many_opt")]
     "author" => authors: Vec<Person> | [Person],
     /// Date at which the item was published.
-    "date" => date: Date,
+    "date" => date: DateParts,
+    /// Whether the item is not yet published in its final form, like an
+    /// article in press. Such items have no volume, issue, or page range.
+    "status" => status: PublicationStatus,
     /// Persons responsible for selecting and revising the content of the item.
     #[serde(serialize_with = "serialize_one_or_many_opt")]
     #[serde(deserialize_with = "deserialize_one_or_many_opt")]
@@ -524,10 +889,17 @@ entry! {
     "page-range" => page_range: Numeric,
     /// The total number of pages the item has.
     "page-total" => page_total: Numeric,
+    /// The section of a statute or code this item refers to.
+    "section" => section: MaybeTyped<Numeric>,
     /// The time range within the parent this item starts and ends at.
     "time-range" => time_range: MaybeTyped<DurationRange>,
     /// The total runtime of the item.
     "runtime" => runtime: MaybeTyped<Duration>,
+    /// The physical or digital format of the item, like an eBook edition or
+    /// an audiobook.
+    "medium" => medium: FormatString,
+    /// The kind of the item within its type, like a doctoral dissertation.
+    "genre" => genre: FormatString,
     /// Canonical public URL of the item, can have access date.
     "url" => url: QualifiedUrl,
     /// Any serial number or version describing the item that is not appropriate
@@ -545,6 +917,11 @@ entry! {
     "call-number" => call_number: FormatString,
     /// Additional description to be appended in the bibliographic entry.
     "note" => note: FormatString,
+    /// Summary of the contents of the item.
+    "abstract" => abstract_: FormatString,
+    /// How often the item was cited by other works, like the count of a
+    /// citation database.
+    "citation-count" => citation_count: u32,
 }
 
 impl Entry {
@@ -631,15 +1008,78 @@ impl Entry {
     }
 
     /// Will recursively get a date off either the entry or any of its ancestors.
-    pub fn date_any(&self) -> Option<&Date> {
+    pub fn date_any(&self) -> Option<&DateParts> {
         self.map(|e| e.date.as_ref())
     }
 
+    /// The date of the entry or, if it has none, of its nearest ancestor,
+    /// like the book of a chapter. If both have a date, the date of the entry
+    /// wins.
+    pub fn resolve_date(&self) -> Option<DateParts> {
+        self.date_any().copied()
+    }
+
     /// Will recursively get an URL off either the entry or any of its ancestors.
     pub fn url_any(&self) -> Option<&QualifiedUrl> {
         self.map(|e| e.url.as_ref())
     }
 
+    /// Get the title joined with the subtitle, if there is one.
+    ///
+    /// The first letter of the subtitle is capitalized as both separators
+    /// start a new sentence-case phrase. No separator punctuation is added
+    /// if the title already ends with a question or exclamation mark.
+    pub fn full_title(&self, separator: TitleSeparator) -> Option<FormatString> {
+        self.full_title_cow(separator).map(Cow::into_owned)
+    }
+
+    /// Get the title joined with the subtitle like
+    /// [`full_title`](Self::full_title), but borrow the title if there is no
+    /// subtitle.
+    pub fn full_title_cow(
+        &self,
+        separator: TitleSeparator,
+    ) -> Option<Cow<'_, FormatString>> {
+        let title = self.title.as_ref()?;
+        let Some(subtitle) = &self.subtitle else {
+            return Some(Cow::Borrowed(title));
+        };
+
+        let mut value = title.value.clone();
+        if value.to_str().ends_with(['?', '!']) {
+            value.push_str(" ", ChunkKind::Normal);
+        } else {
+            value.push_str(separator.as_str(), ChunkKind::Normal);
+        }
+
+        let mut subtitle = subtitle.value.clone();
+        if let Some(first) = subtitle.0.first_mut() {
+            if first.kind == ChunkKind::Normal {
+                let mut chars = first.value.chars();
+                if let Some(c) = chars.next() {
+                    first.value = c.to_uppercase().chain(chars).collect();
+                }
+            }
+        }
+
+        value.extend(subtitle);
+        Some(Cow::Owned(FormatString { value, short: title.short.clone() }))
+    }
+
+    /// Get the title, shortened to at most `max_chars` characters for display
+    /// in tables and other user interfaces.
+    ///
+    /// Titles that are too long are cut at the last word boundary that fits
+    /// and end with an ellipsis, which counts towards the limit. Shorter titles
+    /// are returned as-is and a limit of zero yields an empty string. This is
+    /// unrelated to the short form of the title that styles may use in
+    /// citations.
+    pub fn truncate_title(&self, max_chars: usize) -> Option<String> {
+        self.title
+            .as_ref()
+            .map(|t| truncate_at_word(&t.value.to_str(), max_chars))
+    }
+
     /// Retrieve a keyed serial number.
     pub fn keyed_serial_number(&self, key: &str) -> Option<&str> {
         self.serial_number
@@ -735,6 +1175,7 @@ impl Entry {
         match &self.entry_type {
             EntryType::Article => retrieve_container(&[
                 EntryType::Book,
+                EntryType::Proceedings,
                 EntryType::Periodical,
                 EntryType::Newspaper,
                 EntryType::Blog,
@@ -750,7 +1191,12 @@ impl Entry {
             EntryType::Report => {
                 retrieve_container(&[EntryType::Book, EntryType::Anthology])
             }
-            EntryType::Web => retrieve_container(&[EntryType::Web]),
+            EntryType::Entry => retrieve_container(&[
+                EntryType::Reference,
+                EntryType::Book,
+                EntryType::Web,
+            ]),
+            EntryType::Web => retrieve_container(&[EntryType::Web, EntryType::Blog]),
             EntryType::Scene => retrieve_container(&[
                 EntryType::Audio,
                 EntryType::Video,
@@ -758,11 +1204,17 @@ impl Entry {
                 EntryType::Artwork,
             ]),
             EntryType::Case => retrieve_container(&[
+                EntryType::Periodical,
                 EntryType::Book,
                 EntryType::Anthology,
                 EntryType::Reference,
                 EntryType::Report,
             ]),
+            EntryType::Legislation => retrieve_container(&[
+                EntryType::Anthology,
+                EntryType::Book,
+                EntryType::Periodical,
+            ]),
             EntryType::Post => {
                 retrieve_container(&[EntryType::Thread, EntryType::Blog, EntryType::Web])
             }
@@ -784,12 +1236,41 @@ impl Entry {
             | EntryType::Book
             | EntryType::Reference
             | EntryType::Exhibition => self.parents.iter().find(|e| {
-                e.entry_type == self.entry_type || e.entry_type == EntryType::Anthology
+                e.entry_type == self.entry_type
+                    || e.entry_type == EntryType::Anthology
+                    || (e.entry_type == EntryType::Periodical && self.is_monograph())
             }),
             _ => self.parents.iter().find_map(|e| e.get_collection()),
         }
     }
 
+    /// Get the book that contains this chapter, if it is one. The authors and
+    /// editors of the book apply to its chapters.
+    pub(crate) fn get_book(&self) -> Option<&Self> {
+        if self.entry_type != EntryType::Chapter {
+            return None;
+        }
+
+        self.get_container().filter(|e| {
+            matches!(
+                e.entry_type,
+                EntryType::Book | EntryType::Anthology | EntryType::Reference
+            )
+        })
+    }
+
+    /// Whether this entry is a monograph that can belong to a book series,
+    /// which is often recorded as a periodical.
+    fn is_monograph(&self) -> bool {
+        matches!(
+            self.entry_type,
+            EntryType::Anthology
+                | EntryType::Book
+                | EntryType::Proceedings
+                | EntryType::Reference
+        )
+    }
+
     /// Search a parent by DFS.
     pub(crate) fn dfs_parent(&self, kind: EntryType) -> Option<&Self> {
         if self.entry_type == kind {
@@ -811,6 +1292,40 @@ impl Entry {
     }
 }
 
+/// Shorten a string to at most `max_chars` characters including a trailing
+/// ellipsis, preferably cutting at a word boundary.
+fn truncate_at_word(s: &str, max_chars: usize) -> String {
+    if s.chars().count() <= max_chars {
+        return s.to_string();
+    } else if max_chars == 0 {
+        return String::new();
+    }
+
+    // Leave room for the ellipsis.
+    let cut = s.char_indices().nth(max_chars - 1).map_or(s.len(), |(i, _)| i);
+    let trim = |head: &str| {
+        head.trim_end_matches(|c: char| c.is_whitespace() || c.is_ascii_punctuation())
+            .len()
+    };
+
+    // Only cut at a word boundary if the next character starts a new word and
+    // some text is left before the boundary.
+    let head = &s[..cut];
+    let word = if s[cut..].starts_with(char::is_whitespace) {
+        head
+    } else {
+        head.rfind(char::is_whitespace).map_or(head, |i| &head[..i])
+    };
+    let head = match trim(word) {
+        0 => &head[..trim(head)],
+        len => &word[..len],
+    };
+
+    let mut res = head.to_string();
+    res.push('…');
+    res
+}
+
 #[cfg(feature = "biblatex")]
 impl Entry {
     /// Adds a parent to the current entry. The parent
@@ -949,4 +1464,381 @@ mod tests {
             ["a", "b", "c"]
         );
     }
+
+    #[test]
+    fn truncate_title() {
+        let mut entry = Entry::new("test", EntryType::Book);
+        assert_eq!(entry.truncate_title(10), None);
+
+        entry.set_title(FormatString::with_value("Crazy Rich Asians"));
+        assert_eq!(entry.truncate_title(17).as_deref(), Some("Crazy Rich Asians"));
+        assert_eq!(entry.truncate_title(40).as_deref(), Some("Crazy Rich Asians"));
+        assert_eq!(entry.truncate_title(16).as_deref(), Some("Crazy Rich…"));
+        assert_eq!(entry.truncate_title(12).as_deref(), Some("Crazy Rich…"));
+        assert_eq!(entry.truncate_title(8).as_deref(), Some("Crazy…"));
+
+        entry.set_title(FormatString::with_value("Structure: A Study"));
+        assert_eq!(entry.truncate_title(14).as_deref(), Some("Structure: A…"));
+        assert_eq!(entry.truncate_title(12).as_deref(), Some("Structure…"));
+
+        entry.set_title(FormatString::with_value("Supercalifragilistic"));
+        assert_eq!(entry.truncate_title(6).as_deref(), Some("Super…"));
+
+        // The ellipsis counts towards the limit.
+        entry.set_title(FormatString::with_value("A Study"));
+        assert_eq!(entry.truncate_title(0).as_deref(), Some(""));
+        assert_eq!(entry.truncate_title(1).as_deref(), Some("…"));
+        assert_eq!(entry.truncate_title(2).as_deref(), Some("A…"));
+        assert_eq!(entry.truncate_title(4).as_deref(), Some("A…"));
+
+        // A word boundary that leaves no text is not used.
+        entry.set_title(FormatString::with_value("... and more"));
+        assert_eq!(entry.truncate_title(7).as_deref(), Some("... an…"));
+    }
+
+    #[test]
+    fn full_title() {
+        let mut entry = Entry::new("test", EntryType::Book);
+        entry.set_title(FormatString::with_value("Structure"));
+        assert_eq!(
+            entry.full_title(TitleSeparator::Colon).unwrap().value.to_str(),
+            "Structure"
+        );
+
+        entry.set_subtitle(FormatString::with_value("a study"));
+        assert_eq!(
+            entry.full_title(TitleSeparator::Colon).unwrap().value.to_str(),
+            "Structure: A study"
+        );
+        assert_eq!(
+            entry.full_title(TitleSeparator::Period).unwrap().value.to_str(),
+            "Structure. A study"
+        );
+
+        entry.set_title(FormatString::with_value("Why structure?"));
+        assert_eq!(
+            entry.full_title(TitleSeparator::Colon).unwrap().value.to_str(),
+            "Why structure? A study"
+        );
+    }
+
+    #[test]
+    fn resolve_date() {
+        let library = from_yaml_str(
+            r#"
+            inherited:
+                type: chapter
+                title: A chapter
+                parent:
+                    type: book
+                    title: A book
+                    date: 2018
+            own:
+                type: chapter
+                title: Another chapter
+                date: 2020-05
+                parent:
+                    type: book
+                    title: A book
+                    date: 2018
+            undated:
+                type: chapter
+                title: An undated chapter
+            "#,
+        )
+        .unwrap();
+
+        let year = |key| library.get(key).unwrap().resolve_date().map(|d| d.year);
+        assert_eq!(year("inherited"), Some(2018));
+        assert_eq!(year("own"), Some(2020));
+        assert_eq!(year("undated"), None);
+        assert_eq!(library.get("own").unwrap().resolve_date().unwrap().month, Some(4));
+    }
+
+    #[test]
+    fn author_parts() {
+        let library = from_yaml_str(
+            r#"
+            symphony:
+                type: audio
+                title: Symphony No. 9
+                author:
+                    - van Beethoven, Ludwig
+                    - King, Jr., Martin Luther
+                    - name: de Gaulle
+                      given-name: Charles
+                    - World Health Organization
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            library.author_parts(0),
+            vec![
+                PersonView::Individual {
+                    family: "Beethoven",
+                    given: Some("Ludwig"),
+                    dropping_particle: Some("van"),
+                    non_dropping_particle: None,
+                    suffix: None,
+                },
+                PersonView::Individual {
+                    family: "King",
+                    given: Some("Martin Luther"),
+                    dropping_particle: None,
+                    non_dropping_particle: None,
+                    suffix: Some("Jr."),
+                },
+                PersonView::Individual {
+                    family: "Gaulle",
+                    given: Some("Charles"),
+                    dropping_particle: None,
+                    non_dropping_particle: Some("de"),
+                    suffix: None,
+                },
+                PersonView::Institution("World Health Organization"),
+            ]
+        );
+        assert!(library.author_parts(1).is_empty());
+    }
+
+    #[test]
+    fn hover_form() {
+        let library = from_yaml_str(
+            r#"
+            article:
+                type: article
+                title: The Effects of Long Titles on the Attention Span of Readers of Scholarly Journals
+                author: ["Smith, Jane", "Jones, John"]
+                parent:
+                    type: periodical
+                    title: Journal of Reading
+                    date: 2020
+            short:
+                type: article
+                title: Short
+                author: ["van Beethoven, Ludwig", "Bach, Johann", "Mozart, Wolfgang"]
+                date: 1800
+            untitled:
+                type: misc
+                editor: Editor, Ed
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            library.hover_form(0),
+            "Smith and Jones, 2020, The Effects of Long Titles on the Attention Span of Readers…"
+        );
+        assert_eq!(library.hover_form(1), "van Beethoven et al., 1800, Short");
+        assert_eq!(library.hover_form(2), "Editor");
+        assert_eq!(library.hover_form(3), "");
+    }
+
+    #[test]
+    fn duplicate_keys() {
+        let mut library = from_yaml_str(
+            r#"
+            smith:
+                type: book
+                title: First
+            smith-1:
+                type: book
+                title: Second
+            "#,
+        )
+        .unwrap();
+        let other = from_yaml_str(
+            r#"
+            smith:
+                type: article
+                title: Third
+            smith-2:
+                type: article
+                title: Fourth
+            jones:
+                type: article
+                title: Fifth
+            "#,
+        )
+        .unwrap();
+
+        // Rejecting leaves the library unchanged.
+        let entry = other.get("smith").unwrap().clone();
+        assert_eq!(
+            library.insert(entry.clone(), DuplicateKeys::Reject),
+            Err(DuplicateKeyError("smith".into()))
+        );
+        assert_eq!(
+            library.clone().merge(other.clone(), DuplicateKeys::default()),
+            Err(DuplicateKeyError("smith".into()))
+        );
+        assert_eq!(library.len(), 2);
+
+        let mut renaming = library.clone();
+        assert_eq!(
+            renaming.insert(entry, DuplicateKeys::Rename),
+            Ok(Some("smith-2".into()))
+        );
+        assert_eq!(renaming.get("smith-2").unwrap().key(), "smith-2");
+
+        let renamed = library.merge(other, DuplicateKeys::Rename).unwrap();
+        assert_eq!(
+            renamed.into_iter().collect::<Vec<_>>(),
+            [("smith".to_string(), "smith-3".to_string())]
+        );
+        assert_eq!(
+            library.keys().collect::<Vec<_>>(),
+            ["smith", "smith-1", "smith-3", "smith-2", "jones"]
+        );
+        let title = |key| library.get(key).unwrap().title().unwrap().value.to_str();
+        assert_eq!(title("smith"), "First");
+        assert_eq!(title("smith-3"), "Third");
+        assert_eq!(title("smith-2"), "Fourth");
+    }
+
+    #[test]
+    fn grouped() {
+        let library = from_yaml_str(
+            r#"
+            paper:
+                type: article
+                title: A paper
+                date: 2019
+                parent:
+                    type: proceedings
+                    title: Proceedings of the Conference
+            article:
+                type: article
+                title: An article
+                date: 2021
+                parent:
+                    type: periodical
+                    title: Journal
+            chapter:
+                type: chapter
+                title: A chapter
+                parent:
+                    type: book
+                    title: A book
+            other-article:
+                type: article
+                title: Another article
+                date: 2019
+                parent:
+                    type: periodical
+                    title: Journal
+            "#,
+        )
+        .unwrap();
+
+        let names = |groups: &[Group]| -> Vec<(String, Vec<String>)> {
+            groups
+                .iter()
+                .map(|g| {
+                    (
+                        g.name.clone(),
+                        g.entries.iter().map(|e| e.key().to_string()).collect(),
+                    )
+                })
+                .collect()
+        };
+
+        let by_type = GroupBy::Selectors(vec![
+            ("Journal articles".into(), Selector::parse("Article > Periodical").unwrap()),
+            ("Conference papers".into(), Selector::parse("* > Proceedings").unwrap()),
+            ("Books".into(), Selector::parse("Book").unwrap()),
+        ]);
+        assert_eq!(
+            names(&library.grouped(&by_type, "Other")),
+            vec![
+                (
+                    "Journal articles".into(),
+                    vec!["article".into(), "other-article".into()]
+                ),
+                ("Conference papers".into(), vec!["paper".into()]),
+                ("Other".into(), vec!["chapter".into()]),
+            ]
+        );
+
+        assert_eq!(
+            names(&library.grouped(&GroupBy::Year, "Undated")),
+            vec![
+                ("2021".into(), vec!["article".into()]),
+                ("2019".into(), vec!["other-article".into(), "paper".into()]),
+                ("Undated".into(), vec!["chapter".into()]),
+            ]
+        );
+
+        // Within a group, the most recent entries come first.
+        let all = GroupBy::Custom(Box::new(|_| Some("All".into())));
+        assert_eq!(
+            names(&library.grouped(&all, "Other")),
+            vec![(
+                "All".into(),
+                vec![
+                    "article".into(),
+                    "other-article".into(),
+                    "paper".into(),
+                    "chapter".into()
+                ]
+            )]
+        );
+    }
+
+    #[test]
+    fn statistics() {
+        let library = from_yaml_str(
+            r#"
+            first:
+                type: article
+                title: First
+                author: ["Doe, Jane", "Roe, Rick"]
+                date: 1995
+                serial-number:
+                    doi: 10.1000/1
+            second:
+                type: article
+                title: Second
+                author: Doe, Jane
+                date: 1999-04
+            third:
+                type: book
+                title: Third
+                author: Smith, John
+                date: 2003
+                serial-number:
+                    doi: 10.1000/3
+            chapter:
+                type: chapter
+                title: Fourth
+                parent:
+                    type: book
+                    title: A book
+                    date: 2001
+            undated:
+                type: web
+                title: Fifth
+            "#,
+        )
+        .unwrap();
+
+        let stats = library.statistics();
+        assert_eq!(stats.entries, 5);
+        assert_eq!(
+            stats.by_type,
+            BTreeMap::from([
+                (EntryType::Article, 2),
+                (EntryType::Book, 1),
+                (EntryType::Chapter, 1),
+                (EntryType::Web, 1),
+            ])
+        );
+        assert_eq!(stats.by_decade, BTreeMap::from([(1990, 2), (2000, 2)]));
+        assert_eq!(stats.undated, 1);
+        assert_eq!(stats.authors, 3);
+        assert_eq!(stats.with_doi, 2);
+        assert_eq!(stats.doi_percentage(), 40.0);
+        assert_eq!(Library::new().statistics().doi_percentage(), 0.0);
+    }
 }
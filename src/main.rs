@@ -12,8 +12,9 @@ use clap::{crate_version, Arg, Command};
 use strum::{EnumVariantNames, VariantNames};
 
 use hayagriva::archive::{locales, style, style_by_name, styles};
+use hayagriva::io::ErrorMode;
 use hayagriva::{io, BibliographyDriver, CitationItem, CitationRequest};
-use hayagriva::{BibliographyRequest, Selector};
+use hayagriva::{BibliographyRequest, Entry, Library, Selector};
 
 #[derive(Debug, Copy, Clone, PartialEq, EnumVariantNames)]
 #[strum(serialize_all = "kebab_case")]
@@ -135,6 +136,12 @@ fn main() {
                             .short('c')
                             .help("Combine all keys into one citation (ignored for Chicago Notes)")
                     )
+                    .arg(
+                        Arg::new("nocite")
+                            .long("nocite")
+                            .help("Include entries in the bibliography without citing them, as a comma-separated list of keys or `*` for all entries in the file")
+                            .takes_value(true)
+                    )
             )
             .subcommand(
                 Command::new("reference")
@@ -166,6 +173,12 @@ fn main() {
                             .help("Set a comma-separated list of CSL locales")
                             .takes_value(true)
                     )
+                    .arg(
+                        Arg::new("nocite")
+                            .long("nocite")
+                            .help("Include entries in the bibliography without citing them, as a comma-separated list of keys or `*` for all entries in the file")
+                            .takes_value(true)
+                    )
             )
             .subcommand(
                 Command::new("styles")
@@ -215,14 +228,27 @@ fn main() {
             }
         };
 
-        match format {
-            Format::Yaml => io::from_yaml_str(&input).unwrap(),
+        let res = match format {
+            Format::Yaml => io::from_yaml_str_diagnostics(&input, ErrorMode::CollectAll),
             #[cfg(feature = "biblatex")]
-            Format::Biblatex | Format::Bibtex => io::from_biblatex_str(&input).unwrap(),
+            Format::Biblatex | Format::Bibtex => {
+                io::from_biblatex_str_diagnostics(&input, ErrorMode::CollectAll)
+            }
+        };
+
+        match res {
+            Ok(bibliography) => bibliography,
+            Err(errors) => {
+                for error in errors {
+                    eprintln!("{}\n", error);
+                }
+                exit(9);
+            }
         }
     };
 
     let bib_len = bibliography.len();
+    let library = bibliography.clone();
 
     let selector = matches.value_of("selector").map(|src| match Selector::parse(src) {
         Ok(selector) => selector,
@@ -316,6 +342,16 @@ fn main() {
                 ))
             }
 
+            for entry in nocite_entries(&library, &bibliography, sub_matches) {
+                driver.citation(CitationRequest::new(
+                    vec![CitationItem::new(entry, None, None, true, None)],
+                    &style,
+                    locale.clone(),
+                    &locales,
+                    None,
+                ))
+            }
+
             for row in driver
                 .finish(BibliographyRequest::new(&style, locale, &locales))
                 .bibliography
@@ -370,6 +406,16 @@ fn main() {
                 }
             }
 
+            for entry in nocite_entries(&library, &bibliography, sub_matches) {
+                driver.citation(CitationRequest::new(
+                    vec![CitationItem::new(entry, None, None, true, None)],
+                    &style,
+                    locale.clone(),
+                    &locales,
+                    None,
+                ))
+            }
+
             for row in driver
                 .finish(BibliographyRequest::new(&style, locale, &locales))
                 .bibliography
@@ -419,6 +465,34 @@ fn main() {
     }
 }
 
+/// The entries of the library to include in the bibliography without a
+/// citation, in the order of the `--nocite` argument. Entries that are cited
+/// anyway are skipped.
+fn nocite_entries<'a>(
+    library: &'a Library,
+    cited: &Library,
+    matches: &clap::ArgMatches,
+) -> Vec<&'a Entry> {
+    let Some(keys) = matches.value_of("nocite") else { return vec![] };
+    let entries: Vec<&Entry> = if keys.trim() == "*" {
+        library.iter().collect()
+    } else {
+        keys.split(',')
+            .map(|key| {
+                library.get(key.trim()).unwrap_or_else(|| {
+                    eprintln!("No entry with the key \"{}\" to include.", key.trim());
+                    exit(8);
+                })
+            })
+            .collect()
+    };
+
+    entries
+        .into_iter()
+        .filter(|entry| cited.get(entry.key()).is_none())
+        .collect()
+}
+
 fn retrieve_assets(
     style: Option<&String>,
     csl: Option<&String>,
@@ -0,0 +1,247 @@
+//! The canonical form of an entry for deduplication, diffing, and key
+//! generation.
+
+use unicode_normalization::UnicodeNormalization;
+
+use crate::types::{
+    ChunkKind, ChunkedString, FormatString, MaybeTyped, Person, PersonsWithRoles,
+    SerialNumber, StringChunk,
+};
+use crate::Entry;
+
+impl Entry {
+    /// The canonical form of the entry.
+    ///
+    /// Text is composed to Unicode normalization form C, whitespace runs are
+    /// collapsed to a single space, and leading and trailing whitespace is
+    /// removed. No-break spaces are kept. Optional parts of names that are
+    /// empty then are removed. The DOI loses resolver URLs like
+    /// `https://doi.org/` and trailing periods and is lowercased, since DOIs
+    /// are case-insensitive. The parents are normalized as
+    /// well. Dates and page ranges are already structured when they are
+    /// parsed and serial numbers are sorted by their key, so the fields
+    /// always have the same order.
+    ///
+    /// Normalizing is idempotent. A normalized entry formats like the
+    /// original, except that its text has normalized whitespace and composed
+    /// characters and its DOI is lowercased.
+    pub fn normalize(&self) -> Entry {
+        let mut entry = self.clone();
+        entry.normalize_mut();
+        entry
+    }
+
+    /// Normalize the entry in place. See [`Entry::normalize`].
+    pub fn normalize_mut(&mut self) {
+        let strings = [
+            &mut self.title,
+            &mut self.subtitle,
+            &mut self.publisher,
+            &mut self.location,
+            &mut self.organization,
+            &mut self.medium,
+            &mut self.genre,
+            &mut self.archive,
+            &mut self.archive_location,
+            &mut self.call_number,
+            &mut self.note,
+            &mut self.abstract_,
+        ];
+        for string in strings.into_iter().flatten() {
+            normalize_format_string(string);
+        }
+
+        for title in self.titles.iter_mut().flatten() {
+            normalize_format_string(&mut title.title);
+        }
+
+        for persons in [&mut self.authors, &mut self.editors] {
+            persons.iter_mut().flatten().for_each(normalize_person);
+        }
+        for PersonsWithRoles { names, .. } in self.affiliated.iter_mut().flatten() {
+            names.iter_mut().for_each(normalize_person);
+        }
+
+        for numeric in
+            [&mut self.issue, &mut self.volume, &mut self.edition, &mut self.section]
+        {
+            if let Some(MaybeTyped::String(s)) = numeric {
+                *s = normalize_str(s);
+            }
+        }
+        if let Some(MaybeTyped::String(s)) = &mut self.time_range {
+            *s = normalize_str(s);
+        }
+        if let Some(MaybeTyped::String(s)) = &mut self.runtime {
+            *s = normalize_str(s);
+        }
+
+        if let Some(SerialNumber(serials)) = &mut self.serial_number {
+            for (key, value) in serials.iter_mut() {
+                *value = normalize_str(value);
+                if key == "doi" {
+                    *value = bare_doi(value).to_ascii_lowercase();
+                }
+            }
+        }
+
+        self.parents.iter_mut().for_each(Entry::normalize_mut);
+    }
+}
+
+/// The DOI without a resolver URL or a `doi:` scheme in front and without a
+/// period after it, which is never part of a DOI but often copied along.
+pub(crate) fn bare_doi(doi: &str) -> &str {
+    let doi = doi.trim();
+    let start = doi.find("10.").filter(|&i| {
+        let before = doi[..i].trim_end().to_ascii_lowercase();
+        before.ends_with("doi.org/") || before.ends_with("doi:") || before.is_empty()
+    });
+    let doi = start.map_or(doi, |i| &doi[i..]);
+    doi.strip_suffix('.').unwrap_or(doi)
+}
+
+/// Whether the character is whitespace that may be collapsed. No-break
+/// spaces are kept because they were put there on purpose.
+fn is_collapsible(c: char) -> bool {
+    c.is_whitespace() && !matches!(c, '\u{a0}' | '\u{2007}' | '\u{202f}')
+}
+
+/// Compose the string and collapse its whitespace.
+fn normalize_str(s: &str) -> String {
+    let mut res = String::with_capacity(s.len());
+    let composed: String = s.nfc().collect();
+    for word in composed.split(is_collapsible).filter(|w| !w.is_empty()) {
+        if !res.is_empty() {
+            res.push(' ');
+        }
+        res.push_str(word);
+    }
+    res
+}
+
+fn normalize_person(person: &mut Person) {
+    person.name = normalize_str(&person.name);
+    for part in [
+        &mut person.given_name,
+        &mut person.prefix,
+        &mut person.suffix,
+        &mut person.alias,
+    ] {
+        *part = part.as_deref().map(normalize_str).filter(|s| !s.is_empty());
+    }
+}
+
+fn normalize_format_string(string: &mut FormatString) {
+    normalize_chunked(&mut string.value);
+    if let Some(short) = &mut string.short {
+        normalize_chunked(short);
+    }
+}
+
+/// Compose the chunks of the string and collapse whitespace, also across
+/// chunks. Math chunks are kept as they are.
+fn normalize_chunked(string: &mut ChunkedString) {
+    let mut chunks: Vec<StringChunk> = Vec::with_capacity(string.0.len());
+    // Whether the last character was a space, so that the string does not
+    // start with one.
+    let mut space = true;
+    for chunk in &string.0 {
+        if chunk.kind == ChunkKind::Math {
+            chunks.push(chunk.clone());
+            space = false;
+            continue;
+        }
+
+        let mut value = String::with_capacity(chunk.value.len());
+        for c in chunk.value.nfc() {
+            if !is_collapsible(c) {
+                value.push(c);
+                space = false;
+            } else if !space {
+                value.push(' ');
+                space = true;
+            }
+        }
+
+        if value.is_empty() {
+            continue;
+        }
+
+        chunks.push(StringChunk { value, kind: chunk.kind });
+    }
+
+    if let Some(last) = chunks.last_mut().filter(|c| c.kind != ChunkKind::Math) {
+        if last.value.ends_with(' ') {
+            last.value.pop();
+            if last.value.is_empty() {
+                chunks.pop();
+            }
+        }
+    }
+
+    string.0 = chunks;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::from_yaml_str;
+    use crate::types::EntryType;
+
+    #[test]
+    fn normalize() {
+        let library = from_yaml_str(
+            "
+            entry:
+                type: article
+                title: \"  A   study\\tof  {Things} \"
+                author: [\"Doe,  Jane\", \"Roe, \\u0052ene\\u0301\"]
+                volume: 3
+                serial-number:
+                    doi: https://doi.org/10.1000/ABC.
+                    isbn: \" 978-3-16-148410-0 \"
+                parent:
+                    type: periodical
+                    title: \" Journal  of   Things\"
+            ",
+        )
+        .unwrap();
+
+        let entry = library.get("entry").unwrap().normalize();
+        assert_eq!(entry.title().unwrap().value.to_string(), "A study of Things");
+        let authors = entry.authors().unwrap();
+        assert_eq!(authors[0].given_name.as_deref(), Some("Jane"));
+        assert_eq!(authors[1].given_name.as_deref(), Some("Ren\u{e9}"));
+        assert_eq!(entry.doi(), Some("10.1000/abc"));
+        assert_eq!(entry.isbn(), Some("978-3-16-148410-0"));
+        assert_eq!(*entry.parents()[0].entry_type(), EntryType::Periodical);
+        assert_eq!(
+            entry.parents()[0].title().unwrap().value.to_string(),
+            "Journal of Things"
+        );
+        assert_eq!(entry.normalize(), entry);
+    }
+
+    #[test]
+    fn normalize_chunks() {
+        let chunk = |value: &str, kind| StringChunk { value: value.into(), kind };
+        let mut string = ChunkedString(vec![
+            chunk(" The ", ChunkKind::Normal),
+            chunk(" ", ChunkKind::Verbatim),
+            chunk("  NASA  ", ChunkKind::Verbatim),
+            chunk("x ", ChunkKind::Math),
+            chunk(" mission ", ChunkKind::Normal),
+        ]);
+        normalize_chunked(&mut string);
+        assert_eq!(
+            string.0,
+            vec![
+                chunk("The ", ChunkKind::Normal),
+                chunk("NASA ", ChunkKind::Verbatim),
+                chunk("x ", ChunkKind::Math),
+                chunk(" mission", ChunkKind::Normal),
+            ]
+        );
+    }
+}
@@ -1,14 +1,15 @@
 //! Base types for the bibliography items and their content.
 
-use std::borrow::Cow;
-use std::collections::BTreeMap;
-use std::fmt::{self, Display};
-use std::str::FromStr;
+use alloc::borrow::Cow;
+use alloc::collections::BTreeMap;
+use core::fmt::{self, Display};
+use core::str::FromStr;
 
 use serde::de::value::StrDeserializer;
 use serde::ser::SerializeMap;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
+use unic_langid::LanguageIdentifier;
 use url::Url;
 
 pub use numeric::*;
@@ -78,7 +79,7 @@ macro_rules! derive_or_from_str {
             where
             D: serde::Deserializer<'de>,
             {
-                use std::fmt;
+                use core::fmt;
                 use serde::de::{self, Visitor};
                 struct OurVisitor;
 
@@ -125,7 +126,18 @@ use deserialize_from_str;
 use serialize_display;
 
 /// Describes which kind of work a database entry refers to.
-#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash
+)]
 #[non_exhaustive]
 #[serde(rename_all = "kebab-case")]
 pub enum EntryType {
@@ -250,6 +262,7 @@ impl EntryType {
             Self::Web => Self::Web,
             Self::Scene => Self::Video,
             Self::Artwork => Self::Exhibition,
+            Self::Case => Self::Periodical,
             Self::Legislation => Self::Anthology,
             Self::Post => Self::Post,
             Self::Video => Self::Video,
@@ -459,6 +472,15 @@ impl<'de> Deserialize<'de> for SerialNumber {
     }
 }
 
+/// The title of an item in one of the languages of a multilingual edition.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct LocalizedTitle {
+    /// The language of the title.
+    pub language: LanguageIdentifier,
+    /// The title in that language.
+    pub title: FormatString,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,8 +1,8 @@
-use std::borrow::Cow;
-use std::convert::{TryFrom, TryInto};
-use std::fmt::Write;
-use std::fmt::{self, Display};
-use std::str::FromStr;
+use alloc::borrow::Cow;
+use core::convert::{TryFrom, TryInto};
+use core::fmt::Write;
+use core::fmt::{self, Display};
+use core::str::FromStr;
 
 use citationberg::{GrammarGender, NumberForm, OrdinalLookup};
 use serde::de::Visitor;
@@ -87,7 +87,7 @@ impl Numeric {
     }
 
     /// Creates a new `Numeric` from a range.
-    pub fn from_range(range: std::ops::Range<i32>) -> Self {
+    pub fn from_range(range: core::ops::Range<i32>) -> Self {
         Self {
             value: NumericValue::Set(vec![
                 (range.start, Some(NumericDelimiter::Hyphen)),
@@ -114,23 +114,19 @@ impl Numeric {
     }
 
     /// Format the value without the prefix and suffix.
-    pub fn fmt_value<T>(&self, buf: &mut T, machine_readable: bool) -> std::fmt::Result
+    pub fn fmt_value<T>(&self, buf: &mut T, machine_readable: bool) -> core::fmt::Result
     where
         T: fmt::Write,
     {
-        let format = |n: i32, buf: &mut T| -> std::fmt::Result { write!(buf, "{}", n) };
+        let format = |n: i32, buf: &mut T| -> core::fmt::Result { write!(buf, "{}", n) };
 
         match &self.value {
             &NumericValue::Number(n) => format(n, buf)?,
             NumericValue::Set(s) => {
-                for &(n, sep) in s {
+                for (i, &(n, sep)) in s.iter().enumerate() {
                     format(n, buf)?;
                     if let Some(sep) = sep {
-                        if machine_readable {
-                            buf.write_char(sep.as_char())?
-                        } else {
-                            write!(buf, "{}", sep)?
-                        }
+                        sep.write(buf, i + 1 < s.len(), machine_readable)?;
                     }
                 }
             }
@@ -139,7 +135,7 @@ impl Numeric {
         Ok(())
     }
 
-    fn fmt_custom<T>(&self, buf: &mut T, machine_readable: bool) -> std::fmt::Result
+    fn fmt_custom<T>(&self, buf: &mut T, machine_readable: bool) -> core::fmt::Result
     where
         T: fmt::Write,
     {
@@ -161,11 +157,11 @@ impl Numeric {
         form: NumberForm,
         gender: Option<GrammarGender>,
         ords: OrdinalLookup<'_>,
-    ) -> std::fmt::Result
+    ) -> core::fmt::Result
     where
         T: Write,
     {
-        let format = |n: i32, buf: &mut T| -> std::fmt::Result {
+        let format = |n: i32, buf: &mut T| -> core::fmt::Result {
             match form {
                 NumberForm::Ordinal => {
                     write!(buf, "{}{}", n, ords.lookup(n, gender).unwrap_or_default())
@@ -186,10 +182,10 @@ impl Numeric {
         match &self.value {
             &NumericValue::Number(n) => format(n, buf)?,
             NumericValue::Set(s) => {
-                for &(n, sep) in s {
+                for (i, &(n, sep)) in s.iter().enumerate() {
                     format(n, buf)?;
                     if let Some(sep) = sep {
-                        write!(buf, "{}", sep)?
+                        sep.write(buf, i + 1 < s.len(), false)?;
                     }
                 }
             }
@@ -218,7 +214,7 @@ impl Numeric {
     }
 
     /// Returns a range if the value is a range.
-    pub fn range(&self) -> Option<std::ops::Range<i32>> {
+    pub fn range(&self) -> Option<core::ops::Range<i32>> {
         self.value.range()
     }
 
@@ -232,7 +228,7 @@ impl Numeric {
     }
 
     /// Order the values according to CSL rules.
-    pub(crate) fn csl_cmp(&self, other: &Self) -> std::cmp::Ordering {
+    pub(crate) fn csl_cmp(&self, other: &Self) -> core::cmp::Ordering {
         let mut i = 0;
         loop {
             let a = self.nth(i);
@@ -241,13 +237,13 @@ impl Numeric {
             match (a, b) {
                 (Some(a), Some(b)) => {
                     let ord = a.cmp(&b);
-                    if ord != std::cmp::Ordering::Equal {
+                    if ord != core::cmp::Ordering::Equal {
                         return ord;
                     }
                 }
-                (Some(_), None) => return std::cmp::Ordering::Greater,
-                (None, Some(_)) => return std::cmp::Ordering::Less,
-                (None, None) => return std::cmp::Ordering::Equal,
+                (Some(_), None) => return core::cmp::Ordering::Greater,
+                (None, Some(_)) => return core::cmp::Ordering::Less,
+                (None, None) => return core::cmp::Ordering::Equal,
             }
 
             i += 1;
@@ -257,7 +253,7 @@ impl Numeric {
 
 impl<'a> MaybeTyped<Cow<'a, Numeric>> {
     /// Order the values according to CSL rules.
-    pub(crate) fn csl_cmp(&self, other: &Self) -> std::cmp::Ordering {
+    pub(crate) fn csl_cmp(&self, other: &Self) -> core::cmp::Ordering {
         match (self, other) {
             (MaybeTyped::Typed(a), MaybeTyped::Typed(b)) => a.csl_cmp(b),
             _ => self.to_string().cmp(&other.to_string()),
@@ -273,31 +269,37 @@ impl FromStr for Numeric {
         let prefix =
             s.eat_while(|c: char| !c.is_numeric() && !c.is_whitespace() && c != '-');
 
-        let value = number(&mut s).ok_or(NumericError::NoNumber)?;
-        s.eat_whitespace();
-
-        let value = match s.peek() {
-            Some(c) if is_delimiter(c) => {
-                s.eat();
-                s.eat_until(|c: char| !is_delimiter(c));
-                let mut items = vec![(value, Some(NumericDelimiter::try_from(c)?))];
-                loop {
-                    let num = number(&mut s).ok_or(NumericError::NoNumber)?;
-                    s.eat_whitespace();
-                    match NumericDelimiter::from_str(s.eat_while(is_delimiter)) {
-                        Ok(d) => {
-                            items.push((num, Some(d)));
-                        }
-                        Err(_) => {
-                            items.push((num, None));
-                            break;
-                        }
-                    }
+        let mut items = vec![];
+        loop {
+            let num = number(&mut s).ok_or(NumericError::NoNumber)?;
+            s.eat_whitespace();
+            let continues = s.eat_if('+');
+            s.eat_whitespace();
+
+            let delimiter = match s.peek() {
+                Some(c) if is_delimiter(c) => {
+                    s.eat_while(is_delimiter);
+                    Some(NumericDelimiter::try_from(c)?)
                 }
-                NumericValue::Set(items)
+                _ => None,
+            };
+
+            // The plus sign of a continued page takes the place of the
+            // delimiter that follows it.
+            if continues {
+                items.push((num, Some(NumericDelimiter::Continues)));
+            } else {
+                items.push((num, delimiter));
             }
 
-            _ => NumericValue::Number(value),
+            if delimiter.is_none() {
+                break;
+            }
+        }
+
+        let value = match items.as_slice() {
+            &[(n, None)] => NumericValue::Number(n),
+            _ => NumericValue::Set(items),
         };
         s.eat_whitespace();
         let post = s.eat_while(|c: char| !c.is_whitespace());
@@ -360,7 +362,7 @@ fn number(s: &mut Scanner) -> Option<i32> {
 }
 
 impl Display for Numeric {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> core::fmt::Result {
         self.fmt_custom(f, false)
     }
 }
@@ -376,7 +378,7 @@ pub enum NumericValue {
 
 impl NumericValue {
     /// Returns a range if the value is a range.
-    pub fn range(&self) -> Option<std::ops::Range<i32>> {
+    pub fn range(&self) -> Option<core::ops::Range<i32>> {
         match self {
             Self::Number(_) => None,
             Self::Set(vec) => {
@@ -423,6 +425,9 @@ pub enum NumericDelimiter {
     Ampersand,
     /// A hyphen. Will be converted to an en dash for display.
     Hyphen,
+    /// A plus sign after a page that continues discontinuously, as in "45+".
+    /// It is followed by a comma if more numbers follow.
+    Continues,
 }
 
 impl NumericDelimiter {
@@ -432,16 +437,41 @@ impl NumericDelimiter {
             NumericDelimiter::Comma => ',',
             NumericDelimiter::Ampersand => '&',
             NumericDelimiter::Hyphen => '-',
+            NumericDelimiter::Continues => '+',
+        }
+    }
+
+    /// Write the delimiter after a number, with the comma that separates a
+    /// continued page from the next number.
+    fn write<T: fmt::Write>(
+        self,
+        buf: &mut T,
+        more: bool,
+        machine_readable: bool,
+    ) -> fmt::Result {
+        if machine_readable {
+            buf.write_char(self.as_char())?;
+        } else {
+            write!(buf, "{}", self)?;
+        }
+
+        match self {
+            NumericDelimiter::Continues if more && machine_readable => {
+                buf.write_char(',')
+            }
+            NumericDelimiter::Continues if more => buf.write_str(", "),
+            _ => Ok(()),
         }
     }
 }
 
-impl std::fmt::Display for NumericDelimiter {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for NumericDelimiter {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             NumericDelimiter::Comma => f.write_str(", "),
             NumericDelimiter::Ampersand => f.write_str(" & "),
             NumericDelimiter::Hyphen => f.write_char('–'),
+            NumericDelimiter::Continues => f.write_char('+'),
         }
     }
 }
@@ -471,15 +501,61 @@ impl TryFrom<char> for NumericDelimiter {
             ',' => Ok(NumericDelimiter::Comma),
             '&' => Ok(NumericDelimiter::Ampersand),
             '-' | '–' => Ok(NumericDelimiter::Hyphen),
+            '+' => Ok(NumericDelimiter::Continues),
             _ => Err(NumericError::NotADelimiter),
         }
     }
 }
 
+/// Insert the separator between groups of three digits in all runs of at
+/// least four digits.
+pub(crate) fn group_digits(s: &str, separator: &str) -> String {
+    let mut res = String::with_capacity(s.len());
+    let mut chars = s.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        if !c.is_ascii_digit() {
+            res.push(c);
+            continue;
+        }
+
+        let mut end = start + 1;
+        while let Some(&(i, c)) = chars.peek() {
+            if !c.is_ascii_digit() {
+                break;
+            }
+            end = i + 1;
+            chars.next();
+        }
+
+        let digits = &s[start..end];
+        if digits.len() < 4 {
+            res.push_str(digits);
+            continue;
+        }
+
+        for (i, digit) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                res.push_str(separator);
+            }
+            res.push(digit);
+        }
+    }
+
+    res
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
 
+    #[test]
+    fn test_group_digits() {
+        assert_eq!(group_digits("1234–56", ","), "1,234–56");
+        assert_eq!(group_digits("999", "."), "999");
+        assert_eq!(group_digits("1234567, 12", "."), "1.234.567, 12");
+        assert_eq!(group_digits("S12345", "\u{202f}"), "S12\u{202f}345");
+    }
+
     #[test]
     fn test_mixed_range() {
         let s = "34,37--39";
@@ -493,4 +569,36 @@ mod test {
             ])
         );
     }
+
+    #[test]
+    fn test_continued_pages() {
+        let n: Numeric = "45+".parse().unwrap();
+        assert_eq!(
+            n.value,
+            NumericValue::Set(vec![(45, Some(NumericDelimiter::Continues))])
+        );
+        assert_eq!(n.to_string(), "45+");
+        assert!(!n.is_plural(false));
+
+        let n: Numeric = "45+, 50-52".parse().unwrap();
+        assert_eq!(
+            n.value,
+            NumericValue::Set(vec![
+                (45, Some(NumericDelimiter::Continues)),
+                (50, Some(NumericDelimiter::Hyphen)),
+                (52, None)
+            ])
+        );
+        assert_eq!(n.to_string(), "45+, 50–52");
+        assert_eq!(n.range(), None);
+
+        let mut buf = String::new();
+        n.fmt_value(&mut buf, true).unwrap();
+        assert_eq!(buf, "45+,50-52");
+        assert_eq!(buf.parse::<Numeric>().unwrap(), n);
+
+        let n: Numeric = "12-14, 45+".parse().unwrap();
+        assert_eq!(n.to_string(), "12–14, 45+");
+        assert_eq!(n.suffix, None);
+    }
 }
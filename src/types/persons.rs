@@ -1,6 +1,6 @@
-use std::borrow::Cow;
-use std::cmp::Ordering;
-use std::str::FromStr;
+use alloc::borrow::Cow;
+use core::cmp::Ordering;
+use core::str::FromStr;
 
 use citationberg::LongShortForm;
 use serde::ser::SerializeMap;
@@ -79,6 +79,12 @@ pub enum PersonRole {
     Illustrator,
     /// Provided narration or voice-over for the cited item.
     Narrator,
+    /// Helped the authors write the cited item, as in a book "by A with B".
+    /// These persons are listed after the authors.
+    With,
+    /// Assisted the authors, as in a book "by A with the assistance of B".
+    /// These persons are listed after the authors.
+    Assistant,
 
     /// Various other roles described by the contained string.
     #[serde(skip)]
@@ -102,6 +108,30 @@ derive_or_from_str! {
     }
 }
 
+/// The components of a [`Person`]'s name, borrowed for consumers that want to
+/// lay out names themselves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PersonView<'a> {
+    /// A natural person with a structured name.
+    Individual {
+        /// The family name without any particles.
+        family: &'a str,
+        /// The given name / forename.
+        given: Option<&'a str>,
+        /// A particle that is dropped when the family name stands alone, such
+        /// as the 'van' in 'van Beethoven, Ludwig'. This is the
+        /// [`Person::prefix`].
+        dropping_particle: Option<&'a str>,
+        /// A particle that is part of the family name and kept with it, such
+        /// as the 'de' of a person whose family name is 'de Gaulle'.
+        non_dropping_particle: Option<&'a str>,
+        /// A suffix such as 'Jr.' or 'IV'.
+        suffix: Option<&'a str>,
+    },
+    /// An organization or another name that has no further structure.
+    Institution(&'a str),
+}
+
 impl Serialize for Person {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -237,10 +267,10 @@ impl Person {
     /// `"K-P"` without a delimiter.
     pub fn initials(
         &self,
-        buf: &mut impl std::fmt::Write,
+        buf: &mut impl core::fmt::Write,
         delimiter: Option<&str>,
         with_hyphen: bool,
-    ) -> std::fmt::Result {
+    ) -> core::fmt::Result {
         let Some(gn) = &self.given_name else {
             return Ok(());
         };
@@ -283,9 +313,9 @@ impl Person {
     /// / single letters.
     pub fn first_name_with_delimiter(
         &self,
-        buf: &mut impl std::fmt::Write,
+        buf: &mut impl core::fmt::Write,
         delimiter: Option<&str>,
-    ) -> std::fmt::Result {
+    ) -> core::fmt::Result {
         let Some(name) = &self.given_name else {
             return Ok(());
         };
@@ -313,70 +343,98 @@ impl Person {
     /// Get the name with the family name fist, the initials
     /// afterwards, separated by a comma.
     pub fn name_first(&self, initials: bool, prefix_given_name: bool) -> String {
-        let mut res = if !prefix_given_name {
+        let mut res = String::with_capacity(self.len_hint());
+        self.write_name_first(&mut res, initials, prefix_given_name).unwrap();
+        res
+    }
+
+    /// Write the name with the family name first like [`Self::name_first`].
+    pub fn write_name_first(
+        &self,
+        buf: &mut impl core::fmt::Write,
+        initials: bool,
+        prefix_given_name: bool,
+    ) -> core::fmt::Result {
+        if !prefix_given_name {
             if let Some(prefix) = &self.prefix {
-                format!("{} {}", prefix, self.name)
-            } else {
-                self.name.clone()
+                buf.write_str(prefix)?;
+                buf.write_char(' ')?;
             }
-        } else {
-            self.name.clone()
-        };
+        }
+
+        buf.write_str(&self.name)?;
 
         if initials {
             if self.given_name.is_some() {
-                res += ", ";
-                self.initials(&mut res, Some("."), true).unwrap();
+                buf.write_str(", ")?;
+                self.initials(buf, Some("."), true)?;
             }
         } else if let Some(given_name) = &self.given_name {
-            res += ", ";
-            res += given_name;
+            buf.write_str(", ")?;
+            buf.write_str(given_name)?;
         }
 
         if prefix_given_name {
             if let Some(prefix) = &self.prefix {
                 if self.given_name.is_some() {
-                    res.push(' ');
+                    buf.write_char(' ')?;
                 }
 
-                res += prefix;
+                buf.write_str(prefix)?;
             }
         }
 
         if let Some(suffix) = &self.suffix {
-            res += ", ";
-            res += suffix;
+            buf.write_str(", ")?;
+            buf.write_str(suffix)?;
         }
-        res
+
+        Ok(())
     }
 
     /// Get the name with the given name first, the family name afterwards.
     pub fn given_first(&self, initials: bool) -> String {
-        let mut res = String::new();
+        let mut res = String::with_capacity(self.len_hint());
+        self.write_given_first(&mut res, initials).unwrap();
+        res
+    }
 
+    /// Write the name with the given name first like [`Self::given_first`].
+    pub fn write_given_first(
+        &self,
+        buf: &mut impl core::fmt::Write,
+        initials: bool,
+    ) -> core::fmt::Result {
         if initials {
             if self.given_name.is_some() {
-                self.initials(&mut res, Some("."), true).unwrap();
-                res.push(' ');
+                self.initials(buf, Some("."), true)?;
+                buf.write_char(' ')?;
             }
         } else if let Some(given_name) = &self.given_name {
-            res += given_name;
-            res.push(' ');
+            buf.write_str(given_name)?;
+            buf.write_char(' ')?;
         }
 
         if let Some(prefix) = &self.prefix {
-            res += prefix;
-            res += " ";
+            buf.write_str(prefix)?;
+            buf.write_char(' ')?;
         }
 
-        res += &self.name;
+        buf.write_str(&self.name)?;
 
         if let Some(suffix) = &self.suffix {
-            res += " ";
-            res += suffix;
+            buf.write_char(' ')?;
+            buf.write_str(suffix)?;
         }
 
-        res
+        Ok(())
+    }
+
+    /// The length of the full name with room for the separators, which is
+    /// enough for any of its forms.
+    fn len_hint(&self) -> usize {
+        let len = |part: &Option<String>| part.as_ref().map_or(0, |p| p.len() + 2);
+        self.name.len() + len(&self.given_name) + len(&self.prefix) + len(&self.suffix)
     }
 
     /// Get the non-dropping name particle in the family name.
@@ -422,6 +480,21 @@ impl Person {
         self.given_name.is_none() && self.suffix.is_none() && self.prefix.is_none()
     }
 
+    /// Get the normalized components of the name without rendering them.
+    pub fn view(&self) -> PersonView<'_> {
+        if self.is_institutional() {
+            return PersonView::Institution(&self.name);
+        }
+
+        PersonView::Individual {
+            family: self.name_without_particle(),
+            given: self.given_name.as_deref(),
+            dropping_particle: self.prefix.as_deref(),
+            non_dropping_particle: self.name_particle(),
+            suffix: self.suffix.as_deref(),
+        }
+    }
+
     /// Whether the name contains CJK characters.
     pub fn is_cjk(&self) -> bool {
         self.name.chars().any(is_cjk)
@@ -450,7 +523,7 @@ impl Person {
         other: &Self,
         form: LongShortForm,
         demote_particle: bool,
-    ) -> std::cmp::Ordering {
+    ) -> core::cmp::Ordering {
         let self_cjk = self.is_cjk();
         let other_cjk = other.is_cjk();
 
@@ -552,4 +625,18 @@ mod tests {
         p.first_name_with_delimiter(&mut s, Some(".")).unwrap();
         assert_eq!("James T.", s);
     }
+
+    #[test]
+    fn person_write_forms() {
+        let p = Person::from_strings(vec!["van Beethoven", "Jr.", "Ludwig"]).unwrap();
+        assert_eq!("van Beethoven, Ludwig, Jr.", p.name_first(false, false));
+        assert_eq!("Beethoven, L. van, Jr.", p.name_first(true, true));
+        assert_eq!("L. van Beethoven Jr.", p.given_first(true));
+
+        let mut s = String::from("By ");
+        p.write_given_first(&mut s, false).unwrap();
+        s.push_str("; ");
+        p.write_name_first(&mut s, true, false).unwrap();
+        assert_eq!("By Ludwig van Beethoven Jr.; van Beethoven, L., Jr.", s);
+    }
 }
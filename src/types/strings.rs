@@ -1,7 +1,7 @@
-use std::borrow::Cow;
-use std::fmt;
-use std::fmt::Display;
-use std::str::FromStr;
+use alloc::borrow::Cow;
+use core::fmt;
+use core::fmt::Display;
+use core::str::FromStr;
 
 use citationberg::LongShortForm;
 use serde::{de::Visitor, ser::SerializeMap, Deserialize, Serialize};
@@ -141,6 +141,26 @@ impl FormatString {
     }
 }
 
+/// The punctuation that joins a title and its subtitle.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub enum TitleSeparator {
+    /// Join with a colon, as in "Structure: A study".
+    #[default]
+    Colon,
+    /// Join with a period, as in "Structure. A study".
+    Period,
+}
+
+impl TitleSeparator {
+    /// The separator including the trailing space.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Colon => ": ",
+            Self::Period => ". ",
+        }
+    }
+}
+
 impl fmt::Display for FormatString {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         self.fmt_long(f)
@@ -1,4 +1,4 @@
-use std::{
+use core::{
     convert::TryInto,
     fmt::{Debug, Display, Write},
     str::FromStr,
@@ -28,8 +28,8 @@ impl<'de> Deserialize<'de> for Date {
     where
         D: serde::Deserializer<'de>,
     {
+        use core::fmt;
         use serde::de::Visitor;
-        use std::fmt;
         struct OurVisitor;
 
         impl<'de> Visitor<'de> for OurVisitor {
@@ -100,16 +100,16 @@ impl<'de> Deserialize<'de> for Date {
 }
 
 impl PartialOrd for Date {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
         let ord = self.year.cmp(&other.year);
-        if ord != std::cmp::Ordering::Equal {
+        if ord != core::cmp::Ordering::Equal {
             return Some(ord);
         }
 
         match (self.month, other.month) {
             (Some(a), Some(b)) => {
                 let ord = a.cmp(&b);
-                if ord != std::cmp::Ordering::Equal {
+                if ord != core::cmp::Ordering::Equal {
                     return Some(ord);
                 }
             }
@@ -123,7 +123,7 @@ impl PartialOrd for Date {
         match (self.day, other.day) {
             (Some(a), Some(b)) => {
                 let ord = a.cmp(&b);
-                if ord != std::cmp::Ordering::Equal {
+                if ord != core::cmp::Ordering::Equal {
                     return Some(ord);
                 }
             }
@@ -133,17 +133,17 @@ impl PartialOrd for Date {
             _ => return None,
         }
 
-        Some(std::cmp::Ordering::Equal)
+        Some(core::cmp::Ordering::Equal)
     }
 }
 
 impl Date {
     /// Order two dates according to the CSL specification.
-    pub(crate) fn csl_cmp(&self, other: &Self) -> std::cmp::Ordering {
+    pub(crate) fn csl_cmp(&self, other: &Self) -> core::cmp::Ordering {
         let ord_fn = |a: Option<u8>, b: Option<u8>| match (a, b) {
             (Some(a), Some(b)) => a.cmp(&b),
-            (Some(_), None) => std::cmp::Ordering::Greater,
-            (None, Some(_)) => std::cmp::Ordering::Less,
+            (Some(_), None) => core::cmp::Ordering::Greater,
+            (None, Some(_)) => core::cmp::Ordering::Less,
             (None, None) => self.approximate.cmp(&other.approximate),
         };
 
@@ -280,7 +280,7 @@ impl Date {
 }
 
 impl Display for Date {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         if self.approximate {
             f.write_char('~')?;
         }
@@ -312,6 +312,377 @@ impl Serialize for Date {
     }
 }
 
+/// A season of the year.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Season {
+    /// Spring.
+    Spring,
+    /// Summer.
+    Summer,
+    /// Autumn / fall.
+    Autumn,
+    /// Winter.
+    Winter,
+}
+
+impl Season {
+    /// Parse the English name of a season.
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name.to_lowercase().as_str() {
+            "spring" => Self::Spring,
+            "summer" => Self::Summer,
+            "autumn" | "fall" => Self::Autumn,
+            "winter" => Self::Winter,
+            _ => return None,
+        })
+    }
+
+    /// The EDTF pseudo-month code (21-24) of the season.
+    fn code(self) -> &'static str {
+        match self {
+            Self::Spring => "21",
+            Self::Summer => "22",
+            Self::Autumn => "23",
+            Self::Winter => "24",
+        }
+    }
+
+    /// Get the season from its EDTF pseudo-month code (21-24).
+    fn from_code(code: &str) -> Option<Self> {
+        Some(match code {
+            "21" => Self::Spring,
+            "22" => Self::Summer,
+            "23" => Self::Autumn,
+            "24" => Self::Winter,
+            _ => return None,
+        })
+    }
+}
+
+/// The state of an item that is not yet published in its final form.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PublicationStatus {
+    /// Accepted for publication, but not yet published. Styles like APA
+    /// print "in press" instead of a missing date.
+    InPress,
+    /// Published online before it was assigned to an issue. Styles like APA
+    /// print "Advance online publication" after the periodical.
+    AdvanceOnline,
+    /// Submitted to a journal or publisher, but not yet accepted. Styles like
+    /// APA describe a submitted manuscript as "Manuscript submitted for
+    /// publication".
+    Submitted,
+}
+
+impl PublicationStatus {
+    /// The English description of the status, like "in press".
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::InPress => "in press",
+            Self::AdvanceOnline => "advance online publication",
+            Self::Submitted => "submitted for publication",
+        }
+    }
+}
+
+/// An era of the Gregorian calendar.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Era {
+    /// Before the Common Era (BCE / BC).
+    BeforeCommonEra,
+    /// The Common Era (CE / AD).
+    CommonEra,
+}
+
+/// The structured parts of a possibly partial, uncertain, or ranged date.
+///
+/// Unlike [`Date`], this can represent seasons and ranges. It is the type of
+/// the `date` field of an entry and what the renderers consume.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DateParts {
+    /// The year (1 B.C.E. is represented as 0 and so forth).
+    pub year: i32,
+    /// The optional month (0-11).
+    pub month: Option<u8>,
+    /// The optional day (0-30).
+    pub day: Option<u8>,
+    /// The season, for dates like "Spring 2020" that have no month.
+    pub season: Option<Season>,
+    /// Whether the date is approximate.
+    pub circa: bool,
+    /// The end of the range if this date is a range.
+    pub end: Option<Date>,
+}
+
+impl DateParts {
+    /// The start of the date, dropping the season and range.
+    pub fn start(&self) -> Date {
+        Date {
+            year: self.year,
+            month: self.month,
+            day: self.day,
+            approximate: self.circa,
+        }
+    }
+
+    /// The era the year falls into.
+    pub fn era(&self) -> Era {
+        if self.year > 0 {
+            Era::CommonEra
+        } else {
+            Era::BeforeCommonEra
+        }
+    }
+
+    /// The year as counted within its era, i.e. always positive.
+    pub fn era_year(&self) -> i32 {
+        if self.year > 0 {
+            self.year
+        } else {
+            1 - self.year
+        }
+    }
+
+    /// Order two dates by their start according to the CSL specification.
+    pub(crate) fn csl_cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.start().csl_cmp(&other.start())
+    }
+
+    /// Parse a single date without range.
+    fn parse_single(source: &str) -> Result<Self, DateError> {
+        let mut s = source.trim();
+        let mut circa = false;
+        for prefix in ["~", "circa ", "ca.", "c."] {
+            if let Some(rest) = s.strip_prefix(prefix) {
+                s = rest.trim_start();
+                circa = true;
+                break;
+            }
+        }
+
+        if let Some(rest) = s.strip_suffix(['~', '?']) {
+            s = rest.trim_end();
+            circa = true;
+        }
+
+        // An EDTF season like "2020-21".
+        if let Some((year, code)) = s.split_once('-') {
+            if let Some(season) = Season::from_code(code) {
+                let year = parse_plain_year(year)?;
+                return Ok(Self {
+                    season: Some(season),
+                    circa,
+                    ..Self::from(Date::from_year(year))
+                });
+            }
+        }
+
+        let words: Vec<&str> = s
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|w| !w.is_empty())
+            .collect();
+
+        let parts = match words.as_slice() {
+            [word] => Self::from(Date::from_str(word)?),
+            [name, year] if Season::from_name(name).is_some() => Self {
+                season: Season::from_name(name),
+                ..Self::from(Date::from_year(parse_plain_year(year)?))
+            },
+            [year, era] | [era, year] if era_sign(era).is_some() => {
+                let year = parse_plain_year(year)?;
+                let year = if era_sign(era) == Some(Era::BeforeCommonEra) {
+                    1 - year
+                } else {
+                    year
+                };
+                Self::from(Date::from_year(year))
+            }
+            [month, year] if month_from_name(month).is_some() => Self::from(Date {
+                year: parse_plain_year(year)?,
+                month: month_from_name(month),
+                day: None,
+                approximate: false,
+            }),
+            [day, month, year] | [month, day, year]
+                if month_from_name(month).is_some() =>
+            {
+                let year = parse_plain_year(year)?;
+                let month = month_from_name(month).unwrap();
+                let day: u8 = day.parse().map_err(|_| DateError::UnknownFormat)?;
+                if day == 0 || day > days_in_month(month, year) {
+                    return Err(DateError::DayOutOfBounds(day));
+                }
+
+                Self::from(Date {
+                    year,
+                    month: Some(month),
+                    day: Some(day - 1),
+                    approximate: false,
+                })
+            }
+            _ => return Err(DateError::UnknownFormat),
+        };
+
+        Ok(Self { circa: parts.circa || circa, ..parts })
+    }
+}
+
+impl From<Date> for DateParts {
+    fn from(date: Date) -> Self {
+        Self {
+            year: date.year,
+            month: date.month,
+            day: date.day,
+            season: None,
+            circa: date.approximate,
+            end: None,
+        }
+    }
+}
+
+impl TryFrom<&str> for DateParts {
+    type Error = DateError;
+
+    /// Parse ISO 8601 dates (like `2020-06-15` or `2020-06`), EDTF seasons
+    /// (`2020-21`) and ranges (`2020/2021`), and common English formats like
+    /// `June 15, 2020`, `Spring 2020`, `c. 1850`, or `44 BC`.
+    fn try_from(source: &str) -> Result<Self, Self::Error> {
+        let Some((start, end)) = source.split_once('/') else {
+            return Self::parse_single(source);
+        };
+
+        let start = Self::parse_single(start)?;
+        let end = Self::parse_single(end)?;
+        Ok(Self { end: Some(end.start()), ..start })
+    }
+}
+
+impl Display for DateParts {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if let Some(season) = self.season {
+            if self.circa {
+                f.write_char('~')?;
+            }
+            write!(f, "{:04}-{}", self.year, season.code())?;
+        } else {
+            write!(f, "{}", self.start())?;
+        }
+
+        if let Some(end) = self.end {
+            write!(f, "/{}", end)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'de> Deserialize<'de> for DateParts {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use core::fmt;
+        use serde::de::{IntoDeserializer, Visitor};
+        struct OurVisitor;
+
+        impl<'de> Visitor<'de> for OurVisitor {
+            type Value = DateParts;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a date, season, or date range")
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Self::Value::try_from(value).map_err(|e| E::custom(e.to_string()))
+            }
+
+            fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::MapAccess<'de>,
+            {
+                Date::deserialize(de::value::MapAccessDeserializer::new(map))
+                    .map(Self::Value::from)
+            }
+
+            fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Date::deserialize(value.into_deserializer()).map(Self::Value::from)
+            }
+
+            fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Date::deserialize(value.into_deserializer()).map(Self::Value::from)
+            }
+        }
+
+        deserializer.deserialize_any(OurVisitor)
+    }
+}
+
+impl Serialize for DateParts {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        if self.season.is_none() && self.end.is_none() {
+            self.start().serialize(serializer)
+        } else {
+            serializer.serialize_str(&self.to_string())
+        }
+    }
+}
+
+/// Parse a year that is written without a sign or leading zeros.
+fn parse_plain_year(s: &str) -> Result<i32, DateError> {
+    if s.is_empty() || s.len() > 4 || !s.chars().all(|c| c.is_ascii_digit()) {
+        return Err(DateError::UnknownFormat);
+    }
+
+    s.parse().map_err(|_| DateError::UnknownFormat)
+}
+
+/// Parse an era designation like "BC" or "A.D.".
+fn era_sign(s: &str) -> Option<Era> {
+    match s.replace('.', "").to_uppercase().as_str() {
+        "BC" | "BCE" => Some(Era::BeforeCommonEra),
+        "AD" | "CE" => Some(Era::CommonEra),
+        _ => None,
+    }
+}
+
+/// Parse the full or abbreviated English name of a month into its index.
+fn month_from_name(s: &str) -> Option<u8> {
+    const MONTHS: [&str; 12] = [
+        "january",
+        "february",
+        "march",
+        "april",
+        "may",
+        "june",
+        "july",
+        "august",
+        "september",
+        "october",
+        "november",
+        "december",
+    ];
+
+    let s = s.trim_end_matches('.').to_lowercase();
+    if s.len() < 3 {
+        return None;
+    }
+
+    MONTHS.iter().position(|m| m.starts_with(&s)).map(|i| i as u8)
+}
+
 /// A duration.
 #[derive(Clone, Copy, Default, Debug, PartialEq, Eq, Hash)]
 pub struct Duration {
@@ -445,13 +816,13 @@ impl Duration {
 }
 
 impl PartialOrd for Duration {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
         Some(self.cmp(other))
     }
 }
 
 impl Ord for Duration {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
         self.milliseconds().cmp(&other.milliseconds())
     }
 }
@@ -477,7 +848,7 @@ impl FromStr for Duration {
 }
 
 impl Display for Duration {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         if self.days > 0 {
             write!(f, "{}:", self.days)?;
         }
@@ -534,7 +905,7 @@ impl DurationRange {
 }
 
 impl Display for DurationRange {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", self.start)?;
         if self.start != self.end {
             write!(f, "-{}", self.end)?;
@@ -557,7 +928,7 @@ serialize_display!(DurationRange);
 
 fn parse_int<R>(s: &mut Scanner, digits: R) -> Option<i32>
 where
-    R: std::ops::RangeBounds<usize>,
+    R: core::ops::RangeBounds<usize>,
 {
     s.eat_whitespace();
 
@@ -578,7 +949,7 @@ fn parse_unsigned_int<T, R>(s: &mut Scanner, digits: R) -> Option<T>
 where
     T: FromStr + Ord + Debug,
     <T as FromStr>::Err: Debug,
-    R: std::ops::RangeBounds<usize>,
+    R: core::ops::RangeBounds<usize>,
 {
     s.eat_whitespace();
     let num = s.eat_while(char::is_numeric);
@@ -620,14 +991,14 @@ fn parse_day(s: &mut Scanner) -> Result<u8, DateError> {
 }
 
 fn parse_month_with_year(s: &mut Scanner) -> Result<(i32, u8), DateError> {
-    let year = parse_year(s).unwrap();
+    let year = parse_year(s)?;
     s.eat_whitespace();
 
     if !s.eat_if('-') {
         return Err(DateError::UnknownFormat);
     }
 
-    let month = parse_month(s).unwrap();
+    let month = parse_month(s)?;
 
     s.eat_whitespace();
     if !s.done() {
@@ -688,6 +1059,71 @@ fn days_in_month(month: u8, year: i32) -> u8 {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_date_parts_parse() {
+        let parse = |s| DateParts::try_from(s).unwrap();
+        let date = |year, month, day| {
+            DateParts::from(Date { year, month, day, approximate: false })
+        };
+
+        assert_eq!(parse("2020-06"), date(2020, Some(5), None));
+        assert_eq!(parse("2020-06-15"), date(2020, Some(5), Some(14)));
+        assert_eq!(parse("2020"), date(2020, None, None));
+        assert_eq!(parse("June 2020"), date(2020, Some(5), None));
+        assert_eq!(parse("15 June 2020"), date(2020, Some(5), Some(14)));
+        assert_eq!(parse("Jun. 15, 2020"), date(2020, Some(5), Some(14)));
+        assert_eq!(parse("44 BC"), date(-43, None, None));
+        assert_eq!(parse("A.D. 44"), date(44, None, None));
+        assert_eq!(
+            parse("Spring 2020"),
+            DateParts {
+                season: Some(Season::Spring),
+                ..date(2020, None, None)
+            }
+        );
+        assert_eq!(
+            parse("2020-23"),
+            DateParts {
+                season: Some(Season::Autumn),
+                ..date(2020, None, None)
+            }
+        );
+        assert_eq!(parse("c. 1850"), DateParts { circa: true, ..date(1850, None, None) });
+        assert_eq!(parse("~1850"), DateParts { circa: true, ..date(1850, None, None) });
+        assert_eq!(
+            parse("2020-06/2021"),
+            DateParts {
+                end: Some(Date::from_year(2021)),
+                ..date(2020, Some(5), None)
+            }
+        );
+
+        assert_eq!(DateParts::try_from("2020-13"), Err(DateError::MonthOutOfBounds));
+        assert_eq!(
+            DateParts::try_from("31 June 2020"),
+            Err(DateError::DayOutOfBounds(31))
+        );
+        assert_eq!(DateParts::try_from("sometime"), Err(DateError::UnknownFormat));
+    }
+
+    #[test]
+    fn test_date_parts_display() {
+        for s in ["2020", "2020-06-15", "~1850", "2020-21", "~2020-24", "2020-06/2021"] {
+            assert_eq!(DateParts::try_from(s).unwrap().to_string(), s);
+        }
+    }
+
+    #[test]
+    fn test_date_parts_era() {
+        let parts = DateParts::from(Date::from_year(0));
+        assert_eq!(parts.era(), Era::BeforeCommonEra);
+        assert_eq!(parts.era_year(), 1);
+
+        let parts = DateParts::from(Date::from_year(2020));
+        assert_eq!(parts.era(), Era::CommonEra);
+        assert_eq!(parts.era_year(), 2020);
+    }
+
     #[test]
     fn test_duration_parse() {
         assert_eq!(
@@ -0,0 +1,74 @@
+//! Checks that the getters of entries borrow their data instead of cloning it.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use hayagriva::io::from_yaml_str;
+use hayagriva::types::TitleSeparator;
+
+/// An allocator that counts the allocations.
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+/// The number of allocations of the function.
+fn allocations<T>(f: impl FnOnce() -> T) -> usize {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let res = f();
+    let count = ALLOCATIONS.load(Ordering::Relaxed) - before;
+    drop(res);
+    count
+}
+
+// This is the only test in this file, so no other thread allocates while it
+// counts.
+#[test]
+fn getters_do_not_allocate() {
+    let library = from_yaml_str(
+        r#"
+book:
+    type: book
+    title: The Structure of Scientific Revolutions
+    author: ["Kuhn, Thomas S."]
+    date: 1962
+    publisher: University of Chicago Press
+    organization: University of Chicago
+    serial-number:
+        isbn: 978-0-226-45808-3
+subtitled:
+    type: book
+    title: Gödel, Escher, Bach
+    subtitle: an eternal golden braid
+"#,
+    )
+    .unwrap();
+    let book = library.get("book").unwrap();
+    let subtitled = library.get("subtitled").unwrap();
+
+    assert_eq!(allocations(|| book.title().map(|t| t.value.0.len())), 0);
+    assert_eq!(allocations(|| book.authors().map(<[_]>::len)), 0);
+    assert_eq!(allocations(|| book.publisher().is_some()), 0);
+    assert_eq!(allocations(|| book.organization().is_some()), 0);
+    assert_eq!(allocations(|| book.date_any().map(|d| d.year)), 0);
+    assert_eq!(allocations(|| book.resolve_date().map(|d| d.year)), 0);
+    assert_eq!(allocations(|| book.isbn().map(str::len)), 0);
+
+    // Only a title with a subtitle must be joined.
+    assert_eq!(allocations(|| book.full_title_cow(TitleSeparator::Colon)), 0);
+    assert!(allocations(|| book.full_title(TitleSeparator::Colon)) > 0);
+    assert!(allocations(|| subtitled.full_title_cow(TitleSeparator::Colon)) > 0);
+}
@@ -1,4 +1,4 @@
-#![cfg(feature = "rkyv")]
+#![cfg(feature = "archive")]
 use citationberg::Style;
 use citationberg::{Locale, LocaleFile, XmlError};
 use rkyv::Archive;
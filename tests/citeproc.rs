@@ -1,20 +1,31 @@
 //! Parse and execute the citeproc test suite.
 
+use std::collections::BTreeMap;
 use std::fmt;
 use std::path::PathBuf;
 use std::str::FromStr;
 
 mod common;
 use citationberg::taxonomy::Locator;
-use citationberg::{Locale, LocaleCode, Style, XmlError};
+use citationberg::{
+    DisambiguationRule, FontStyle, IndependentStyle, Locale, LocaleCode, NameAnd,
+    SecondFieldAlign, Style, XmlError,
+};
 use common::{ensure_repo, iter_files_with_name, CACHE_PATH};
 
 use citationberg::json as csl_json;
 use hayagriva::archive::{locales, style_by_name};
-use hayagriva::io::from_biblatex_str;
+use hayagriva::io::{from_biblatex_str, from_yaml_str};
+use hayagriva::lang::CaseExceptions;
+use hayagriva::types::{EntryType, TitleSeparator};
 use hayagriva::{
-    BibliographyDriver, BibliographyRequest, CitationItem, CitationRequest, CitePurpose,
-    Entry, LocatorPayload, SpecificLocator,
+    BibliographyDriver, BibliographyRequest, BidiIsolation, BufWriteFormat,
+    CitationEngine, CitationItem, CitationRequest, CitationTarget, CitePurpose,
+    ElemChild, ElemChildren, ElemMeta, Entry, IncrementalEngine, ItemLayout, KeyedCite,
+    LocatorPayload, NameOrder, NoteStream, PublisherLocation, Rendered,
+    RenderedBibliography, RenderedCitation, ReproductionNote, RoleLabelCase,
+    RoleLabelStyle, Signal, SpecificLocator, StyleOptions, TitleLanguages,
+    UncitedPlacement, UnknownKeyError, UnknownKeysError,
 };
 use unscanny::Scanner;
 
@@ -510,10 +521,7 @@ where
 
 #[test]
 fn purposes() {
-    let style = style_by_name("apa").unwrap();
-    let Style::Independent(style) = style else {
-        panic!("test has dependent style");
-    };
+    let style = independent_style("apa");
 
     let item: csl_json::Item = serde_json::from_str(
         r#"{
@@ -554,21 +562,14 @@ fn purposes() {
         ));
 
         let rendered = driver.finish(BibliographyRequest::new(&style, None, &[]));
-        let mut buf = String::new();
-        rendered.citations[0]
-            .citation
-            .write_buf(&mut buf, hayagriva::BufWriteFormat::Plain)
-            .unwrap();
+        let buf = plain(&rendered.citations[0].citation);
         assert_eq!(buf, res);
     }
 }
 
 #[test]
 fn case_folding() {
-    let style = style_by_name("chicago-author-date").unwrap();
-    let Style::Independent(style) = style else {
-        panic!("test has dependent style");
-    };
+    let style = independent_style("chicago-author-date");
 
     let item: csl_json::Item = serde_json::from_str(
         r#"{
@@ -593,21 +594,14 @@ fn case_folding() {
         Some(LocaleCode("de-DE".to_string())),
         &[],
     ));
-    let mut buf = String::new();
-    rendered.bibliography.unwrap().items[0]
-        .content
-        .write_buf(&mut buf, hayagriva::BufWriteFormat::Plain)
-        .unwrap();
+    let buf = plain(&rendered.bibliography.unwrap().items[0].content);
     assert_eq!(buf, ". my lowercase container title");
 }
 
 #[test]
 fn access_date() {
-    let style = style_by_name("apa").unwrap();
+    let style = independent_style("apa");
     let locales = locales();
-    let Style::Independent(style) = style else {
-        panic!("test has dependent style");
-    };
 
     let lib = from_biblatex_str(
         r#"@test{test,
@@ -629,10 +623,4545 @@ fn access_date() {
     ));
 
     let rendered = driver.finish(BibliographyRequest::new(&style, None, &locales));
+    let buf = plain(&rendered.bibliography.unwrap().items[0].content);
+    assert_eq!(buf, "(n.d.). Retrieved 2021, from https://example.com/");
+}
+
+/// The archived style with the given name, which must be an independent style.
+fn independent_style(name: &str) -> IndependentStyle {
+    let Style::Independent(style) = style_by_name(name).unwrap() else {
+        panic!("{name} is a dependent style");
+    };
+    style
+}
+
+/// The plain text of rendered elements.
+fn plain(rendered: &impl fmt::Display) -> String {
+    format!("{rendered:#}")
+}
+
+/// The HTML of rendered elements.
+fn render_html(children: &ElemChildren) -> String {
     let mut buf = String::new();
-    rendered.bibliography.unwrap().items[0]
-        .content
-        .write_buf(&mut buf, hayagriva::BufWriteFormat::Plain)
-        .unwrap();
-    assert_eq!(buf, "Retrieved 2021, from https://example.com/");
+    children.write_buf(&mut buf, BufWriteFormat::Html).unwrap();
+    buf
+}
+
+/// The plain text of the items of a bibliography.
+fn plain_items(bibliography: &RenderedBibliography) -> Vec<String> {
+    bibliography.items.iter().map(|item| plain(&item.content)).collect()
+}
+
+fn bibliography(style: &str, yaml: &str) -> RenderedBibliography {
+    bibliography_with(&independent_style(style), yaml)
+}
+
+fn bibliography_with(style: &IndependentStyle, yaml: &str) -> RenderedBibliography {
+    render_with_options(style, yaml, &StyleOptions::default())
+        .bibliography
+        .unwrap()
+}
+
+fn render_with_options(
+    style: &IndependentStyle,
+    yaml: &str,
+    options: &StyleOptions,
+) -> Rendered {
+    let locales = locales();
+    let lib = from_yaml_str(yaml).unwrap();
+    let mut driver: BibliographyDriver<'_, Entry> = BibliographyDriver::new();
+    for entry in lib.iter() {
+        driver.citation(
+            CitationRequest::from_items(
+                vec![CitationItem::with_entry(entry)],
+                style,
+                &locales,
+            )
+            .with_options(options.clone()),
+        );
+    }
+
+    driver.finish(
+        BibliographyRequest::new(style, None, &locales).with_options(options.clone()),
+    )
+}
+
+fn render_bibliography(style: &str, yaml: &str) -> Vec<String> {
+    render_bibliography_with(style, yaml, &StyleOptions::default())
+}
+
+fn render_bibliography_with(
+    style: &str,
+    yaml: &str,
+    options: &StyleOptions,
+) -> Vec<String> {
+    plain_items(
+        &render_with_options(&independent_style(style), yaml, options)
+            .bibliography
+            .unwrap(),
+    )
+}
+
+#[test]
+fn page_prefix_by_container() {
+    let bib = render_bibliography(
+        "apa",
+        r#"
+chapter:
+    type: Chapter
+    title: The chapter
+    author: Doe, Jane
+    page-range: 5-10
+    date: 2010
+    parent:
+        type: Book
+        title: The Book
+        publisher: Penguin
+single:
+    type: Chapter
+    title: Short chapter
+    author: Doe, Jane
+    page-range: 5
+    date: 2011
+    parent:
+        type: Book
+        title: The Book
+        publisher: Penguin
+article:
+    type: Article
+    title: The article
+    author: Doe, John
+    page-range: 5-10
+    date: 2012
+    parent:
+        type: Periodical
+        title: Journal
+        volume: 3
+        issue: 2
+"#,
+    );
+
+    assert_eq!(bib[0], "Doe, J. (2010). The chapter. In The Book (pp. 5–10). Penguin.");
+    assert_eq!(bib[1], "Doe, J. (2011). Short chapter. In The Book (p. 5). Penguin.");
+    assert_eq!(bib[2], "Doe, J. (2012). The article. Journal, 3(2), 5–10.");
+}
+
+#[test]
+fn rtf_bibliography() {
+    let bib = bibliography(
+        "apa",
+        r#"
+boell:
+    type: Book
+    title: Gruppenbild mit Dame
+    author: Böll, Heinrich
+    date: 1971
+    publisher: Kiepenheuer & Witsch
+"#,
+    );
+
+    assert_eq!(
+        hayagriva::render_rtf_bibliography(&bib),
+        include_str!("data/apa-bibliography.rtf")
+    );
+}
+
+#[test]
+fn no_date_term() {
+    let lib = from_yaml_str(
+        r#"
+undated:
+    type: Book
+    title: Undated
+    author: Doe, Jane
+"#,
+    )
+    .unwrap();
+    let entry = lib.nth(0).unwrap();
+    let locales = locales();
+
+    let cite = |style: &IndependentStyle, locale: Option<&str>, purpose| {
+        let locale = locale.map(|l| LocaleCode(l.to_string()));
+        let mut driver: BibliographyDriver<'_, Entry> = BibliographyDriver::new();
+        driver.citation(CitationRequest::new(
+            vec![CitationItem::new(entry, None, None, false, purpose)],
+            style,
+            locale.clone(),
+            &locales,
+            None,
+        ));
+
+        let rendered = driver.finish(BibliographyRequest::new(style, locale, &locales));
+        plain(&rendered.citations[0].citation)
+    };
+
+    let apa = independent_style("apa");
+    assert_eq!(cite(&apa, None, None), "(Doe, n.d.)");
+    assert_eq!(cite(&apa, None, Some(CitePurpose::Year)), "n.d.");
+    assert_eq!(cite(&apa, Some("de-DE"), None), "(Doe, o.\u{a0}J.)");
+    assert_eq!(cite(&apa, Some("de-DE"), Some(CitePurpose::Year)), "o.\u{a0}J.");
+
+    let undated = IndependentStyle::from_xml(
+        r#"<style xmlns="http://purl.org/net/xbiblio/csl" class="in-text" version="1.0">
+            <info>
+                <title>Undated</title>
+                <id>undated</id>
+                <updated>2023-01-01T00:00:00+00:00</updated>
+            </info>
+            <locale xml:lang="en">
+                <terms><term name="no date" form="short">undated</term></terms>
+            </locale>
+            <citation>
+                <layout prefix="(" suffix=")" delimiter="; ">
+                    <group delimiter=", ">
+                        <names variable="author"><name form="short"/></names>
+                        <choose>
+                            <if variable="issued">
+                                <date variable="issued" form="numeric" date-parts="year"/>
+                            </if>
+                            <else><text term="no date" form="short"/></else>
+                        </choose>
+                    </group>
+                </layout>
+            </citation>
+        </style>"#,
+    )
+    .unwrap();
+    assert_eq!(cite(&undated, None, None), "(Doe, undated)");
+    assert_eq!(cite(&undated, None, Some(CitePurpose::Year)), "undated");
+}
+
+#[test]
+fn partial_dates() {
+    let bib = render_bibliography(
+        "apa",
+        r#"
+caesar:
+    type: Book
+    title: Commentarii de Bello Gallico
+    author: Caesar, Gaius Julius
+    date: -0049
+cicero:
+    type: Book
+    title: De re publica
+    author: Cicero, Marcus Tullius
+    date: 54 BC
+news:
+    type: Article
+    title: Partial dates considered harmful
+    author: Doe, Jane
+    date: 2020-06
+    parent:
+        type: Newspaper
+        title: The Daily
+zero:
+    type: Book
+    title: Year zero
+    author: Zed, Zoe
+    date: 0000
+"#,
+    );
+
+    assert_eq!(bib[0], "Caesar, G. J. (50BC). Commentarii de Bello Gallico.");
+    assert_eq!(bib[1], "Cicero, M. T. (54BC). De re publica.");
+    assert_eq!(
+        bib[2],
+        "Doe, J. (2020, June). Partial dates considered harmful. The Daily."
+    );
+    // Year 0 is 1 BC, not 1 AD.
+    assert_eq!(bib[3], "Zed, Z. (1BC). Year zero.");
+}
+
+#[test]
+fn seasons_and_date_ranges() {
+    let bib = render_bibliography(
+        "apa",
+        r#"
+season:
+    type: Article
+    title: Seasonal affective disorder
+    author: Avery, Ann
+    date: Spring 2020
+    parent:
+        type: Newspaper
+        title: The Daily
+months:
+    type: Article
+    title: Partial dates considered harmful
+    author: Baker, Bob
+    date: 2020-06/2020-07
+    parent:
+        type: Newspaper
+        title: The Daily
+years:
+    type: Book
+    title: Collected letters
+    author: Carter, Cleo
+    date: 2019/2021
+"#,
+    );
+
+    assert_eq!(
+        bib,
+        [
+            "Avery, A. (2020, Spring). Seasonal affective disorder. The Daily.",
+            "Baker, B. (2020, June–July). Partial dates considered harmful. The Daily.",
+            "Carter, C. (2019–2021). Collected letters.",
+        ]
+    );
+
+    let style = IndependentStyle::from_xml(
+        r#"<style xmlns="http://purl.org/net/xbiblio/csl" class="in-text" version="1.0">
+            <info>
+                <title>Dates</title>
+                <id>dates</id>
+                <updated>2023-01-01T00:00:00+00:00</updated>
+            </info>
+            <citation>
+                <layout><text variable="title"/></layout>
+            </citation>
+            <bibliography>
+                <sort><key variable="title"/></sort>
+                <layout>
+                    <text variable="title" suffix=": "/>
+                    <choose>
+                        <if is-uncertain-date="issued"><text value="ca. "/></if>
+                    </choose>
+                    <date variable="issued" form="text"/>
+                </layout>
+            </bibliography>
+        </style>"#,
+    )
+    .unwrap();
+
+    let bib = plain_items(&bibliography_with(
+        &style,
+        r#"
+a:
+    type: Misc
+    title: A day range
+    date: 2020-06-05/2020-06-06
+b:
+    type: Misc
+    title: B month range
+    date: 2020-06-05/2020-07-06
+c:
+    type: Misc
+    title: C year range
+    date: 2019-12-30/2020-01-02
+d:
+    type: Misc
+    title: D circa
+    date: c. 1850
+e:
+    type: Misc
+    title: E season
+    date: 2020-23
+"#,
+    ));
+
+    assert_eq!(
+        bib,
+        [
+            "A day range: June 5–6, 2020",
+            "B month range: June 5–July 6, 2020",
+            "C year range: December 30, 2019–January 2, 2020",
+            "D circa: ca. 1850",
+            "E season: Autumn 2020",
+        ]
+    );
+}
+
+#[test]
+fn link_break_hints() {
+    let yaml = r#"
+article:
+    type: Article
+    title: Long identifiers
+    author: Doe, Jane
+    date: 2020
+    serial-number:
+        doi: 10.1016/j.long-doi.2020.01.001
+    parent:
+        type: Periodical
+        title: Journal
+"#;
+    let style = independent_style("apa");
+
+    // Links are not marked by default.
+    let bib = bibliography_with(&style, yaml);
+    let html = render_html(&bib.items[0].content);
+    assert!(html.ends_with("\">https://doi.org/10.1016/j.long-doi.2020.01.001</a>"));
+
+    let options = StyleOptions { link_breaks: true, ..Default::default() };
+    let bib = render_with_options(&style, yaml, &options).bibliography.unwrap();
+    let content = &bib.items[0].content;
+
+    let html = render_html(content);
+    assert!(html.ends_with(
+        "<a href=\"https://doi.org/10.1016/j.long-doi.2020.01.001\">https://doi<wbr>\
+         .org/<wbr>10<wbr>.1016/<wbr>j<wbr>.long<wbr>-doi<wbr>.2020<wbr>.01<wbr>.001</a>"
+    ));
+
+    assert!(hayagriva::render_latex(content).ends_with(
+        "\\href{https://doi.org/10.1016/j.long-doi.2020.01.001}{https://doi\
+         \\allowbreak{}.org/\\allowbreak{}10\\allowbreak{}.1016/\\allowbreak{}j\
+         \\allowbreak{}.long\\allowbreak{}-doi\\allowbreak{}.2020\\allowbreak{}.01\
+         \\allowbreak{}.001}"
+    ));
+}
+
+#[test]
+fn subtitle() {
+    let bib = render_bibliography(
+        "apa",
+        r#"
+with-subtitle:
+    type: Book
+    title: Structure
+    subtitle: a study of form
+    author: Doe, Jane
+    date: 2020
+without-subtitle:
+    type: Book
+    title: Structure
+    author: Doe, John
+    date: 2021
+"#,
+    );
+
+    assert_eq!(bib[0], "Doe, J. (2020). Structure: A study of form.");
+    assert_eq!(bib[1], "Doe, J. (2021). Structure.");
+}
+
+#[test]
+fn title_separator() {
+    let options = StyleOptions {
+        title_separator: TitleSeparator::Period,
+        ..Default::default()
+    };
+    let bib = render_bibliography_with(
+        "apa",
+        r#"
+with-subtitle:
+    type: Book
+    title: Structure
+    subtitle: a study of form
+    author: Doe, Jane
+    date: 2020
+"#,
+        &options,
+    );
+
+    assert_eq!(bib[0], "Doe, J. (2020). Structure. A study of form.");
+}
+
+#[test]
+fn audiobook() {
+    let yaml = r#"
+audiobook:
+    type: Book
+    title: Dune
+    author: Herbert, Frank
+    date: 2007
+    medium: Audiobook
+    publisher: Macmillan Audio
+    affiliated:
+        - role: narrator
+          names: ["Brick, Scott", "Bryant, Orlagh"]
+ebook:
+    type: Book
+    title: The Hobbit
+    author: Tolkien, J. R. R.
+    date: 2012
+    medium: eBook edition
+    publisher: HarperCollins
+"#;
+
+    let bib = render_bibliography("apa", yaml);
+    assert_eq!(bib[0], "Herbert, F. (2007). Dune [Audiobook]. Macmillan Audio.");
+    assert_eq!(
+        bib[1],
+        "Tolkien, J. R. R. (2012). The Hobbit [EBook edition]. HarperCollins."
+    );
+
+    // The bundled APA style does not print narrators, so check that they are
+    // passed on to styles that do.
+    let style = IndependentStyle::from_xml(
+        r#"<style xmlns="http://purl.org/net/xbiblio/csl" class="in-text" version="1.0">
+            <info>
+                <title>Narrated</title>
+                <id>narrated</id>
+                <updated>2023-01-01T00:00:00+00:00</updated>
+            </info>
+            <citation><layout><text variable="title"/></layout></citation>
+            <bibliography>
+                <layout>
+                    <group delimiter=" ">
+                        <names variable="author"><name/></names>
+                        <text variable="title" font-style="italic"/>
+                        <names variable="narrator" prefix="(" suffix=")">
+                            <name and="symbol" initialize-with=". "/>
+                            <label form="short" prefix=", " text-case="capitalize-first"/>
+                        </names>
+                        <text variable="medium" prefix="[" suffix="]"/>
+                    </group>
+                </layout>
+            </bibliography>
+        </style>"#,
+    )
+    .unwrap();
+
+    let buf = plain(&bibliography_with(&style, yaml).items[0].content);
+    assert_eq!(buf, "Frank Herbert Dune (S. Brick & O. Bryant, Narrs.) [Audiobook]");
+}
+
+#[test]
+fn style_options() {
+    let yaml = r#"
+rivers:
+    type: article
+    title: Rivers of the north
+    author: ["Doe, Jane", "Roe, Richard", "Poe, Edgar"]
+    date: 2020
+    serial-number:
+        doi: 10.1000/rivers
+    parent:
+        type: periodical
+        title: Hydrology
+        volume: 4
+"#;
+
+    let style = independent_style("apa");
+
+    let render = |options: &StyleOptions| {
+        let rendered = render_with_options(&style, yaml, options);
+        let citation = plain(&rendered.citations[0].citation);
+        let bib = plain(&rendered.bibliography.unwrap().items[0].content);
+        (citation, bib)
+    };
+
+    assert_eq!(
+        render(&StyleOptions::default()),
+        (
+            "(Doe et al., 2020)".to_string(),
+            "Doe, J., Roe, R., & Poe, E. (2020). Rivers of the north. Hydrology, 4. \
+             https://doi.org/10.1000/rivers"
+                .to_string()
+        )
+    );
+
+    let options = StyleOptions {
+        name_delimiter: Some("; ".into()),
+        and: Some(NameAnd::Text),
+        period_after_url: Some(true),
+        ..Default::default()
+    };
+    assert_eq!(
+        render(&options).1,
+        "Doe, J.; Roe, R.; and Poe, E. (2020). Rivers of the north. Hydrology, 4. \
+         https://doi.org/10.1000/rivers."
+    );
+}
+
+#[test]
+fn space_before_semicolon() {
+    let style = IndependentStyle::from_xml(
+        r#"<style xmlns="http://purl.org/net/xbiblio/csl" class="in-text" version="1.0">
+            <info>
+                <title>Semicolons</title>
+                <id>semicolons</id>
+                <updated>2023-01-01T00:00:00+00:00</updated>
+            </info>
+            <citation>
+                <layout><text variable="title"/></layout>
+            </citation>
+            <bibliography>
+                <layout>
+                    <text variable="title" suffix=" "/>
+                    <text value="; see also"/>
+                </layout>
+            </bibliography>
+        </style>"#,
+    )
+    .unwrap();
+    let yaml = r#"
+rivers:
+    type: book
+    title: Rivers
+"#;
+
+    let render = |options: &StyleOptions| {
+        let bib = render_with_options(&style, yaml, options).bibliography.unwrap();
+        plain(&bib.items[0].content)
+    };
+
+    // The space that a style puts before a semicolon is kept unless the
+    // delimiters are overridden.
+    assert_eq!(render(&StyleOptions::default()), "Rivers ; see also");
+    let options = StyleOptions {
+        name_delimiter: Some("; ".into()),
+        ..Default::default()
+    };
+    assert_eq!(render(&options), "Rivers; see also");
+}
+
+#[test]
+fn with_contributors() {
+    let yaml = r#"
+memoir:
+    type: book
+    title: My life on the ice
+    author: Gretzky, Wayne
+    affiliated:
+        - role: with
+          names: Reilly, Rick
+    date: 1990
+    publisher: HarperCollins
+
+memoir-two:
+    type: book
+    title: Climbing back
+    author: Hillary, Peter
+    affiliated:
+        - role: with
+          names: ["Elder, Anne", "Shaw, Brian"]
+    date: 1995
+    publisher: Viking
+"#;
+
+    assert_eq!(
+        render_bibliography("apa", yaml),
+        [
+            "Gretzky, W. (with Reilly, R.). (1990). My life on the ice. HarperCollins.",
+            "Hillary, P. (with Elder, A., & Shaw, B.). (1995). Climbing back. Viking.",
+        ]
+    );
+
+    // The phrase follows the language of the locale.
+    let style = independent_style("apa");
+    let locales = locales();
+    let lib = from_yaml_str(yaml).unwrap();
+    let locale = Some(LocaleCode("de-DE".into()));
+    let mut driver: BibliographyDriver<'_, Entry> = BibliographyDriver::new();
+    driver.citation(CitationRequest::new(
+        vec![CitationItem::with_entry(lib.get("memoir").unwrap())],
+        &style,
+        locale.clone(),
+        &locales,
+        None,
+    ));
+    let rendered = driver.finish(BibliographyRequest::new(&style, locale, &locales));
+    assert_eq!(
+        plain(&rendered.bibliography.unwrap().items[0].content),
+        "Gretzky, W. (mit Reilly, R.). (1990). My life on the ice. HarperCollins."
+    );
+}
+
+#[test]
+fn smart_punctuation() {
+    let yaml = r#"
+war:
+    type: article
+    title: The "war to end all wars" and the well-known peace, 1914-1918
+    author: Doe, Jane
+    date: 2014
+    parent:
+        type: periodical
+        title: History's Review
+"#;
+
+    let options = StyleOptions { smart_punctuation: true, ..Default::default() };
+    let render = |style: &str| {
+        let style = independent_style(style);
+        let rendered = render_with_options(&style, yaml, &options);
+        plain(&rendered.bibliography.unwrap().items[0].content)
+    };
+
+    assert_eq!(
+        render("apa"),
+        "Doe, J. (2014). The “war to end all wars” and the well-known peace, \
+         1914–1918. History’s Review."
+    );
+    assert_eq!(
+        render("chicago-author-date"),
+        "Doe, Jane. 2014. “The ‘War to End All Wars’ and the Well-Known Peace, \
+         1914–1918”. History’s Review"
+    );
+}
+
+#[test]
+fn legal() {
+    let yaml = r#"
+brown:
+    type: case
+    title: Brown v. Board of Education
+    date: 1954
+    volume: 347
+    page-range: 483
+    parent:
+        title: U.S.
+
+ada:
+    type: legislation
+    title: Americans With Disabilities Act of 1990
+    date: 1990
+    volume: 42
+    section: 12101
+    parent:
+        title: U.S.C.
+"#;
+
+    assert_eq!(
+        render_bibliography("apa", yaml),
+        [
+            "Americans With Disabilities Act of 1990, 42 U.S.C. § 12101 (1990).",
+            "Brown v. Board of Education, 347 U.S. 483 (1954).",
+        ]
+    );
+}
+
+#[test]
+fn non_breaking_spaces() {
+    let yaml = r#"
+tolkien:
+    type: book
+    title: The Lord of the Rings
+    author: Tolkien, John Ronald Reuel
+    date: 1954
+    publisher: Allen & Unwin
+"#;
+
+    let style = independent_style("apa");
+
+    let locales = locales();
+    let lib = from_yaml_str(yaml).unwrap();
+    let options = StyleOptions { non_breaking_spaces: true, ..Default::default() };
+    let mut driver: BibliographyDriver<'_, Entry> = BibliographyDriver::new();
+    driver.citation(
+        CitationRequest::from_items(
+            vec![CitationItem::new(
+                lib.get("tolkien").unwrap(),
+                Some(SpecificLocator(Locator::Page, LocatorPayload::Str("5"))),
+                None,
+                false,
+                None,
+            )],
+            &style,
+            &locales,
+        )
+        .with_options(options.clone()),
+    );
+
+    let rendered = driver
+        .finish(BibliographyRequest::new(&style, None, &locales).with_options(options));
+
+    let citation = plain(&rendered.citations[0].citation);
+    assert_eq!(citation, "(Tolkien, 1954, p.\u{a0}5)");
+
+    let bib = plain(&rendered.bibliography.unwrap().items[0].content);
+    assert_eq!(
+        bib,
+        "Tolkien, J.\u{a0}R.\u{a0}R. (1954). The Lord of the Rings. Allen & Unwin."
+    );
+}
+
+#[test]
+fn grouped_digits() {
+    let yaml = r#"
+atlas:
+    type: article
+    title: Atlas
+    author: Doe, Jane
+    date: 2020
+    page-range: 1234-1256
+    serial-number:
+        doi: 10.12345/67890
+    parent:
+        type: periodical
+        title: Maps
+        volume: 12000
+"#;
+
+    let style = independent_style("apa");
+
+    let locales = locales();
+    let lib = from_yaml_str(yaml).unwrap();
+    let options = StyleOptions { group_digits: true, ..Default::default() };
+    let render = |locale: &str| {
+        let locale = Some(LocaleCode(locale.into()));
+        let mut driver: BibliographyDriver<'_, Entry> = BibliographyDriver::new();
+        driver.citation(
+            CitationRequest::new(
+                vec![CitationItem::with_entry(lib.get("atlas").unwrap())],
+                &style,
+                locale.clone(),
+                &locales,
+                None,
+            )
+            .with_options(options.clone()),
+        );
+
+        let rendered = driver.finish(
+            BibliographyRequest::new(&style, locale, &locales)
+                .with_options(options.clone()),
+        );
+        plain(&rendered.bibliography.unwrap().items[0].content)
+    };
+
+    assert_eq!(
+        render("en-US"),
+        "Doe, J. (2020). Atlas. Maps, 12,000, 1,234–1,256. \
+         https://doi.org/10.12345/67890"
+    );
+    assert_eq!(
+        render("de-DE"),
+        "Doe, J. (2020). Atlas. Maps, 12.000, 1.234–1.256. \
+         https://doi.org/10.12345/67890"
+    );
+}
+
+#[test]
+fn link_targets() {
+    let yaml = r#"
+atlas:
+    type: article
+    title: Atlas
+    author: Doe, Jane
+    date: 2020
+    serial-number:
+        doi: 10.12345/67890
+    parent:
+        type: periodical
+        title: Maps
+
+globe:
+    type: web
+    title: Globe
+    author: Roe, Richard
+    date: 2021
+    url: https://example.com/globe
+"#;
+
+    let style = independent_style("modern-language-association");
+
+    let options = StyleOptions {
+        strip_link_scheme: true,
+        link_breaks: true,
+        ..Default::default()
+    };
+    let bib = render_with_options(&style, yaml, &options).bibliography.unwrap();
+    let html: Vec<_> = bib.items.iter().map(|item| render_html(&item.content)).collect();
+
+    assert!(html[0].ends_with(
+        "<a href=\"https://doi.org/10.12345/67890\">doi<wbr>.org/<wbr>10<wbr>\
+         .12345/<wbr>67890</a>"
+    ));
+    assert!(html[1].ends_with(
+        "<a href=\"https://example.com/globe\">example<wbr>.com/<wbr>globe</a>"
+    ));
+    assert!(hayagriva::render_rtf(&bib.items[0].content).ends_with(
+        "{\\field{\\*\\fldinst HYPERLINK \"https://doi.org/10.12345/67890\"}\
+         {\\fldrslt doi\\zwbo .org/\\zwbo 10\\zwbo .12345/\\zwbo 67890}}"
+    ));
+}
+
+#[test]
+fn narrative_conjunction() {
+    let yaml = r#"
+two:
+    type: article
+    title: Atlas
+    author: ["Smith, Jane", "Jones, Bob"]
+    date: 2020
+    parent:
+        type: periodical
+        title: Maps
+
+three:
+    type: article
+    title: Globe
+    author: ["Doe, Jane", "Roe, Richard", "Poe, Edgar"]
+    date: 2021
+    parent:
+        type: periodical
+        title: Maps
+"#;
+
+    let style = independent_style("apa");
+
+    let locales = locales();
+    let lib = from_yaml_str(yaml).unwrap();
+    let mut driver: BibliographyDriver<'_, Entry> = BibliographyDriver::new();
+    for key in ["two", "three"] {
+        for purpose in [None, Some(CitePurpose::Prose), Some(CitePurpose::Author)] {
+            driver.citation(CitationRequest::from_items(
+                vec![CitationItem::new(
+                    lib.get(key).unwrap(),
+                    None,
+                    None,
+                    false,
+                    purpose,
+                )],
+                &style,
+                &locales,
+            ));
+        }
+    }
+
+    let rendered = driver.finish(BibliographyRequest::new(&style, None, &locales));
+    let citations: Vec<_> =
+        rendered.citations.iter().map(|c| plain(&c.citation)).collect();
+
+    assert_eq!(
+        citations,
+        [
+            "(Smith & Jones, 2020)",
+            "Smith and Jones (2020)",
+            "Smith and Jones",
+            "(Doe et al., 2021)",
+            "Doe et al. (2021)",
+            "Doe et al.",
+        ]
+    );
+}
+
+#[test]
+fn empty_source() {
+    let style = IndependentStyle::from_xml(
+        r#"<style xmlns="http://purl.org/net/xbiblio/csl" class="in-text" version="1.0">
+            <info>
+                <title>Sources</title>
+                <id>sources</id>
+                <updated>2023-01-01T00:00:00+00:00</updated>
+            </info>
+            <citation>
+                <layout><text variable="title"/></layout>
+            </citation>
+            <bibliography>
+                <layout suffix=".">
+                    <group delimiter=", ">
+                        <text variable="container-title" font-style="italic"/>
+                        <text variable="publisher"/>
+                    </group>
+                </layout>
+            </bibliography>
+        </style>"#,
+    )
+    .unwrap();
+
+    let bib = bibliography_with(
+        &style,
+        r#"
+article:
+    type: article
+    title: An article
+    parent:
+        type: periodical
+        title: Journal
+website:
+    type: web
+    title: A website
+    url: https://example.com/
+book:
+    type: book
+    title: A book
+    publisher: Publisher
+"#,
+    );
+
+    assert_eq!(bib.items.len(), 3);
+    assert_eq!(bib.entries_with_empty_source(), vec![1]);
+}
+
+#[test]
+fn series_editors() {
+    let style = IndependentStyle::from_xml(
+        r#"<style xmlns="http://purl.org/net/xbiblio/csl" class="in-text" version="1.0">
+            <info>
+                <title>Series</title>
+                <id>series</id>
+                <updated>2023-01-01T00:00:00+00:00</updated>
+            </info>
+            <locale xml:lang="en">
+                <terms>
+                    <term name="collection-editor" form="short">
+                        <single>ser. ed.</single>
+                        <multiple>ser. eds.</multiple>
+                    </term>
+                </terms>
+            </locale>
+            <citation>
+                <layout><text variable="title"/></layout>
+            </citation>
+            <bibliography>
+                <layout>
+                    <group delimiter=". ">
+                        <text variable="title" font-style="italic"/>
+                        <group delimiter=", " prefix="(" suffix=")">
+                            <text variable="collection-title"/>
+                            <names variable="collection-editor">
+                                <name and="symbol" initialize-with=". "/>
+                                <label form="short" prefix=" (" suffix=")"/>
+                            </names>
+                            <number variable="collection-number"/>
+                        </group>
+                    </group>
+                </layout>
+            </bibliography>
+        </style>"#,
+    )
+    .unwrap();
+
+    let bib = bibliography_with(
+        &style,
+        r#"
+lncs:
+    type: book
+    title: Deep Learning
+    parent:
+        type: book
+        title: Lecture Notes in Computer Science
+        volume: 1234
+        editor: ["Goos, Gerhard", "Hartmanis, Juris"]
+lnm:
+    type: book
+    title: Topology
+    parent:
+        type: periodical
+        title: Lecture Notes in Mathematics
+        volume: 12
+        editor: Dold, Albrecht
+article:
+    type: article
+    title: An Article
+    parent:
+        type: periodical
+        title: Journal
+        parent:
+            type: periodical
+            title: Journal Series
+"#,
+    );
+
+    let rendered: Vec<String> =
+        bib.items.iter().map(|item| plain(&item.content)).collect();
+    assert_eq!(
+        rendered,
+        [
+            "Deep Learning. (Lecture Notes in Computer Science, G. Goos & J. Hartmanis (ser. eds.), 1234)",
+            "Topology. (Lecture Notes in Mathematics, A. Dold (ser. ed.), 12)",
+            "An Article. (Journal Series)",
+        ]
+    );
+}
+
+#[test]
+fn annotations() {
+    let apa = independent_style("apa");
+    let yaml = r#"
+noted:
+    type: Book
+    title: Noted
+    author: Doe, Jane
+    date: 2020
+    note: a classic study of the IETF, cited in ch. 2
+    abstract: We study standards.
+abstract:
+    type: Book
+    title: Abstract
+    author: Roe, Rich
+    date: 2021
+    abstract: We study THE web.
+plain:
+    type: Book
+    title: Plain
+    author: Poe, Paul
+    date: 2022
+"#;
+
+    let annotations = |options: &StyleOptions| -> Vec<Option<String>> {
+        render_with_options(&apa, yaml, options)
+            .bibliography
+            .unwrap()
+            .items
+            .into_iter()
+            .map(|item| {
+                item.annotation.map(|annotation| {
+                    render_html(&ElemChildren(vec![ElemChild::Elem(annotation)]))
+                })
+            })
+            .collect()
+    };
+
+    assert_eq!(annotations(&StyleOptions::default()), [None, None, None]);
+    // The bibliography is sorted by author.
+    assert_eq!(
+        annotations(&StyleOptions { annotations: true, ..Default::default() }),
+        [
+            Some(
+                "<div style=\"padding-left: 4em;\">a classic study of the IETF, cited in ch. 2</div>"
+                    .to_string()
+            ),
+            None,
+            Some("<div style=\"padding-left: 4em;\">We study THE web.</div>".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn publisher_location() {
+    let yaml = r#"
+book:
+    type: Book
+    title: A Book
+    author: Doe, Jane
+    date: 2020
+    publisher: Penguin
+    location: New York
+self-published:
+    type: Book
+    title: Self Published
+    author: Roe, Rich
+    date: 2020
+    location: Berlin
+talk:
+    type: Article
+    title: A Talk
+    author: Poe, Paul
+    date: 2020
+    parent:
+        type: Proceedings
+        title: Proceedings of the Conference
+        publisher: ACM
+        parent:
+            type: Conference
+            title: The Conference
+            location: Paris
+"#;
+
+    let render = |style: &str, policy| -> Vec<String> {
+        let style = independent_style(style);
+        let options = StyleOptions { publisher_location: policy, ..Default::default() };
+        plain_items(&render_with_options(&style, yaml, &options).bibliography.unwrap())
+    };
+
+    assert_eq!(
+        render("apa", None),
+        [
+            "Doe, J. (2020). A Book. Penguin.",
+            "Poe, P. (2020). A Talk. Proceedings of the Conference.",
+            "Roe, R. (2020). Self Published.",
+        ]
+    );
+    assert_eq!(
+        render("apa", Some(PublisherLocation::WhenPrePublisher)),
+        [
+            "Doe, J. (2020). A Book. New York: Penguin.",
+            "Poe, P. (2020). A Talk. Proceedings of the Conference.",
+            "Roe, R. (2020). Self Published.",
+        ]
+    );
+    assert_eq!(
+        render("apa", Some(PublisherLocation::Always)),
+        [
+            "Doe, J. (2020). A Book. New York: Penguin.",
+            "Poe, P. (2020). A Talk. Proceedings of the Conference.",
+            "Roe, R. (2020). Self Published. Berlin.",
+        ]
+    );
+    assert_eq!(
+        render("chicago-author-date", Some(PublisherLocation::Never)),
+        [
+            "Doe, Jane. 2020. A Book. Penguin",
+            "Poe, Paul. 2020. “A Talk”. In Proceedings of the Conference. ACM",
+            "Roe, Rich. 2020. Self Published",
+        ]
+    );
+    assert_eq!(
+        render("chicago-author-date", Some(PublisherLocation::WhenPrePublisher)),
+        render("chicago-author-date", None)
+    );
+}
+
+#[test]
+fn item_prefix_and_body() {
+    let yaml = r#"
+boell:
+    type: Book
+    title: Gruppenbild mit Dame
+    author: Böll, Heinrich
+    date: 1971
+    publisher: Kiepenheuer & Witsch
+"#;
+
+    let ieee = bibliography("ieee", yaml);
+    let item = &ieee.items[0];
+    assert_eq!(ieee.layout(), ItemLayout::Aligned(SecondFieldAlign::Flush));
+    assert_eq!(plain(&item.prefix()), "[1]");
+    assert!(plain(&item.body()).starts_with("H. Böll"));
+    assert_eq!(
+        hayagriva::render_latex_bibliography(&ieee),
+        "\\begin{list}{}{\\settowidth{\\labelwidth}{[1]}\
+         \\setlength{\\leftmargin}{\\labelwidth}\\addtolength{\\leftmargin}{\\labelsep}}\n\
+         \\item[{[1]}] H. Böll, \\textit{Gruppenbild mit Dame}. Kiepenheuer \\& Witsch, 1971.\n\
+         \\end{list}\n"
+    );
+
+    let apa = bibliography("apa", yaml);
+    let item = &apa.items[0];
+    assert_eq!(apa.layout(), ItemLayout::HangingIndent);
+    assert!(item.prefix().is_empty());
+    assert_eq!(
+        hayagriva::render_latex_bibliography(&apa),
+        "\\begin{list}{}{\\setlength{\\leftmargin}{2em}\\setlength{\\itemindent}{-2em}}\n\
+         \\item Böll, H. (1971). \\textit{Gruppenbild mit Dame}. Kiepenheuer \\& Witsch.\n\
+         \\end{list}\n"
+    );
+}
+
+#[test]
+fn chapter_contributors() {
+    let yaml = r#"
+layered:
+    type: Chapter
+    title: The Chapter
+    author: Doe, Jane
+    page-range: 10-20
+    parent:
+        type: Book
+        title: The Collected Works
+        author: Smith, Sam
+        editor: Roe, Rich
+        date: 2020
+        publisher: Penguin
+preface:
+    type: Chapter
+    title: Preface
+    parent:
+        type: Book
+        title: The Monograph
+        author: Smith, Sam
+        date: 2021
+        publisher: Penguin
+"#;
+
+    assert_eq!(
+        render_bibliography("chicago-author-date", yaml),
+        [
+            "Doe, Jane. 2020. “The Chapter”. In The Collected Works, by Sam Smith, \
+             edited by Rich Roe, 10–20. Penguin",
+            "Smith, Sam. 2021. “Preface”. In The Monograph. Penguin",
+        ]
+    );
+    assert_eq!(
+        render_bibliography("apa", yaml),
+        [
+            "Doe, J. (2020). The Chapter. In R. Roe (Ed.), The Collected Works \
+             (pp. 10–20). Penguin.",
+            "Smith, S. (2021). Preface. In The Monograph. Penguin.",
+        ]
+    );
+}
+
+#[test]
+fn bidi_isolation() {
+    let apa = independent_style("apa");
+    let yaml = r#"
+arabic:
+    type: Article
+    title: تاريخ الأدب العربي
+    author: Doe, Jane
+    date: 2020
+    page-range: 10-20
+    parent:
+        type: Periodical
+        title: Journal of Literature
+        volume: 3
+"#;
+
+    let render = |mode, format| {
+        let options = StyleOptions { bidi_isolation: mode, ..Default::default() };
+        let mut buf = String::new();
+        render_with_options(&apa, yaml, &options).bibliography.unwrap().items[0]
+            .content
+            .write_buf(&mut buf, format)
+            .unwrap();
+        buf
+    };
+
+    assert_eq!(
+        render(None, hayagriva::BufWriteFormat::Plain),
+        "Doe, J. (2020). تاريخ الأدب العربي. Journal of Literature, 3, 10–20."
+    );
+    assert_eq!(
+        render(Some(BidiIsolation::Characters), hayagriva::BufWriteFormat::Plain),
+        "Doe, J. (2020). \u{2068}تاريخ الأدب العربي\u{2069}. Journal of Literature, 3, 10–20."
+    );
+    assert_eq!(
+        render(Some(BidiIsolation::Markup), hayagriva::BufWriteFormat::Html),
+        "Doe, J. (2020). <bdi>تاريخ الأدب العربي</bdi>. <span style=\"font-style: italic;\">Journal of Literature</span>, \
+         <span style=\"font-style: italic;\">3</span>, 10–20."
+    );
+}
+
+#[test]
+fn chapter_doi() {
+    let bib = render_bibliography(
+        "apa",
+        r#"
+own:
+    type: Chapter
+    title: The Chapter
+    author: Doe, Jane
+    serial-number:
+        doi: 10.1000/chapter
+    parent:
+        type: Book
+        title: The Book
+        editor: Roe, Rich
+        date: 2020
+        publisher: Penguin
+        serial-number:
+            doi: 10.1000/book
+inherited:
+    type: Chapter
+    title: Other Chapter
+    author: Doe, Jane
+    parent:
+        type: Book
+        title: The Book
+        editor: Roe, Rich
+        date: 2020
+        publisher: Penguin
+        serial-number:
+            doi: 10.1000/book
+"#,
+    );
+
+    assert_eq!(
+        bib,
+        [
+            "Doe, J. (2020a). Other Chapter. In R. Roe (Ed.), The Book. Penguin. \
+             https://doi.org/10.1000/book",
+            "Doe, J. (2020b). The Chapter. In R. Roe (Ed.), The Book. Penguin. \
+             https://doi.org/10.1000/chapter",
+        ]
+    );
+}
+
+#[test]
+fn url_display() {
+    let apa = independent_style("apa");
+    let yaml = r#"
+page:
+    type: Web
+    title: A Page
+    author: Doe, Jane
+    date: 2020
+    url: https://example.com/news/2020/a-page?utm_source=feed&utm_medium=rss
+escaped:
+    type: Web
+    title: Escaped
+    author: Roe, Rich
+    date: 2020
+    url: https://example.com/caf%C3%A9%20cr%C3%A8me
+"#;
+
+    let render = |options: &StyleOptions| -> Vec<(String, String)> {
+        render_with_options(&apa, yaml, options)
+            .bibliography
+            .unwrap()
+            .items
+            .into_iter()
+            .map(|item| find_link(&item.content.0).expect("entry has no link"))
+            .collect()
+    };
+
+    let full = render(&StyleOptions::default());
+    assert_eq!(full[0].0, full[0].1);
+    assert_eq!(full[1].0, full[1].1);
+
+    let options = StyleOptions { strip_url_query: true, ..Default::default() };
+    assert_eq!(
+        render(&options)[0],
+        (
+            "https://example.com/news/2020/a-page".to_string(),
+            "https://example.com/news/2020/a-page?utm_source=feed&utm_medium=rss"
+                .to_string()
+        )
+    );
+
+    let options = StyleOptions { url_max_length: Some(36), ..Default::default() };
+    let shortened = render(&options);
+    assert_eq!(shortened[0].0, "https://example.com/news/2020/…");
+    assert_eq!(shortened[0].1, full[0].1);
+    assert_eq!(shortened[1].0, "https://example.com/caf%C3%A9%20cr…");
+
+    let options = StyleOptions { url_max_length: Some(32), ..Default::default() };
+    assert_eq!(render(&options)[1].0, "https://example.com/caf%C3%A9…");
+}
+
+/// Find the displayed text and the target of the first link.
+fn find_link(children: &[ElemChild]) -> Option<(String, String)> {
+    children.iter().find_map(|child| match child {
+        ElemChild::Link { text, url } => Some((text.text.clone(), url.clone())),
+        ElemChild::Elem(e) => find_link(&e.children.0),
+        _ => None,
+    })
+}
+
+#[test]
+fn edition_and_version() {
+    let bib = render_bibliography(
+        "apa",
+        r#"
+software:
+    type: Repository
+    title: Tool
+    author: Doe, Jane
+    date: 2020
+    edition: 2
+    serial-number:
+        version: 2.1.0
+    url: https://example.com/tool
+book:
+    type: Book
+    title: A Book
+    author: Roe, Rich
+    date: 2021
+    edition: 3
+    publisher: Penguin
+    serial-number:
+        version: v3
+versioned:
+    type: Book
+    title: Versioned Book
+    author: Poe, Paul
+    date: 2021
+    edition: Version 2
+    publisher: Penguin
+"#,
+    );
+
+    assert_eq!(
+        bib,
+        [
+            "Doe, J. (2020). Tool (Version 2.1.0) [Computer software]. \
+             https://example.com/tool",
+            "Poe, P. (2021). Versioned Book (Version 2). Penguin.",
+            "Roe, R. (2021). A Book (3rd ed.). Penguin.",
+        ]
+    );
+}
+
+#[test]
+fn citation_engine() {
+    let library = from_yaml_str(
+        r#"
+knuth:
+    type: Book
+    title: The Art of Computer Programming
+    author: Knuth, Donald
+    date: 1968
+    publisher: Addison-Wesley
+dijkstra:
+    type: Article
+    title: Go To Statement Considered Harmful
+    author: Dijkstra, Edsger
+    date: 1968
+    parent:
+        type: Periodical
+        title: Communications of the ACM
+turing:
+    type: Article
+    title: On Computable Numbers
+    author: Turing, Alan
+    date: 1937
+    parent:
+        type: Periodical
+        title: Proceedings of the London Mathematical Society
+uncited:
+    type: Book
+    title: Never Cited
+    author: Nobody, Noel
+    date: 2000
+"#,
+    )
+    .unwrap();
+    let locales = locales();
+
+    let document = |style: &str| -> (Vec<String>, Vec<String>) {
+        let style = independent_style(style);
+        let mut engine = CitationEngine::new(&library, &style, None, &locales);
+        assert_eq!(engine.cite(vec![KeyedCite::new("turing")], None), Ok(vec![true]));
+        assert_eq!(
+            engine.cite(vec![KeyedCite::new("knuth"), KeyedCite::new("turing")], None),
+            Ok(vec![true, false])
+        );
+        // Unknown keys are reported together, and the known keys are still
+        // cited.
+        assert_eq!(
+            engine.cite(
+                vec![
+                    KeyedCite::new("missing"),
+                    KeyedCite::new("dijkstra"),
+                    KeyedCite::new("absent"),
+                ],
+                None
+            ),
+            Err(UnknownKeysError(vec!["missing".into(), "absent".into()]))
+        );
+        assert!(engine.is_cited("dijkstra"));
+        let page = SpecificLocator(Locator::Page, LocatorPayload::Str("42"));
+        assert_eq!(
+            engine.cite(vec![KeyedCite::with_locator("knuth", page)], None),
+            Ok(vec![false])
+        );
+        assert_eq!(
+            engine.cited_keys_in_order().collect::<Vec<_>>(),
+            ["turing", "knuth", "dijkstra"]
+        );
+
+        let rendered = engine.finish();
+        let citations = rendered.citations.iter().map(|c| plain(&c.citation)).collect();
+        let bibliography = rendered
+            .bibliography
+            .unwrap()
+            .items
+            .iter()
+            .map(|i| match &i.first_field {
+                Some(first) => format!("{} {}", plain(first), plain(&i.content)),
+                None => plain(&i.content),
+            })
+            .collect();
+        (citations, bibliography)
+    };
+
+    let (citations, bibliography) = document("ieee");
+    assert_eq!(citations, ["[1]", "[1], [2]", "[3]", "[2, p. 42]"]);
+    assert_eq!(bibliography.len(), 3);
+    assert!(bibliography[0].starts_with("[1] A. Turing"));
+    assert!(bibliography[1].starts_with("[2] D. Knuth"));
+    assert!(bibliography[2].starts_with("[3] E. Dijkstra"));
+
+    let (citations, bibliography) = document("apa");
+    assert_eq!(
+        citations,
+        [
+            "(Turing, 1937)",
+            "(Knuth, 1968; Turing, 1937)",
+            "(Dijkstra, 1968)",
+            "(Knuth, 1968, p. 42)"
+        ]
+    );
+    assert_eq!(bibliography.len(), 3);
+    assert!(bibliography[0].starts_with("Dijkstra, E."));
+    assert!(bibliography[1].starts_with("Knuth, D."));
+    assert!(bibliography[2].starts_with("Turing, A."));
+}
+
+#[test]
+fn grouped_references() {
+    let yaml = r#"
+knuth:
+    type: Book
+    title: The Art of Computer Programming
+    author: Knuth, Donald
+    date: 1968
+dijkstra:
+    type: Article
+    title: Go To Statement Considered Harmful
+    author: Dijkstra, Edsger
+    date: 1968
+    parent:
+        type: Periodical
+        title: Communications of the ACM
+turing:
+    type: Article
+    title: On Computable Numbers
+    author: Turing, Alan
+    date: 1937
+    parent:
+        type: Periodical
+        title: Proceedings of the London Mathematical Society
+undated:
+    type: Book
+    title: Undated Notes
+    author: Anon, Ann
+"#;
+    let library = from_yaml_str(yaml).unwrap();
+    let bib = bibliography("apa", yaml);
+
+    // The most recent year comes first and the undated entries last, like in
+    // `Library::grouped`.
+    let by_year = bib.grouped_by_year(&library);
+    assert_eq!(
+        by_year.iter().map(|(year, _)| *year).collect::<Vec<_>>(),
+        [Some(1968), Some(1937), None]
+    );
+    let refs = &by_year[0].1;
+    assert_eq!(refs.len(), 2);
+    assert!(refs[0].starts_with("Dijkstra, E. (1968)."));
+    assert!(refs[1].starts_with("Knuth, D. (1968)."));
+    assert_eq!(by_year[1].1.len(), 1);
+    assert_eq!(by_year[2].1.len(), 1);
+    assert!(by_year[2].1[0].starts_with("Anon, A."));
+
+    let by_type = bib.grouped_by_type(&library);
+    assert_eq!(
+        by_type.iter().map(|(entry_type, _)| *entry_type).collect::<Vec<_>>(),
+        [EntryType::Article, EntryType::Book]
+    );
+    assert!(by_type[0].1[0].starts_with("Dijkstra"));
+    assert!(by_type[0].1[1].starts_with("Turing"));
+    assert!(by_type[1].1[0].starts_with("Anon"));
+    assert!(by_type[1].1[1].starts_with("Knuth"));
+}
+
+#[test]
+fn given_name_disambiguation() {
+    let yaml = r#"
+john:
+    type: Book
+    title: One
+    author: Smith, John
+    date: 2020
+anna:
+    type: Book
+    title: Two
+    author: Smith, Anna
+    date: 2019
+again:
+    type: Book
+    title: Three
+    author: Smith, John
+    date: 2021
+doe:
+    type: Book
+    title: Four
+    author: Doe, Jane
+    date: 2020
+"#;
+
+    let style = independent_style("apa");
+    let render = |yaml: &str| {
+        let options = StyleOptions {
+            givenname_disambiguation: Some(DisambiguationRule::PrimaryName),
+            ..Default::default()
+        };
+        let rendered = render_with_options(&style, yaml, &options);
+        let citations: Vec<_> =
+            rendered.citations.iter().map(|c| plain(&c.citation)).collect();
+        let bibliography: Vec<_> = plain_items(&rendered.bibliography.unwrap());
+        (citations, bibliography)
+    };
+
+    // Only the colliding names get initials, even if their years differ. The
+    // same person cited twice is no collision.
+    let (citations, bibliography) = render(yaml);
+    assert_eq!(
+        citations,
+        ["(J. Smith, 2020)", "(A. Smith, 2019)", "(J. Smith, 2021)", "(Doe, 2020)"]
+    );
+    assert_eq!(
+        bibliography,
+        [
+            "Doe, J. (2020). Four.",
+            "Smith, A. (2019). Two.",
+            "Smith, J. (2020). One.",
+            "Smith, J. (2021). Three.",
+        ]
+    );
+
+    let (citations, _) = render(&yaml.replace("Smith, Anna", "Smith, James"));
+    assert_eq!(
+        citations,
+        [
+            "(John Smith, 2020)",
+            "(James Smith, 2019)",
+            "(John Smith, 2021)",
+            "(Doe, 2020)"
+        ]
+    );
+
+    let (citations, _) = render(&yaml.replace("Smith, Anna", "Roe, Anna"));
+    assert_eq!(
+        citations,
+        ["(Smith, 2020)", "(Roe, 2019)", "(Smith, 2021)", "(Doe, 2020)"]
+    );
+}
+
+#[test]
+fn proquest_dissertation() {
+    let options = StyleOptions {
+        thesis_publication_numbers: true,
+        ..Default::default()
+    };
+    let bib = render_bibliography_with(
+        "apa",
+        r#"
+numbered:
+    type: Thesis
+    title: A Study of Things
+    author: Smith, John
+    date: 2020
+    genre: Doctoral dissertation
+    serial-number: 12345
+    organization: University of Somewhere
+    archive: ProQuest Dissertations and Theses Global
+unnumbered:
+    type: Thesis
+    title: Another Study
+    author: Doe, Jane
+    date: 2019
+    genre: Doctoral dissertation
+    organization: University of Elsewhere
+    archive: ProQuest Dissertations and Theses Global
+unpublished:
+    type: Thesis
+    title: A Local Study
+    author: Roe, Rich
+    date: 2018
+    genre: Doctoral dissertation
+    organization: University of Nowhere
+"#,
+        &options,
+    );
+
+    assert_eq!(
+        bib,
+        [
+            "Doe, J. (2019). Another Study [Doctoral dissertation, University of \
+             Elsewhere]. ProQuest Dissertations and Theses Global.",
+            "Roe, R. (2018). A Local Study [Doctoral dissertation]. University of \
+             Nowhere.",
+            "Smith, J. (2020). A Study of Things (Publication No. 12345) [Doctoral \
+             dissertation, University of Somewhere]. ProQuest Dissertations and \
+             Theses Global.",
+        ]
+    );
+}
+
+#[test]
+fn ibid_in_notes() {
+    let library = from_yaml_str(
+        r#"
+first:
+    type: Book
+    title: The First Book
+    author: Smith, John
+    date: 2020
+    publisher: Penguin
+    location: New York
+second:
+    type: Book
+    title: The Second Book
+    author: Doe, Jane
+    date: 2019
+    publisher: Knopf
+    location: Boston
+"#,
+    )
+    .unwrap();
+    let locales = locales();
+    let style = independent_style("turabian-fullnote-8");
+
+    let page = |p| Some(SpecificLocator(Locator::Page, LocatorPayload::Str(p)));
+    let notes: Vec<Vec<(&str, Option<SpecificLocator>)>> = vec![
+        vec![("first", page("45"))],
+        vec![("first", page("45"))],
+        vec![("first", page("52"))],
+        vec![("first", None)],
+        vec![("second", None)],
+        vec![("first", page("3"))],
+        vec![("first", page("3")), ("second", None)],
+        vec![("second", None)],
+        vec![("second", page("7"))],
+    ];
+
+    let mut engine = CitationEngine::new(&library, &style, None, &locales);
+    for (i, cites) in notes.into_iter().enumerate() {
+        let cites = cites
+            .into_iter()
+            .map(|(key, locator)| KeyedCite { locator, ..KeyedCite::new(key) })
+            .collect();
+        engine.cite(cites, Some(i + 1)).unwrap();
+    }
+
+    let notes: Vec<_> =
+        engine.finish().citations.iter().map(|c| plain(&c.citation)).collect();
+
+    assert_eq!(
+        notes,
+        [
+            "John Smith, The First Book (New York: Penguin, 2020), 45.",
+            "Ibid.",
+            "Ibid., 52.",
+            // "Ibid." would refer to page 52.
+            "Smith, The First Book.",
+            "Jane Doe, The Second Book (Boston: Knopf, 2019).",
+            "Smith, The First Book, 3.",
+            "Ibid.; Doe, The Second Book.",
+            // The previous note cites two works.
+            "Doe, The Second Book.",
+            "Ibid., 7.",
+        ]
+    );
+}
+
+#[test]
+fn ascii_only() {
+    let style = independent_style("apa");
+    let options = StyleOptions { ascii_only: true, ..Default::default() };
+    let render = |yaml: &str| {
+        let rendered = render_with_options(&style, yaml, &options);
+        let items = plain_items(&rendered.bibliography.unwrap());
+        (items, rendered.non_ascii)
+    };
+
+    assert_eq!(
+        render(
+            r#"
+godel:
+    type: Book
+    title: Über formal unentscheidbare Sätze
+    author: Gödel, Kurt
+    date: 1931
+    publisher: Springer
+"#
+        ),
+        (
+            vec!["Godel, K. (1931). Uber formal unentscheidbare Satze. Springer.".into()],
+            vec![]
+        )
+    );
+
+    assert_eq!(
+        render(
+            r#"
+tokyo:
+    type: Book
+    title: 東京
+    author: Åberg, Jörg
+    date: 2001
+"#
+        ),
+        (vec!["Aberg, J. (2001). 東京.".into()], vec!['京', '東'])
+    );
+}
+
+#[test]
+fn bilingual_title() {
+    let style = independent_style("apa");
+    let render = |yaml: &str, title_languages| {
+        let options = StyleOptions { title_languages, ..Default::default() };
+        plain(
+            &render_with_options(&style, yaml, &options)
+                .bibliography
+                .unwrap()
+                .items[0]
+                .content,
+        )
+    };
+
+    let yaml = r#"
+rilke:
+    type: Book
+    author: Rilke, Rainer Maria
+    date: 1996
+    publisher: Insel
+    language: de
+    titles:
+        - language: en
+          title: Poems
+        - language: de
+          title: Gedichte
+        - language: fr
+          title: Poèmes
+"#;
+    assert_eq!(render(yaml, None), "Rilke, R. M. (1996). Gedichte. Insel.");
+    assert_eq!(
+        render(yaml, Some(TitleLanguages::WithTranslation)),
+        "Rilke, R. M. (1996). Gedichte [Poems]. Insel."
+    );
+    assert_eq!(
+        render(yaml, Some(TitleLanguages::All)),
+        "Rilke, R. M. (1996). Gedichte / Poems / Poèmes. Insel."
+    );
+
+    // Without a language, the title in the language of the bibliography is
+    // primary and the translation is the next title.
+    let yaml = yaml.replace("    language: de\n", "");
+    assert_eq!(
+        render(&yaml, Some(TitleLanguages::WithTranslation)),
+        "Rilke, R. M. (1996). Poems [Gedichte]. Insel."
+    );
+}
+
+#[test]
+fn locator_types() {
+    let library = from_yaml_str(
+        r#"
+smith:
+    type: Book
+    title: The Book
+    author: Smith, John
+    date: 2020
+    publisher: Penguin
+"#,
+    )
+    .unwrap();
+    let locales = locales();
+    let locators = [
+        SpecificLocator::new(Locator::Page, "12-15"),
+        SpecificLocator::new(Locator::Paragraph, "4"),
+        SpecificLocator::new(Locator::Chapter, "3"),
+        SpecificLocator::new(Locator::Figure, "2"),
+        SpecificLocator::new(Locator::Timestamp, "01:02:03"),
+        SpecificLocator::custom("box", "7"),
+    ];
+
+    let cite = |style: &str| {
+        let style = independent_style(style);
+        let mut engine = CitationEngine::new(&library, &style, None, &locales);
+        for locator in locators {
+            engine
+                .cite(vec![KeyedCite::with_locator("smith", locator)], None)
+                .unwrap();
+        }
+        engine
+            .finish()
+            .citations
+            .iter()
+            .map(|c| plain(&c.citation))
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        cite("apa"),
+        [
+            "(Smith, 2020, pp. 12–15)",
+            "(Smith, 2020, para. 4)",
+            "(Smith, 2020, Chapter 3)",
+            "(Smith, 2020, fig. 2)",
+            "(Smith, 2020, 01:02:03)",
+            "(Smith, 2020, box 7)",
+        ]
+    );
+    assert_eq!(
+        cite("chicago-author-date"),
+        [
+            "(Smith 2020, 12–15)",
+            "(Smith 2020, para. 4)",
+            "(Smith 2020, chap. 3)",
+            "(Smith 2020, fig. 2)",
+            "(Smith 2020, 01:02:03)",
+            "(Smith 2020, box 7)",
+        ]
+    );
+}
+
+#[test]
+fn disambiguation_map() {
+    let bib = bibliography(
+        "apa",
+        r#"
+smith-b:
+    type: Article
+    title: Beta
+    author: Smith, John
+    date: 2020
+smith-a:
+    type: Article
+    title: Alpha
+    author: Smith, John
+    date: 2020
+lee:
+    type: Article
+    title: Gamma
+    author: Lee, Anna
+    date: 2020
+"#,
+    );
+
+    let keys: Vec<_> = bib.items.iter().map(|item| item.key.as_str()).collect();
+    assert_eq!(keys, ["lee", "smith-a", "smith-b"]);
+    assert_eq!(
+        bib.disambiguation_map(),
+        BTreeMap::from([(0, String::new()), (1, "a".into()), (2, "b".into())])
+    );
+}
+
+#[test]
+fn citation_affixes() {
+    let library = from_yaml_str(
+        r#"
+smith:
+    type: Article
+    title: A Review
+    author: Smith, John
+    date: 2020
+"#,
+    )
+    .unwrap();
+    let entry = library.get("smith").unwrap();
+    let locales = locales();
+    let style = independent_style("apa");
+
+    let mut driver = BibliographyDriver::new();
+    let request = |locator| {
+        CitationRequest::from_items(
+            vec![CitationItem::with_locator(entry, locator)],
+            &style,
+            &locales,
+        )
+    };
+    driver.citation(request(None).with_prefix("e.g.,"));
+    driver.citation(request(None).with_suffix("for a review"));
+    driver.citation(
+        request(Some(SpecificLocator::new(Locator::Page, "3")))
+            .with_prefix("see ")
+            .with_suffix(", emphasis added"),
+    );
+
+    let rendered = driver.finish(BibliographyRequest::new(&style, None, &locales));
+    let citations: Vec<_> =
+        rendered.citations.iter().map(|c| plain(&c.citation)).collect();
+    assert_eq!(
+        citations,
+        [
+            "(e.g., Smith, 2020)",
+            "(Smith, 2020, for a review)",
+            "(see Smith, 2020, p. 3, emphasis added)",
+        ]
+    );
+
+    // The affixes remain distinguishable from the cites.
+    let children = &rendered.citations[2].citation.0;
+    let affix = |meta| {
+        children.iter().find_map(|child| match child {
+            ElemChild::Elem(e) if e.meta == Some(meta) => Some(plain(&e.children)),
+            _ => None,
+        })
+    };
+    assert_eq!(affix(ElemMeta::CitationPrefix).as_deref(), Some("see "));
+    assert_eq!(affix(ElemMeta::CitationSuffix).as_deref(), Some(", emphasis added"));
+}
+
+#[test]
+fn conference_proceedings() {
+    let options = StyleOptions {
+        tidy_punctuation: true,
+        proceedings_titles: true,
+        ..Default::default()
+    };
+    let bib = render_bibliography_with(
+        "ieee",
+        r#"
+numbered:
+    type: Article
+    title: Fast Parsing of Citations
+    author: Doe, Jane
+    date: 2021
+    page-range: 12-20
+    parent:
+        type: Proceedings
+        publisher: ACM
+        parent:
+            type: Conference
+            title: International Conference on Digital Libraries
+            edition: 5
+            location: Vienna, Austria
+unnumbered:
+    type: Article
+    title: Slow Parsing of Citations
+    author: Roe, Rich
+    date: 2022
+    page-range: 1-9
+    parent:
+        type: Proceedings
+        publisher: IEEE
+        parent:
+            type: Conference
+            title: Workshop on Bibliographies
+"#,
+        &options,
+    );
+
+    assert_eq!(
+        bib,
+        [
+            "J. Doe, “Fast Parsing of Citations,” in Proceedings of the 5th \
+             International Conference on Digital Libraries, Vienna, Austria, ACM, \
+             2021, pp. 12–20.",
+            "R. Roe, “Slow Parsing of Citations,” in Proceedings of the Workshop on \
+             Bibliographies, IEEE, 2022, pp. 1–9.",
+        ]
+    );
+}
+
+#[test]
+fn suppress_author() {
+    let library = from_yaml_str(
+        r#"
+smith:
+    type: Article
+    title: First
+    author: Smith, John
+    date: 2020
+smith-b:
+    type: Article
+    title: Second
+    author: Smith, John
+    date: 2020
+jones:
+    type: Article
+    title: Third
+    author: Jones, Bob
+    date: 2019
+"#,
+    )
+    .unwrap();
+    let locales = locales();
+
+    let document = |style: &str| -> Vec<String> {
+        let style = independent_style(style);
+        let suppressed =
+            |cite: KeyedCite<'static>| KeyedCite { suppress_author: true, ..cite };
+        let page = SpecificLocator::new(Locator::Page, "4");
+        let mut engine = CitationEngine::new(&library, &style, None, &locales);
+        engine.cite(vec![suppressed(KeyedCite::new("smith"))], None).unwrap();
+        engine
+            .cite(vec![suppressed(KeyedCite::with_locator("smith", page))], None)
+            .unwrap();
+        engine
+            .cite(
+                vec![KeyedCite::new("jones"), suppressed(KeyedCite::new("smith"))],
+                None,
+            )
+            .unwrap();
+        engine.cite(vec![KeyedCite::new("smith-b")], None).unwrap();
+        engine.finish().citations.iter().map(|c| plain(&c.citation)).collect()
+    };
+
+    assert_eq!(
+        document("apa"),
+        ["(2020a)", "(2020a, p. 4)", "(Jones, 2019; 2020a)", "(Smith, 2020b)"]
+    );
+    assert_eq!(document("ieee"), ["[1]", "[1, p. 4]", "[1], [2]", "[3]"]);
+}
+
+#[test]
+fn role_label_case() {
+    let render = |name: &str, yaml: &str, role_label_case| {
+        let style = independent_style(name);
+        let options = StyleOptions { role_label_case, ..Default::default() };
+        plain_items(&render_with_options(&style, yaml, &options).bibliography.unwrap())
+    };
+
+    let chapter = r#"
+chapter:
+    type: Chapter
+    title: The chapter
+    author: Doe, Jane
+    date: 2010
+    page-range: 5-10
+    parent:
+        type: Anthology
+        title: The Book
+        editor: Roe, Richard
+        publisher: Penguin
+"#;
+    let book = r#"
+book:
+    type: Book
+    title: The Book
+    author: Doe, Jane
+    editor: Roe, Richard
+    date: 2010
+    publisher: Penguin
+"#;
+
+    assert_eq!(
+        render("apa", chapter, None),
+        ["Doe, J. (2010). The chapter. In R. Roe (Ed.), The Book (pp. 5–10). Penguin."]
+    );
+    assert_eq!(
+        render("apa", chapter, Some(RoleLabelCase::Lowercase)),
+        ["Doe, J. (2010). The chapter. In R. Roe (ed.), The Book (pp. 5–10). Penguin."]
+    );
+    assert_eq!(
+        render("apa", chapter, Some(RoleLabelCase::Capitalized)),
+        ["Doe, J. (2010). The chapter. In R. Roe (Ed.), The Book (pp. 5–10). Penguin."]
+    );
+
+    // A label at the start of a sentence stays capitalized.
+    assert_eq!(
+        render("chicago-author-date", book, Some(RoleLabelCase::Lowercase)),
+        render("chicago-author-date", book, None)
+    );
+    assert!(render("chicago-author-date", book, Some(RoleLabelCase::Lowercase))[0]
+        .contains(". Edited by Richard Roe."));
+}
+
+#[test]
+fn repeated_authors() {
+    let library = from_yaml_str(
+        r#"
+smith-2019:
+    type: Book
+    title: Alpha
+    author: Smith, John
+    date: 2019
+smith-jones:
+    type: Book
+    title: Beta
+    author: ["Smith, John", "Jones, Bob"]
+    date: 2021
+smith-2020:
+    type: Book
+    title: Gamma
+    author: Smith, John
+    date: 2020
+jones-lee:
+    type: Book
+    title: Delta
+    author: ["Smith, John", "Jones, Bob", "Lee, Ann"]
+    date: 2022
+king-cole:
+    type: Book
+    title: Epsilon
+    author: ["Smith, John", "King, Ann", "Cole, Pat"]
+    date: 2023
+"#,
+    )
+    .unwrap();
+    let locales = locales();
+
+    let document = |style: &str, options: StyleOptions| {
+        let style = independent_style(style);
+        let mut engine =
+            CitationEngine::new(&library, &style, None, &locales).with_options(options);
+        let keys: Vec<_> = library.keys().map(KeyedCite::new).collect();
+        engine.cite(keys, None).unwrap();
+        let rendered = engine.finish();
+
+        (
+            plain(&rendered.citations[0].citation),
+            plain_items(&rendered.bibliography.unwrap()),
+        )
+    };
+
+    // The years of the same authors are collapsed, but "Smith et al." with
+    // different co-authors are different authors.
+    let (citation, bibliography) = document("apa", StyleOptions::default());
+    assert_eq!(
+        citation,
+        "(Smith, 2019, 2020; Smith et al., 2022; Smith et al., 2023; Smith & Jones, 2021)"
+    );
+    assert_eq!(bibliography[1], "Smith, J. (2020). Gamma.");
+
+    let options = StyleOptions {
+        repeated_author_substitute: Some("———".into()),
+        ..Default::default()
+    };
+    let (_, bibliography) = document("chicago-author-date", options.clone());
+    assert_eq!(
+        bibliography,
+        [
+            "Smith, John. 2019. Alpha",
+            "———. 2020. Gamma",
+            "Smith, John, and Bob Jones. 2021. Beta",
+            "Smith, John, Bob Jones, and Ann Lee. 2022. Delta",
+            "Smith, John, Ann King, and Pat Cole. 2023. Epsilon",
+        ]
+    );
+
+    let (_, bibliography) = document("modern-language-association", options);
+    assert_eq!(
+        bibliography,
+        [
+            "Smith, John. Alpha. 2019",
+            "———. Gamma. 2020",
+            "Smith, John, and Bob Jones. Beta. 2021",
+            "Smith, John, et al. Delta. 2022",
+            "Smith, John, et al. Epsilon. 2023",
+        ]
+    );
+}
+
+#[test]
+fn edited_web_resources() {
+    let encyclopedia = r#"
+consciousness:
+    type: Entry
+    title: Consciousness
+    date: 2021
+    url: https://plato.stanford.edu/entries/consciousness/
+    parent:
+        type: Reference
+        title: The Stanford Encyclopedia of Philosophy
+        editor: Zalta, Edward N.
+"#;
+
+    // The editor of the encyclopedia takes the place of the author.
+    assert_eq!(
+        render_bibliography("chicago-author-date", encyclopedia),
+        ["Zalta, Edward N., ed. 2021. “Consciousness”. In The Stanford Encyclopedia of Philosophy. https://plato.stanford.edu/entries/consciousness/"]
+    );
+    assert_eq!(
+        render_bibliography("modern-language-association", encyclopedia),
+        ["Zalta, Edward N., editor. “Consciousness.” The Stanford Encyclopedia of Philosophy, 2021, https://plato.stanford.edu/entries/consciousness/"]
+    );
+    assert_eq!(
+        render_bibliography("apa", encyclopedia),
+        ["Consciousness. (2021). In E. N. Zalta (Ed.), The Stanford Encyclopedia of Philosophy. https://plato.stanford.edu/entries/consciousness/"]
+    );
+
+    // Without author and editor, the title takes their place.
+    let untitled = r#"
+page:
+    type: Web
+    title: Only a title
+    url: https://example.com/page
+"#;
+    assert_eq!(
+        render_bibliography("chicago-author-date", untitled),
+        ["“Only a Title.” n.d. https://example.com/page"]
+    );
+    assert_eq!(
+        render_bibliography("modern-language-association", untitled),
+        ["Only a Title. https://example.com/page"]
+    );
+}
+
+#[test]
+fn cited_order_bibliography() {
+    let library = from_yaml_str(
+        r#"
+beta:
+    type: Book
+    title: Beta
+    author: Brown, Bob
+    date: 2002
+gamma:
+    type: Book
+    title: Gamma
+    author: Clark, Cid
+    date: 2003
+alpha:
+    type: Book
+    title: Alpha
+    author: Adams, Ann
+    date: 2001
+delta:
+    type: Book
+    title: Delta
+    author: Davis, Dan
+    date: 2004
+"#,
+    )
+    .unwrap();
+    let locales = locales();
+
+    let document = |style: &str, placement| {
+        let style = independent_style(style);
+        let mut engine = CitationEngine::new(&library, &style, None, &locales)
+            .with_uncited_placement(placement);
+        engine.cite(vec![KeyedCite::new("gamma")], None).unwrap();
+        engine.nocite("alpha").unwrap();
+        engine
+            .cite(vec![KeyedCite::new("delta"), KeyedCite::new("gamma")], None)
+            .unwrap();
+        engine.cite(vec![KeyedCite::new("beta")], None).unwrap();
+        assert_eq!(engine.nocite("missing"), Err(UnknownKeyError("missing".into())));
+        assert_eq!(
+            engine.cited_keys_in_order().collect::<Vec<_>>(),
+            ["gamma", "delta", "beta"]
+        );
+
+        let bibliography = engine.bibliography();
+        let rendered = engine.finish();
+        assert_eq!(rendered.citations.len(), 3);
+        let keys = |bibliography: &RenderedBibliography| {
+            bibliography
+                .items
+                .iter()
+                .map(|item| item.key.clone())
+                .collect::<Vec<_>>()
+        };
+        let keys_before = keys(&bibliography.unwrap());
+        let keys = keys(&rendered.bibliography.unwrap());
+        assert_eq!(keys, keys_before);
+        keys
+    };
+
+    let ieee = ["gamma", "delta", "beta", "alpha"];
+    assert_eq!(document("ieee", UncitedPlacement::Merged), ieee);
+    assert_eq!(document("ieee", UncitedPlacement::End), ieee);
+    // Rendering the same document again gives the same order.
+    assert_eq!(document("ieee", UncitedPlacement::Merged), ieee);
+
+    assert_eq!(
+        document("apa", UncitedPlacement::Merged),
+        ["alpha", "beta", "gamma", "delta"]
+    );
+    assert_eq!(
+        document("apa", UncitedPlacement::End),
+        ["beta", "gamma", "delta", "alpha"]
+    );
+}
+
+#[test]
+fn repeated_container_marker() {
+    let style = independent_style("chicago-author-date");
+    let yaml = r#"
+first:
+    type: Article
+    title: First
+    author: Adams, Ann
+    date: 2001
+    parent:
+        type: Periodical
+        title: Nature
+        volume: 12
+second:
+    type: Article
+    title: Second
+    author: Brown, Bob
+    date: 2002
+    parent:
+        type: Periodical
+        title: Nature
+        volume: 13
+third:
+    type: Article
+    title: Third
+    author: Clark, Cid
+    date: 2003
+    parent:
+        type: Periodical
+        title: Science
+fourth:
+    type: Article
+    title: Fourth
+    author: Davis, Dan
+    date: 2004
+    parent:
+        type: Periodical
+        title: Nature
+"#;
+    let render = |repeated_container_marker: Option<&str>| {
+        let options = StyleOptions {
+            repeated_container_marker: repeated_container_marker.map(Into::into),
+            ..Default::default()
+        };
+        plain_items(&render_with_options(&style, yaml, &options).bibliography.unwrap())
+    };
+
+    assert_eq!(render(None)[1], "Brown, Bob. 2002. “Second”. Nature 13");
+    // Only the second of the consecutive articles in Nature is abbreviated.
+    assert_eq!(
+        render(Some("Ibid.")),
+        [
+            "Adams, Ann. 2001. “First”. Nature 12",
+            "Brown, Bob. 2002. “Second”. Ibid. 13",
+            "Clark, Cid. 2003. “Third”. Science",
+            "Davis, Dan. 2004. “Fourth”. Nature",
+        ]
+    );
+}
+
+#[test]
+fn unused_and_missing_entries() {
+    let library = from_yaml_str(
+        r#"
+cited:
+    type: Book
+    title: Cited
+    author: Brown, Bob
+    date: 2002
+unused:
+    type: Book
+    title: Unused
+    author: Adams, Ann
+    date: 2001
+included:
+    type: Book
+    title: Included
+    author: Clark, Cid
+    date: 2003
+"#,
+    )
+    .unwrap();
+    let locales = locales();
+    let style = independent_style("apa");
+
+    let document = |all: bool| {
+        let mut engine = CitationEngine::new(&library, &style, None, &locales)
+            .with_uncited_entries(all);
+        engine.cite(vec![KeyedCite::new("cited")], None).unwrap();
+        engine.nocite("included").unwrap();
+        assert!(engine
+            .cite(vec![KeyedCite::new("cited"), KeyedCite::new("missing")], None)
+            .is_err());
+        assert!(engine.nocite("gone").is_err());
+
+        assert_eq!(
+            engine.unused_entries().map(|entry| entry.key()).collect::<Vec<_>>(),
+            ["unused"]
+        );
+        assert_eq!(engine.missing_keys().collect::<Vec<_>>(), ["missing", "gone"]);
+
+        engine
+            .finish()
+            .bibliography
+            .unwrap()
+            .items
+            .into_iter()
+            .map(|item| item.key)
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(document(false), ["cited", "included"]);
+    assert_eq!(document(true), ["unused", "cited", "included"]);
+}
+
+#[test]
+fn citation_links() {
+    let library = from_yaml_str(
+        r#"
+zeta:
+    type: Book
+    title: Zeta
+    author: Zimmer, Zoe
+    date: 2001
+alpha:
+    type: Book
+    title: Alpha
+    author: Adams, Ann
+    date: 2002
+beta:
+    type: Book
+    title: Beta
+    author: Brown, Bob
+    date: 2003
+"#,
+    )
+    .unwrap();
+    let locales = locales();
+    let style = independent_style("apa");
+
+    let mut engine = CitationEngine::new(&library, &style, None, &locales);
+    engine
+        .cite_at(vec![KeyedCite::new("zeta"), KeyedCite::new("alpha")], None, "p3")
+        .unwrap();
+    engine.cite_at(vec![KeyedCite::new("zeta")], None, "p3").unwrap();
+    engine.cite(vec![KeyedCite::new("beta")], None).unwrap();
+    engine.cite_at(vec![KeyedCite::new("zeta")], None, "p7").unwrap();
+    engine.nocite("beta").unwrap();
+
+    let (rendered, links) = engine.finish_with_links();
+    assert_eq!(rendered.citations.len(), 4);
+
+    let target =
+        |key: &str, index| CitationTarget { key: key.into(), index: Some(index) };
+    assert_eq!(
+        links.targets,
+        [
+            vec![target("zeta", 2), target("alpha", 0)],
+            vec![target("zeta", 2)],
+            vec![target("beta", 1)],
+            vec![target("zeta", 2)],
+        ]
+    );
+    assert_eq!(
+        links.back_references.into_iter().collect::<Vec<_>>(),
+        [
+            ("zeta".to_string(), vec!["p3".to_string(), "p7".to_string()]),
+            ("alpha".to_string(), vec!["p3".to_string()]),
+        ]
+    );
+}
+
+#[test]
+fn nocite_numbering() {
+    let library = from_yaml_str(
+        r#"
+beta:
+    type: Book
+    title: Beta
+    author: Brown, Bob
+    date: 2002
+gamma:
+    type: Book
+    title: Gamma
+    author: Clark, Cid
+    date: 2003
+alpha:
+    type: Book
+    title: Alpha
+    author: Adams, Ann
+    date: 2001
+delta:
+    type: Book
+    title: Delta
+    author: Davis, Dan
+    date: 2004
+"#,
+    )
+    .unwrap();
+    let locales = locales();
+    let style = independent_style("ieee");
+
+    let document = |all: bool| {
+        let mut engine = CitationEngine::new(&library, &style, None, &locales);
+        engine.nocite("alpha").unwrap();
+        engine.cite(vec![KeyedCite::new("delta")], None).unwrap();
+        engine.cite(vec![KeyedCite::new("gamma")], None).unwrap();
+        if all {
+            engine.nocite_all();
+        }
+
+        let (rendered, links) = engine.finish_with_links();
+        let cites = rendered
+            .citations
+            .iter()
+            .map(|citation| plain(&citation.citation))
+            .collect::<Vec<_>>();
+        assert_eq!(cites, ["[1]", "[2]"]);
+        assert!(links.back_references.is_empty());
+
+        let items = rendered
+            .bibliography
+            .unwrap()
+            .items
+            .into_iter()
+            .map(|item| (plain(&item.first_field.unwrap()), item.key))
+            .collect::<Vec<_>>();
+        (items, links.uncited)
+    };
+
+    let item = |number: &str, key: &str| (number.to_string(), key.to_string());
+    assert_eq!(
+        document(false),
+        (
+            vec![item("[1]", "delta"), item("[2]", "gamma"), item("[3]", "alpha")],
+            vec!["alpha".to_string()]
+        )
+    );
+    assert_eq!(
+        document(true),
+        (
+            vec![
+                item("[1]", "delta"),
+                item("[2]", "gamma"),
+                item("[3]", "alpha"),
+                item("[4]", "beta"),
+            ],
+            vec!["alpha".to_string(), "beta".to_string()]
+        )
+    );
+}
+
+#[test]
+fn authorless_citations() {
+    let Style::Independent(apa) =
+        style_by_name("american-psychological-association").unwrap()
+    else {
+        panic!("APA is an independent style")
+    };
+    let yaml = r#"
+book:
+    type: Book
+    title: "The Great Book: A Study of Greatness"
+    date: 2020
+article:
+    type: Article
+    title: { value: Effects of Things on Other Things, short: Effects of Things }
+    date: 2021
+    parent:
+        type: Periodical
+        title: Nature
+"#;
+
+    let citations = |options: &StyleOptions| -> Vec<String> {
+        render_with_options(&apa, yaml, options)
+            .citations
+            .into_iter()
+            .map(|citation| render_html(&citation.citation))
+            .collect()
+    };
+
+    // A standalone work is italicized and a contained work is quoted. The
+    // comma moves into the quotes.
+    assert_eq!(
+        citations(&StyleOptions::default()),
+        [
+            "(<span style=\"font-style: italic;\">The Great Book: A Study of Greatness</span>, 2020)",
+            "(“Effects of Things,” 2021)",
+        ]
+    );
+    assert_eq!(
+        citations(&StyleOptions { derive_short_titles: true, ..Default::default() }),
+        [
+            "(<span style=\"font-style: italic;\">The Great Book</span>, 2020)",
+            "(“Effects of Things,” 2021)",
+        ]
+    );
+}
+
+#[test]
+fn note_stream() {
+    let library = from_yaml_str(
+        r#"
+smith:
+    type: Book
+    title: The First Book
+    author: Smith, John
+    date: 2020
+    publisher: Penguin
+    location: New York
+doe:
+    type: Book
+    title: The Second Book
+    author: Doe, Jane
+    date: 2019
+    publisher: Knopf
+    location: Boston
+"#,
+    )
+    .unwrap();
+    let locales = locales();
+    let style = independent_style("chicago-fullnotes");
+
+    let cite = |key: &str, page: Option<&'static str>| {
+        let locator =
+            page.map(|page| SpecificLocator(Locator::Page, LocatorPayload::Str(page)));
+        CitationRequest::new(
+            vec![CitationItem::new(
+                library.get(key).unwrap(),
+                locator,
+                None,
+                false,
+                None,
+            )],
+            &style,
+            None,
+            &locales,
+            None,
+        )
+    };
+
+    let notes = [
+        vec![cite("smith", Some("45"))],
+        vec![cite("smith", Some("45"))],
+        vec![cite("smith", Some("52"))],
+        vec![],
+        vec![cite("doe", None)],
+        vec![cite("smith", Some("3")), cite("doe", Some("8"))],
+        vec![cite("doe", Some("8"))],
+        vec![cite("smith", None)],
+        vec![cite("doe", None), cite("smith", None)],
+        vec![cite("doe", Some("7"))],
+    ];
+
+    let mut stream = NoteStream::new();
+    let mut rendered = vec![];
+    for (i, citations) in notes.iter().enumerate() {
+        let note = stream.push_note(citations);
+        assert_eq!(note.number, i + 1);
+
+        let marker = render_html(&note.marker());
+        assert_eq!(
+            marker,
+            format!("<span style=\"vertical-align: super;\">{}</span>", i + 1)
+        );
+
+        let texts: Vec<_> = note.citations.iter().map(plain).collect();
+        rendered.push(texts.join(" "));
+    }
+
+    assert_eq!(stream.len(), 10);
+    // "Ibid." does not refer to a note with several citations.
+    assert_eq!(
+        rendered,
+        [
+            "John Smith, The First Book (New York: Penguin, 2020), 45.",
+            "Smith, 45.",
+            "Smith, 52.",
+            "",
+            "Jane Doe, The Second Book (Boston: Knopf, 2019).",
+            "Smith, The First Book, 3. Doe, The Second Book, 8.",
+            "Doe, The Second Book, 8.",
+            "Smith, The First Book.",
+            "Doe, The Second Book. Smith, The First Book.",
+            "Doe, The Second Book, 7.",
+        ]
+    );
+
+    let bibliography = stream
+        .finish(BibliographyRequest::new(&style, None, &locales))
+        .unwrap()
+        .items
+        .into_iter()
+        .map(|item| item.key)
+        .collect::<Vec<_>>();
+    assert_eq!(bibliography, ["doe", "smith"]);
+}
+
+#[test]
+fn citation_signals() {
+    let library = from_yaml_str(
+        r#"
+smith:
+    type: Book
+    title: The First Book
+    author: Smith, John
+    date: 2020
+    publisher: Penguin
+    location: New York
+jones:
+    type: Book
+    title: The Second Book
+    author: Jones, Jane
+    date: 2019
+    publisher: Knopf
+    location: Boston
+adams:
+    type: Book
+    title: The Third Book
+    author: Adams, Ann
+    date: 2018
+    publisher: Norton
+    location: London
+"#,
+    )
+    .unwrap();
+    let locales = locales();
+
+    let render_in =
+        |locale: Option<&str>, style: &str, options: &StyleOptions, format| {
+            let style = independent_style(style);
+            let locale = locale.map(|l| LocaleCode(l.into()));
+            let mut engine = CitationEngine::new(&library, &style, locale, &locales)
+                .with_options(options.clone());
+            let cite = |key, signal| KeyedCite { signal, ..KeyedCite::new(key) };
+            engine
+                .cite(
+                    vec![
+                        cite("jones", Some(Signal::Cf)),
+                        cite("smith", Some(Signal::See)),
+                        cite("adams", Some(Signal::See)),
+                    ],
+                    Some(1),
+                )
+                .unwrap();
+
+            let mut buf = String::new();
+            engine.finish().citations[0]
+                .citation
+                .write_buf(&mut buf, format)
+                .unwrap();
+            buf
+        };
+    let render = |style: &str, options: &StyleOptions, format| {
+        render_in(None, style, options, format)
+    };
+
+    // Items are grouped by their signal, which is printed once per group.
+    assert_eq!(
+        render("chicago-author-date", &StyleOptions::default(), BufWriteFormat::Plain),
+        "(see Smith 2020; Adams 2018; cf. Jones 2019)"
+    );
+    // A signal at the start of a note is capitalized.
+    assert_eq!(
+        render("chicago-fullnotes", &StyleOptions::default(), BufWriteFormat::Plain),
+        "See John Smith, The First Book (New York: Penguin, 2020); Ann Adams, The \
+         Third Book (London: Norton, 2018); cf. Jane Jones, The Second Book (Boston: \
+         Knopf, 2019)."
+    );
+    // Signals can be italicized, as OSCOLA does with "cf.".
+    let italic_cf = StyleOptions {
+        italic_signals: vec![Signal::Cf],
+        ..Default::default()
+    };
+    let html = render("chicago-fullnotes", &italic_cf, BufWriteFormat::Html);
+    assert!(html.starts_with("See John Smith"));
+    assert!(html.contains("; <span style=\"font-style: italic;\">cf.</span> Jane Jones"));
+    // The signals follow the language of the locale.
+    assert_eq!(
+        render_in(
+            Some("de-DE"),
+            "chicago-author-date",
+            &StyleOptions::default(),
+            BufWriteFormat::Plain
+        ),
+        "(siehe Smith 2020; Adams 2018; vgl. Jones 2019)"
+    );
+}
+
+#[test]
+fn canonical_role_order() {
+    let style = IndependentStyle::from_xml(
+        r#"<style xmlns="http://purl.org/net/xbiblio/csl" class="in-text" version="1.0">
+            <info>
+                <title>Roles</title>
+                <id>roles</id>
+                <updated>2023-01-01T00:00:00+00:00</updated>
+            </info>
+            <citation>
+                <layout><text variable="title"/></layout>
+            </citation>
+            <bibliography>
+                <layout>
+                    <names variable="organizer illustrator translator editor" delimiter="; ">
+                        <name/>
+                        <label form="short" prefix=", "/>
+                    </names>
+                </layout>
+            </bibliography>
+        </style>"#,
+    )
+    .unwrap();
+
+    let yaml = r#"
+book:
+    type: book
+    title: A Book
+    editor: ["Edit, Ed"]
+    affiliated:
+        - role: illustrator
+          names: ["Ill, Ida"]
+        - role: organizer
+          names: ["Org, Otto"]
+        - role: translator
+          names: ["Trans, Tom"]
+"#;
+
+    let render = |options: &StyleOptions| {
+        let rendered = render_with_options(&style, yaml, options);
+        plain(&rendered.bibliography.unwrap().items[0].content)
+    };
+
+    assert_eq!(
+        render(&StyleOptions::default()),
+        "Otto Org, org.; Ida Ill, ill.; Tom Trans, tran.; Ed Edit, ed."
+    );
+    assert_eq!(
+        render(&StyleOptions { canonical_role_order: true, ..Default::default() }),
+        "Ed Edit, ed.; Tom Trans, tran.; Ida Ill, ill.; Otto Org, org."
+    );
+
+    // The style's delimiter separates the roles.
+    let apa = independent_style("apa");
+    let rendered = render_with_options(
+        &apa,
+        r#"
+book:
+    type: book
+    title: A Book
+    author: ["Adams, Alice"]
+    date: 2001
+    publisher: Penguin
+    editor: ["Edit, Ed"]
+    affiliated:
+        - role: translator
+          names: ["Trans, Tom"]
+"#,
+        &StyleOptions::default(),
+    );
+    let buf = plain(&rendered.bibliography.unwrap().items[0].content);
+    assert_eq!(
+        buf,
+        "Adams, A. (2001). A Book (E. Edit, Ed.; T. Trans, Trans.). Penguin."
+    );
+}
+
+#[test]
+fn unpublished_articles() {
+    let apa = independent_style("apa");
+    let rendered = render_with_options(
+        &apa,
+        r#"
+online:
+    type: article
+    title: A new finding
+    author: ["Smith, Jane"]
+    date: 2023
+    status: advance-online
+    serial-number:
+        doi: 10.1000/xyz
+    parent:
+        type: periodical
+        title: Journal of Findings
+        volume: 12
+        issue: 3
+pressed:
+    type: article
+    title: A pending finding
+    author: ["Jones, Tom"]
+    status: in-press
+    parent:
+        type: periodical
+        title: Journal of Findings
+"#,
+        &StyleOptions::default(),
+    );
+
+    let items = plain_items(&rendered.bibliography.unwrap());
+    assert_eq!(
+        items,
+        [
+            "Jones, T. (in press). A pending finding. Journal of Findings.",
+            "Smith, J. (2023). A new finding. Journal of Findings. Advance online \
+             publication. https://doi.org/10.1000/xyz",
+        ]
+    );
+}
+
+#[test]
+fn submitted_manuscripts() {
+    let apa = independent_style("apa");
+    let rendered = render_with_options(
+        &apa,
+        r#"
+submitted:
+    type: manuscript
+    title: The first study
+    author: ["Doe, Jane"]
+    date: 2020
+    status: submitted
+    organization: Department of Psychology, University of Nowhere
+preprint:
+    type: manuscript
+    title: The second study
+    author: ["Roe, Rick"]
+    date: 2021
+    status: submitted
+    organization: Department of Psychology, University of Nowhere
+    archive: PsyArXiv
+    url: https://psyarxiv.com/abcd
+"#,
+        &StyleOptions::default(),
+    );
+
+    let items = plain_items(&rendered.bibliography.unwrap());
+
+    // The department is replaced by the archive of a preprint.
+    assert_eq!(
+        items,
+        [
+            "Doe, J. (2020). The first study [Manuscript submitted for publication]. \
+             Department of Psychology, University of Nowhere.",
+            "Roe, R. (2021). The second study [Manuscript submitted for publication]. \
+             PsyArXiv. https://psyarxiv.com/abcd",
+        ]
+    );
+}
+
+#[test]
+fn sorting_large_bibliography() {
+    let apa = independent_style("apa");
+
+    // Entries in a scrambled order whose authors sort by their family name
+    // and then by year.
+    let mut yaml = String::new();
+    let mut expected = vec![];
+    let mut seed: u32 = 7;
+    for i in 0..300 {
+        seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+        let family: String = (0..3)
+            .map(|k| (b'a' + ((seed >> (8 + 5 * k)) % 26) as u8) as char)
+            .collect();
+        let family = format!("{}{}", family[..1].to_uppercase(), &family[1..]);
+        let year = 1950 + (seed >> 24) % 70;
+        yaml.push_str(&format!(
+            "e{i}:\n    type: Book\n    title: Book {i}\n    author: {family}, Ann\n    date: {year}\n"
+        ));
+        expected.push((family, year, i));
+    }
+    expected.sort_by(|a, b| {
+        (&a.0, a.1)
+            .cmp(&(&b.0, b.1))
+            .then_with(|| format!("book {}", a.2).cmp(&format!("book {}", b.2)))
+    });
+
+    let rendered = render_with_options(&apa, &yaml, &StyleOptions::default());
+    let keys = rendered
+        .bibliography
+        .unwrap()
+        .items
+        .into_iter()
+        .map(|item| item.key)
+        .collect::<Vec<_>>();
+    let expected = expected
+        .into_iter()
+        .map(|(_, _, i)| format!("e{i}"))
+        .collect::<Vec<_>>();
+    assert_eq!(keys, expected);
+}
+
+#[test]
+fn dictionary_entries() {
+    let bib = render_bibliography(
+        "apa",
+        r#"
+heuristic:
+    type: Entry
+    title: Heuristic
+    date: 2018
+    parent:
+        type: Reference
+        title: APA dictionary of psychology
+        editor: VandenBos, Gary
+        publisher: American Psychological Association
+        edition: 2
+serendipity:
+    type: Entry
+    title: Serendipity
+    parent:
+        type: Reference
+        title: Merriam-Webster.com dictionary
+        publisher: Merriam-Webster
+        url:
+            value: https://www.merriam-webster.com/dictionary/serendipity
+            date: 2023-05-14
+"#,
+    );
+
+    assert_eq!(
+        bib,
+        [
+            "Heuristic. (2018). In G. VandenBos (Ed.), APA dictionary of psychology \
+             (2nd ed.). American Psychological Association.",
+            "Serendipity. (n.d.). In Merriam-Webster.com dictionary. Merriam-Webster. \
+             Retrieved May 14, 2023, from \
+             https://www.merriam-webster.com/dictionary/serendipity",
+        ]
+    );
+}
+
+#[test]
+fn name_order() {
+    let style = independent_style("chicago-author-date");
+    let yaml = r#"
+a:
+    type: book
+    title: A Book
+    author: ["Smith, John", "Jones, Tom"]
+    editor: ["Edit, Ed"]
+    date: 2001
+    publisher: Penguin
+b:
+    type: book
+    title: B Book
+    author: ["Adams, Zed"]
+    date: 2002
+"#;
+
+    let render = |name_order: Option<NameOrder>| {
+        let options = StyleOptions { name_order, ..Default::default() };
+        plain_items(&render_with_options(&style, yaml, &options).bibliography.unwrap())
+    };
+
+    assert_eq!(
+        render(None),
+        [
+            "Adams, Zed. 2002. B Book",
+            "Smith, John, and Tom Jones. 2001. A Book. Edited by Ed Edit. Penguin"
+        ]
+    );
+    assert_eq!(render(Some(NameOrder::FirstFamilyFirst)), render(None));
+
+    // The bibliography stays sorted by family name and the editors keep their
+    // order.
+    assert_eq!(
+        render(Some(NameOrder::FamilyFirst)),
+        [
+            "Adams, Zed. 2002. B Book",
+            "Smith, John, and Jones, Tom. 2001. A Book. Edited by Ed Edit. Penguin"
+        ]
+    );
+    assert_eq!(
+        render(Some(NameOrder::GivenFirst)),
+        [
+            "Zed Adams. 2002. B Book",
+            "John Smith and Tom Jones. 2001. A Book. Edited by Ed Edit. Penguin"
+        ]
+    );
+
+    // APA inverts all names, so only inverting the first one changes it.
+    let apa = independent_style("apa");
+    let options = StyleOptions {
+        name_order: Some(NameOrder::FirstFamilyFirst),
+        ..Default::default()
+    };
+    let rendered = render_with_options(&apa, yaml, &options);
+    let bib = plain(&rendered.bibliography.unwrap().items[1].content);
+    assert!(bib.starts_with("Smith, J., & T. Jones. (2001)."), "{bib}");
+}
+
+#[test]
+fn reproduction_note() {
+    let library = from_yaml_str(
+        r#"
+book:
+    type: book
+    title: The Structure of Scientific Revolutions
+    author: ["Kuhn, Thomas S."]
+    date: 1962
+    publisher: University of Chicago Press
+article:
+    type: article
+    title: A study of things
+    author: ["Smith, John", "Jones, Tom", "Adams, Zed"]
+    date: 2019
+    serial-number:
+        doi: 10.1000/xyz
+    parent:
+        type: periodical
+        title: Journal of Things
+        volume: 3
+        issue: 2
+"#,
+    )
+    .unwrap();
+    let locales = locales();
+    let page = Some(SpecificLocator(Locator::Page, LocatorPayload::Str("45")));
+
+    let mut note = ReproductionNote::new(library.get("book").unwrap());
+    note.adapted = true;
+    note.locator = page;
+    note.copyright_holder = Some("University of Chicago Press");
+    let rendered = note.render(&locales);
+    assert_eq!(
+        format!("{rendered:#}"),
+        "Note. Adapted from The Structure of Scientific Revolutions (p. 45), by T. S. \
+         Kuhn, 1962, University of Chicago Press. Copyright 1962 by University of \
+         Chicago Press."
+    );
+    let ElemChild::Text(label) = &rendered.0[0] else { panic!("note has no label") };
+    assert_eq!(label.formatting.font_style, FontStyle::Italic);
+
+    let mut note = ReproductionNote::new(library.get("article").unwrap());
+    note.locator = page;
+    assert_eq!(
+        plain(&note.render(&locales)),
+        "Note. Reprinted from “A study of things,” by J. Smith, T. Jones, and Z. \
+         Adams, 2019, Journal of Things, 3(2), p. 45 (https://doi.org/10.1000/xyz)."
+    );
+
+    // The words of the note follow the language of the locale.
+    note.locale = Some(LocaleCode("de-DE".into()));
+    note.copyright_holder = Some("Journal of Things");
+    assert_eq!(
+        plain(&note.render(&locales)),
+        "Anmerkung. Nachdruck aus „A study of things“, von J. Smith, T. Jones, und Z. \
+         Adams, 2019, Journal of Things, 3(2), S. 45 (https://doi.org/10.1000/xyz). \
+         Copyright 2019 von Journal of Things."
+    );
+}
+
+#[test]
+#[cfg(feature = "rayon")]
+fn parallel_bibliography() {
+    let apa = independent_style("apa");
+
+    // Authors with several works in a year need year suffixes.
+    let mut yaml = String::new();
+    for i in 0..500 {
+        yaml.push_str(&format!(
+            "e{i}:\n    type: Book\n    title: Book {i}\n    author: Author{}, Ann\n    date: {}\n",
+            i % 50,
+            2000 + i % 3,
+        ));
+    }
+    let library = from_yaml_str(&yaml).unwrap();
+    let locales = locales();
+
+    let render = |parallel: bool| {
+        let mut driver = BibliographyDriver::new();
+        for entry in library.iter() {
+            driver.citation(CitationRequest::from_items(
+                vec![CitationItem::with_entry(entry)],
+                &apa,
+                &locales,
+            ));
+        }
+        let request = BibliographyRequest::new(&apa, None, &locales);
+        let rendered =
+            if parallel { driver.finish_par(request) } else { driver.finish(request) };
+        let bibliography = rendered.bibliography.unwrap();
+        rendered
+            .citations
+            .iter()
+            .map(|c| plain(&c.citation))
+            .chain(
+                bibliography
+                    .items
+                    .iter()
+                    .map(|i| format!("{} {:#}", i.key, i.content)),
+            )
+            .collect::<Vec<_>>()
+    };
+
+    let serial = render(false);
+    assert!(serial.iter().any(|item| item.contains("(2001b)")));
+    assert_eq!(render(true), serial);
+}
+
+#[test]
+fn same_surname_authors() {
+    let apa = independent_style("apa");
+    let yaml = r#"
+bob:
+    type: book
+    title: Zeta
+    author: ["Smith, Bob"]
+    date: 2001
+alice:
+    type: book
+    title: Yotta
+    author: ["Smith, Alice"]
+    date: 2021
+initial:
+    type: book
+    title: Xylo
+    author: ["Smith, A."]
+    date: 2010
+albert:
+    type: book
+    title: Vat
+    author: ["Smith, Albert"]
+    date: 2005
+"#;
+
+    let render = |options: &StyleOptions| {
+        plain_items(&render_with_options(&apa, yaml, options).bibliography.unwrap())
+    };
+
+    // The full given names decide, and an initial precedes a given name.
+    assert_eq!(
+        render(&StyleOptions::default()),
+        [
+            "Smith, A. (2010). Xylo.",
+            "Smith, A. (2005). Vat.",
+            "Smith, A. (2021). Yotta.",
+            "Smith, B. (2001). Zeta.",
+        ]
+    );
+
+    // Authors that look the same are ordered by year, but not merged.
+    assert_eq!(
+        render(&StyleOptions {
+            sort_by_initials: true,
+            repeated_author_substitute: Some("———".into()),
+            ..Default::default()
+        }),
+        [
+            "Smith, A. (2005). Vat.",
+            "Smith, A. (2010). Xylo.",
+            "Smith, A. (2021). Yotta.",
+            "Smith, B. (2001). Zeta.",
+        ]
+    );
+}
+
+#[test]
+fn apa_signal_phrases() {
+    let library = from_yaml_str(
+        r#"
+smith:
+    type: Book
+    title: The First Book
+    author: Smith, John
+    date: 2020
+    publisher: Penguin
+jones:
+    type: Book
+    title: The Second Book
+    author: Jones, Jane
+    date: 2019
+    publisher: Knopf
+adams:
+    type: Book
+    title: The Third Book
+    author: Adams, Ann
+    date: 2018
+    publisher: Norton
+"#,
+    )
+    .unwrap();
+    let locales = locales();
+    let style = independent_style("apa");
+
+    let render = |cites: Vec<KeyedCite>| {
+        let mut engine = CitationEngine::new(&library, &style, None, &locales);
+        engine.cite(cites, None).unwrap();
+        plain(&engine.finish().citations[0].citation)
+    };
+    let cite = |key, signal| KeyedCite::new(key).with_signal(signal);
+
+    assert_eq!(
+        render(vec![cite("smith", Signal::Eg), cite("jones", Signal::Eg)]),
+        "(e.g., Jones, 2019; Smith, 2020)"
+    );
+    // Signals group the cites before the sort of the style.
+    assert_eq!(
+        render(vec![
+            cite("jones", Signal::SeeAlso),
+            cite("smith", Signal::Eg),
+            cite("adams", Signal::SeeAlso),
+        ]),
+        "(e.g., Smith, 2020; see also Adams, 2018; Jones, 2019)"
+    );
+
+    // The locator stays with its cite.
+    let located = KeyedCite::with_locator(
+        "smith",
+        SpecificLocator(Locator::Page, LocatorPayload::Str("4")),
+    )
+    .with_signal(Signal::Eg);
+    assert_eq!(
+        render(vec![located, cite("jones", Signal::Eg)]),
+        "(e.g., Jones, 2019; Smith, 2020, p. 4)"
+    );
+}
+
+#[test]
+fn theatrical_performance() {
+    let yaml = r#"
+hamlet:
+    type: performance
+    title: Hamlet
+    genre: Theatrical performance
+    affiliated:
+        - role: director
+          names: ["Doran, Gregory"]
+    date: 2008-07-24
+    publisher: Courtyard Theatre
+    location: Stratford-upon-Avon, United Kingdom
+cats:
+    type: performance
+    title: Cats
+    genre: Theatrical performance
+    date: 2020-01-03
+    publisher: London Palladium
+"#;
+
+    // The director takes the place of the author, and the title moves up if
+    // no director is named.
+    let tidy = StyleOptions { tidy_punctuation: true, ..Default::default() };
+    assert_eq!(
+        render_bibliography_with("apa", yaml, &tidy),
+        [
+            "Cats. (2020). [Theatrical performance]. London Palladium.",
+            "Doran, G. (Director). (2008). Hamlet [Theatrical performance]. \
+             Courtyard Theatre.",
+        ]
+    );
+}
+
+#[test]
+fn tidy_punctuation() {
+    let yaml = r#"
+wait:
+    type: Book
+    title: Wait . . . what happened
+    author: Doe, Jane
+    date: 2020
+    publisher: Penguin
+kept:
+    type: Book
+    title: "{Ideas..}"
+    author: Roe, Rich
+    date: 2021
+    publisher: Penguin
+"#;
+
+    let tidy = StyleOptions { tidy_punctuation: true, ..Default::default() };
+    // Spaced ellipses and verbatim titles are left alone.
+    assert_eq!(
+        render_bibliography_with("apa", yaml, &tidy),
+        [
+            "Doe, J. (2020). Wait . . . what happened. Penguin.",
+            "Roe, R. (2021). Ideas.. Penguin.",
+        ]
+    );
+}
+
+#[test]
+fn streamed_bibliography() {
+    let library = from_yaml_str(
+        r#"
+first:
+    type: Book
+    title: The First Book
+    author: Smith, John
+    date: 2020
+    publisher: Penguin
+second:
+    type: Book
+    title: The Second Book
+    author: Smith, John
+    date: 2019
+    publisher: Knopf
+third:
+    type: Article
+    title: An Article
+    author: Møller, Anna
+    date: 2018
+    parent:
+        type: Periodical
+        title: Journal of Studies
+"#,
+    )
+    .unwrap();
+    let locales = locales();
+    let options = StyleOptions {
+        repeated_author_substitute: Some("———".into()),
+        ascii_only: true,
+        ..Default::default()
+    };
+
+    for name in ["apa", "ieee"] {
+        let style = independent_style(name);
+        let driver = || {
+            let mut driver = BibliographyDriver::new();
+            for entry in library.iter() {
+                driver.citation(CitationRequest::from_items(
+                    vec![CitationItem::with_entry(entry)],
+                    &style,
+                    &locales,
+                ));
+            }
+            driver
+        };
+        let request = || {
+            BibliographyRequest::new(&style, None, &locales).with_options(options.clone())
+        };
+
+        let batch = driver().finish(request());
+        let streamed = driver().finish_stream(request());
+        let texts = |citations: &[RenderedCitation]| {
+            citations.iter().map(|c| plain(&c.citation)).collect::<Vec<_>>()
+        };
+        assert_eq!(texts(&streamed.citations), texts(&batch.citations));
+
+        let mut stream = streamed.bibliography.unwrap();
+        let mut out = Vec::new();
+        stream.write_to(&mut out, BufWriteFormat::Html).unwrap();
+        assert_eq!(stream.non_ascii(), batch.non_ascii);
+
+        let expected: String = batch
+            .bibliography
+            .unwrap()
+            .items
+            .iter()
+            .map(|item| match &item.first_field {
+                Some(first_field) => format!(
+                    "{} {}\n",
+                    render_html(&ElemChildren(vec![first_field.clone()])),
+                    render_html(&item.content)
+                ),
+                None => render_html(&item.content) + "\n",
+            })
+            .collect();
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+    }
+}
+
+#[test]
+fn timestamp_locators() {
+    let library = from_yaml_str(
+        r#"
+video:
+    type: Video
+    title: How to Cook Rice
+    author: Smith, John
+    date: 2020
+    url: https://www.youtube.com/watch?v=abc
+"#,
+    )
+    .unwrap();
+    let locales = locales();
+    let style = independent_style("apa");
+
+    let mut engine = CitationEngine::new(&library, &style, None, &locales);
+    for timestamp in ["4:32", "4:32-5:10", "4:32 – 5:10"] {
+        let locator = SpecificLocator::new(Locator::Timestamp, timestamp);
+        engine
+            .cite(vec![KeyedCite::with_locator("video", locator)], None)
+            .unwrap();
+    }
+    let citations: Vec<_> =
+        engine.finish().citations.iter().map(|c| plain(&c.citation)).collect();
+
+    // The ends of a range are joined by an en dash.
+    assert_eq!(
+        citations,
+        ["(Smith, 2020, 4:32)", "(Smith, 2020, 4:32–5:10)", "(Smith, 2020, 4:32–5:10)"]
+    );
+}
+
+#[test]
+fn timestamp_range_delimiter() {
+    let library = from_yaml_str(
+        r#"
+video:
+    type: Video
+    title: How to Cook Rice
+    date: 2020
+"#,
+    )
+    .unwrap();
+    let style = IndependentStyle::from_xml(
+        r#"<style xmlns="http://purl.org/net/xbiblio/csl" class="in-text" version="1.0">
+            <info>
+                <title>Timestamps</title>
+                <id>timestamps</id>
+                <updated>2023-01-01T00:00:00+00:00</updated>
+            </info>
+            <locale>
+                <terms>
+                    <term name="page-range-delimiter">/</term>
+                </terms>
+            </locale>
+            <citation>
+                <layout><text variable="locator"/></layout>
+            </citation>
+        </style>"#,
+    )
+    .unwrap();
+    let locales = locales();
+
+    let mut engine = CitationEngine::new(&library, &style, None, &locales);
+    let locator = SpecificLocator::new(Locator::Timestamp, "4:32-5:10");
+    engine
+        .cite(vec![KeyedCite::with_locator("video", locator)], None)
+        .unwrap();
+
+    // The locale's range delimiter joins the ends of the range.
+    assert_eq!(plain(&engine.finish().citations[0].citation), "4:32/5:10");
+}
+
+#[test]
+fn report_and_patent_numbers() {
+    let yaml = r#"
+report:
+    type: Report
+    title: Water Quality
+    author: Doe, Jane
+    date: 2020
+    genre: Technical report
+    serial-number: 123-4
+    organization: EPA
+patent:
+    type: Patent
+    title: Widget
+    author: Roe, Rick
+    date: 2019
+    serial-number: 5,678,901
+    organization: USPTO
+anonymous:
+    type: Report
+    title: Logician
+    serial-number: n10269785
+"#;
+
+    let style = independent_style("apa");
+
+    let locales = locales();
+    let lib = from_yaml_str(yaml).unwrap();
+    let render = |key: &str, locale: &str| {
+        let locale = Some(LocaleCode(locale.into()));
+        let mut driver: BibliographyDriver<'_, Entry> = BibliographyDriver::new();
+        driver.citation(CitationRequest::new(
+            vec![CitationItem::with_entry(lib.get(key).unwrap())],
+            &style,
+            locale.clone(),
+            &locales,
+            None,
+        ));
+
+        let rendered = driver.finish(BibliographyRequest::new(&style, locale, &locales));
+        plain(&rendered.bibliography.unwrap().items[0].content)
+    };
+
+    // Report numbers follow the genre, patent numbers the patent term. Both
+    // keep their hyphens and commas.
+    assert_eq!(
+        render("report", "en-US"),
+        "Doe, J. (2020). Water Quality (Technical Report No. 123-4)."
+    );
+    assert_eq!(
+        render("report", "de-DE"),
+        "Doe, J. (2020). Water Quality (Technical report Nr. 123-4)."
+    );
+    assert_eq!(
+        render("patent", "en-US"),
+        "Roe, R. (2019). Widget (USPTO Patent No. 5,678,901)."
+    );
+    assert_eq!(
+        render("patent", "de-DE"),
+        "Roe, R. (2019). Widget (USPTO Patent Nr. 5,678,901)."
+    );
+
+    // Without an author, the title and its number substitute for it. The
+    // label does not use up the number.
+    assert_eq!(render("anonymous", "en-US"), "Logician (No. n10269785). (n.d.).");
+}
+
+#[test]
+fn continued_pages() {
+    let yaml = r#"
+cars:
+    type: Article
+    title: The Future of Cars
+    author: Doe, Jane
+    date: 2020-05
+    page-range: 45+
+    parent:
+        type: Periodical
+        title: Time
+        volume: 195
+        issue: 18
+roads:
+    type: Article
+    title: Roads
+    author: Roe, Rick
+    date: 2021-05
+    page-range: 45+, 50-52
+    parent:
+        type: Periodical
+        title: Time
+        volume: 196
+        issue: 2
+"#;
+
+    assert_eq!(
+        render_bibliography("apa", yaml),
+        [
+            "Doe, J. (2020). The Future of Cars. Time, 195(18), 45+.",
+            "Roe, R. (2021). Roads. Time, 196(2), 45+, 50–52.",
+        ]
+    );
+    assert_eq!(
+        render_bibliography("ieee", yaml),
+        [
+            "J. Doe, “The Future of Cars,” Time, vol. 195, no. 18, p. 45+, May 2020.",
+            "R. Roe, “Roads,” Time, vol. 196, no. 2, pp. 45+, 50–52, May 2021.",
+        ]
+    );
+}
+
+#[test]
+fn incremental_updates() {
+    let library = from_yaml_str(
+        r#"
+first:
+    type: article
+    title: Glaciers of the Alps
+    author: Keller, Anna
+    date: 2020
+second:
+    type: article
+    title: Rivers of the Alps
+    author: Keller, Anna
+    date: 2020
+third:
+    type: book
+    title: Lakes
+    author: Brunner, Beat
+    date: 2018
+"#,
+    )
+    .unwrap();
+    let locales = locales();
+    let style = independent_style("apa");
+
+    let updated = |key: &str, edit: &dyn Fn(&mut Entry)| {
+        let mut entry = library.get(key).unwrap().clone();
+        edit(&mut entry);
+        entry
+    };
+    let citation = |engine: &IncrementalEngine, i: usize| {
+        plain(&engine.rendered().citations[i].citation)
+    };
+
+    let mut engine = IncrementalEngine::new(library.clone(), &style, None, &locales);
+    assert_eq!(citation(&engine, 0), "(Keller, 2020a)");
+
+    // An unrelated edit only changes the edited entry.
+    let third = updated("third", &|e| e.set_title("Mountain Lakes".to_string().into()));
+    assert_eq!(engine.update_entry("third", third).unwrap(), ["third"]);
+
+    // Without a second work of the author, the year suffixes disappear.
+    let second = updated("second", &|e| {
+        e.set_authors(vec![
+            hayagriva::types::Person::from_strings(vec!["Huber", "Lea"]).unwrap()
+        ])
+    });
+    assert_eq!(engine.update_entry("second", second).unwrap(), ["first", "second"]);
+    assert_eq!(citation(&engine, 0), "(Keller, 2020)");
+    assert_eq!(citation(&engine, 1), "(Huber, 2020)");
+
+    // The result matches a complete render of the updated library.
+    let fresh = IncrementalEngine::new(engine.library().clone(), &style, None, &locales);
+    let bibliography = |engine: &IncrementalEngine| -> Vec<String> {
+        let items = &engine.rendered().bibliography.as_ref().unwrap().items;
+        items.iter().map(|item| plain(&item.content)).collect()
+    };
+    assert_eq!(bibliography(&engine), bibliography(&fresh));
+    for i in 0..3 {
+        assert_eq!(citation(&engine, i), citation(&fresh, i));
+    }
+
+    assert_eq!(
+        engine.update_entry("fourth", library.get("third").unwrap().clone()),
+        Err(UnknownKeyError("fourth".to_string()))
+    );
+
+    // Numbers in the order of citation do not change with the content.
+    let style = independent_style("ieee");
+    let mut engine = IncrementalEngine::new(library.clone(), &style, None, &locales);
+    let first = updated("first", &|e| e.set_title("Ice".to_string().into()));
+    assert_eq!(engine.update_entry("first", first).unwrap(), ["first"]);
+    assert_eq!(citation(&engine, 2), "[3]");
+}
+
+#[test]
+fn sentence_case_exceptions() {
+    let style = IndependentStyle::from_xml(
+        r#"<style xmlns="http://purl.org/net/xbiblio/csl" class="in-text" version="1.0">
+            <info>
+                <title>Sentence case</title>
+                <id>sentence-case</id>
+                <updated>2023-01-01T00:00:00+00:00</updated>
+            </info>
+            <citation>
+                <layout><text variable="title"/></layout>
+            </citation>
+            <bibliography>
+                <layout>
+                    <text variable="title" text-case="sentence"/>
+                </layout>
+            </bibliography>
+        </style>"#,
+    )
+    .unwrap();
+    let yaml = r#"
+collider:
+    type: article
+    title: Results From the Large Hadron Collider on the iPhone
+"#;
+
+    let render = |options: &StyleOptions| {
+        let bib = render_with_options(&style, yaml, options).bibliography.unwrap();
+        plain(&bib.items[0].content)
+    };
+
+    assert_eq!(
+        render(&StyleOptions::default()),
+        "Results from the large hadron collider on the Iphone"
+    );
+    let options = StyleOptions {
+        sentence_case_exceptions: CaseExceptions::new(&[
+            "Large Hadron Collider",
+            "iPhone",
+        ]),
+        ..Default::default()
+    };
+    assert_eq!(render(&options), "Results from the Large Hadron Collider on the iPhone");
+}
+
+#[test]
+fn role_label_styles() {
+    let yaml = r#"
+both:
+    type: book
+    title: Collected Stories
+    author: Chekhov, Anton
+    editor: Bartlett, Rosamund
+    affiliated:
+      - role: translator
+        names: Garnett, Constance
+    date: 1990
+    publisher: Penguin
+trans:
+    type: book
+    title: The Trial
+    author: Kafka, Franz
+    affiliated:
+      - role: translator
+        names: Muir, Willa
+    date: 1925
+    publisher: Schocken
+"#;
+
+    let render = |style: &str, role_label_style| -> Vec<String> {
+        let style = independent_style(style);
+        let options = StyleOptions { role_label_style, ..Default::default() };
+        plain_items(&render_with_options(&style, yaml, &options).bibliography.unwrap())
+    };
+
+    // A label that is already in place keeps the punctuation of the style.
+    let apa = [
+        "Chekhov, A. (1990). Collected Stories (R. Bartlett, Ed.; C. Garnett, Trans.). Penguin.",
+        "Kafka, F. (1925). The Trial (W. Muir, Trans.). Schocken.",
+    ];
+    assert_eq!(render("apa", None), apa);
+    assert_eq!(render("apa", Some(RoleLabelStyle::Parenthetical)), apa);
+    assert_eq!(
+        render("apa", Some(RoleLabelStyle::SpelledOut)),
+        [
+            "Chekhov, A. (1990). Collected Stories (edited by R. Bartlett; translated by C. Garnett). Penguin.",
+            "Kafka, F. (1925). The Trial (translated by W. Muir). Schocken.",
+        ]
+    );
+
+    let chicago = [
+        "Chekhov, Anton. 1990. Collected Stories. Edited by Rosamund Bartlett. Translated by Constance Garnett. Penguin",
+        "Kafka, Franz. 1925. The Trial. Translated by Willa Muir. Schocken",
+    ];
+    assert_eq!(render("chicago-author-date", None), chicago);
+    assert_eq!(render("chicago-author-date", Some(RoleLabelStyle::SpelledOut)), chicago);
+    assert_eq!(
+        render("chicago-author-date", Some(RoleLabelStyle::Parenthetical)),
+        [
+            "Chekhov, Anton. 1990. Collected Stories. Rosamund Bartlett (Ed.). Constance Garnett (Trans.). Penguin",
+            "Kafka, Franz. 1925. The Trial. Willa Muir (Trans.). Schocken",
+        ]
+    );
+}
+
+#[test]
+fn leading_institutions() {
+    let yaml = r#"
+alone:
+    type: report
+    title: Alone
+    author: World Health Organization
+    date: 2019
+two:
+    type: report
+    title: Two
+    author: ["World Health Organization", "Doe, Jane"]
+    date: 2020
+three:
+    type: report
+    title: Three
+    author: ["World Health Organization", "Doe, Jane", "Roe, Rick"]
+    date: 2021
+"#;
+
+    let render = |style: &str, delimiter_after_institution| {
+        let style = independent_style(style);
+        let options = StyleOptions { delimiter_after_institution, ..Default::default() };
+        let rendered = render_with_options(&style, yaml, &options);
+        let citations: Vec<_> =
+            rendered.citations.iter().map(|c| plain(&c.citation)).collect();
+        let items: Vec<_> = plain_items(&rendered.bibliography.unwrap());
+        (citations, items)
+    };
+
+    // The institution is never abbreviated and stays a single unit for the
+    // et al. of the citations.
+    let (citations, items) = render("apa", None);
+    assert_eq!(
+        citations,
+        [
+            "(World Health Organization, 2019)",
+            "(World Health Organization & Doe, 2020)",
+            "(World Health Organization et al., 2021)",
+        ]
+    );
+    assert_eq!(
+        items,
+        [
+            "World Health Organization. (2019). Alone.",
+            "World Health Organization, & Doe, J. (2020). Two.",
+            "World Health Organization, Doe, J., & Roe, R. (2021). Three.",
+        ]
+    );
+
+    // Without the delimiter, only the list of two names changes.
+    let (_, items) = render("apa", Some(false));
+    assert_eq!(
+        items,
+        [
+            "World Health Organization. (2019). Alone.",
+            "World Health Organization & Doe, J. (2020). Two.",
+            "World Health Organization, Doe, J., & Roe, R. (2021). Three.",
+        ]
+    );
+
+    let (_, items) = render("chicago-author-date", Some(false));
+    assert_eq!(
+        items,
+        [
+            "World Health Organization. 2019. “Alone”",
+            "World Health Organization and Jane Doe. 2020. “Two”",
+            "World Health Organization, Jane Doe, and Rick Roe. 2021. “Three”",
+        ]
+    );
+}
+
+#[test]
+fn doi_case() {
+    let Style::Independent(apa) = style_by_name("apa").unwrap() else {
+        panic!("APA is an independent style")
+    };
+    let yaml = r#"
+mixed:
+    type: Article
+    title: Mixed
+    author: Doe, Jane
+    date: 2020
+    serial-number:
+        doi: 10.ABCD/PhysRevB.102.1
+"#;
+
+    let render = |lowercase_doi| {
+        let options = StyleOptions { lowercase_doi, ..Default::default() };
+        let item =
+            &render_with_options(&apa, yaml, &options).bibliography.unwrap().items[0];
+        let html = render_html(&item.content);
+        (plain(&item.content), html)
+    };
+
+    // The registrant prefix is lowercased, the suffix keeps its case.
+    let (plain, html) = render(false);
+    assert_eq!(plain, "Doe, J. (2020). Mixed. https://doi.org/10.abcd/PhysRevB.102.1");
+    assert!(html.contains("href=\"https://doi.org/10.abcd/PhysRevB.102.1\""));
+
+    let (plain, html) = render(true);
+    assert_eq!(plain, "Doe, J. (2020). Mixed. https://doi.org/10.abcd/physrevb.102.1");
+    assert!(html.contains("href=\"https://doi.org/10.abcd/physrevb.102.1\""));
+}
+
+#[test]
+fn undated_web_pages() {
+    let yaml = r#"
+undated:
+    type: Web
+    title: Ishkur's Guide to Electronic Music
+    author: Ishkur
+    url:
+        value: http://www.techno.org/electronic-music-guide/
+        date: 2020-11-12
+dated:
+    type: Web
+    title: A dated page
+    author: Doe, Jane
+    date: 2019-05-01
+    url:
+        value: http://example.com/
+        date: 2020-11-12
+"#;
+
+    // An undated page has "n.d." in the date slot and its access date in the
+    // retrieval clause, so the access year is only given once. The
+    // publication date of a dated page wins over its access date.
+    let items = render_bibliography("apa", yaml);
+    assert_eq!(
+        items,
+        [
+            "Doe, J. (2019, May 1). A dated page. http://example.com/",
+            "Ishkur. (n.d.). Ishkur's Guide to Electronic Music. Retrieved November 12, \
+             2020, from http://www.techno.org/electronic-music-guide/",
+        ]
+    );
+    assert_eq!(items[1].matches("2020").count(), 1);
+
+    assert_eq!(
+        render_bibliography("chicago-author-date", yaml)[1],
+        "Ishkur. n.d. “Ishkur's Guide to Electronic Music”. Accessed November 12, \
+         2020. http://www.techno.org/electronic-music-guide/"
+    );
+}
+
+#[test]
+fn illustrators_and_assistants() {
+    let Style::Independent(apa) = style_by_name("apa").unwrap() else {
+        panic!("APA is an independent style")
+    };
+    let yaml = r#"
+kitchen:
+    type: book
+    title: In the night kitchen
+    author: Doe, Jane
+    affiliated:
+        - role: illustrator
+          names: Sendak, Maurice
+        - role: translator
+          names: Trans, Tom
+    date: 1970
+    publisher: Harper & Row
+wild:
+    type: book
+    title: Where the wild things are
+    author: Sendak, Maurice
+    affiliated:
+        - role: illustrator
+          names: Sendak, Maurice
+    date: 1963
+    publisher: Harper & Row
+climbing:
+    type: book
+    title: Climbing back
+    author: Roe, Rick
+    affiliated:
+        - role: assistant
+          names: Helper, Hanna
+    date: 1995
+    publisher: Viking
+"#;
+
+    let render = |list_illustrators| {
+        let options = StyleOptions { list_illustrators, ..Default::default() };
+        plain_items(&render_with_options(&apa, yaml, &options).bibliography.unwrap())
+    };
+
+    assert_eq!(
+        render(false),
+        [
+            "Doe, J. (1970). In the night kitchen (T. Trans, Trans.). Harper & Row.",
+            "Roe, R. (with the assistance of H. Helper). (1995). Climbing back. Viking.",
+            "Sendak, M. (1963). Where the wild things are. Harper & Row.",
+        ]
+    );
+
+    // The author who illustrated their own book is not listed again.
+    assert_eq!(
+        render(true),
+        [
+            "Doe, J. (1970). In the night kitchen (T. Trans, Trans.; M. Sendak, Ill.). \
+             Harper & Row.",
+            "Roe, R. (with the assistance of H. Helper). (1995). Climbing back. Viking.",
+            "Sendak, M. (1963). Where the wild things are. Harper & Row.",
+        ]
+    );
+
+    // Styles that name illustrators do so once, without the option.
+    assert_eq!(
+        render_bibliography("modern-language-association", yaml),
+        [
+            "Doe, Jane. In the Night Kitchen. Translated by Tom Trans, Illustrated by \
+             Maurice Sendak, Harper & Row, 1970",
+            "Roe, Rick (with the assistance of Hanna Helper). Climbing Back. Viking, 1995",
+            "Sendak, Maurice. Where the Wild Things Are. Harper & Row, 1963",
+        ]
+    );
+}
+
+#[test]
+fn standalone_web_pages() {
+    let yaml = r#"
+water:
+    type: web
+    title: Clean water guidelines
+    organization: Example Organization
+    url:
+        value: https://example.com/water
+        date: 2021-03-04
+dogs:
+    type: web
+    title: Guidelines for dogs
+    organization: Example Org Inc.
+    url: https://example.com/dogs
+    date: 2020
+press:
+    type: web
+    title: Press page
+    publisher: Example Press
+    organization: Example Organization
+    url: https://example.com/press
+    date: 2018
+page:
+    type: web
+    title: Just a page
+    url: https://example.com/page
+    date: 2017
+"#;
+
+    // The publisher wins over the organization, and a site name that ends
+    // with a period gets no second one.
+    assert_eq!(
+        render_bibliography("apa", yaml),
+        [
+            "Clean water guidelines. (n.d.). Example Organization. Retrieved March 4, \
+             2021, from https://example.com/water",
+            "Guidelines for dogs. (2020). Example Org Inc. https://example.com/dogs",
+            "Just a page. (2017). https://example.com/page",
+            "Press page. (2018). Example Press. https://example.com/press",
+        ]
+    );
+}
+
+#[test]
+fn web_pages_on_sites() {
+    let yaml = r#"
+only-url:
+    type: web
+    title: Just a page
+    url: https://example.com/page
+visited:
+    type: web
+    title: A subpage of things
+    date: 2020
+    url:
+        value: https://example.com/sub
+        date: 2022-01-02
+    parent:
+        type: web
+        title: Example Site
+post:
+    type: web
+    title: A post about things
+    author: Doe, Jane
+    date: 2020-05-01
+    url: https://blog.example.com/post
+    parent:
+        type: blog
+        title: The Example Blog
+report:
+    type: web
+    title: Annual report
+    author: Example Organization
+    organization: Example Organization
+    date: 2019
+    url: https://example.com/report
+fact-sheet:
+    type: web
+    title: Fact sheet
+    author: Example Organization
+    date: 2021
+    url: https://example.com/facts
+    parent:
+        type: web
+        title: Example Organization
+"#;
+
+    assert_eq!(
+        render_bibliography("apa", yaml),
+        [
+            "A subpage of things. (2020). Example Site. https://example.com/sub",
+            "Doe, J. (2020, May 1). A post about things. The Example Blog. \
+             https://blog.example.com/post",
+            "Example Organization. (2019). Annual report. https://example.com/report",
+            "Example Organization. (2021). Fact sheet. https://example.com/facts",
+            "Just a page. (n.d.). https://example.com/page",
+        ]
+    );
+
+    // The name of the blog takes the place of a container title.
+    let rendered = bibliography("apa", yaml);
+    let html = render_html(&rendered.items[1].content);
+    assert!(html.contains("<span style=\"font-style: italic;\">The Example Blog</span>"));
+}
+
+#[test]
+fn citation_counts() {
+    let Style::Independent(apa) = style_by_name("apa").unwrap() else {
+        panic!("APA is an independent style")
+    };
+    let yaml = r#"
+cited:
+    type: book
+    title: A much cited book
+    author: Doe, Jane
+    date: 2001
+    publisher: Penguin
+    citation-count: 42
+once:
+    type: web
+    title: A page
+    author: Roe, Rick
+    date: 2010
+    url: https://example.com/page
+    citation-count: 1
+uncited:
+    type: book
+    title: A forgotten book
+    author: Smith, Sam
+    date: 1999
+    publisher: Viking
+"#;
+
+    let render = |citation_counts| {
+        let options = StyleOptions { citation_counts, ..Default::default() };
+        plain_items(&render_with_options(&apa, yaml, &options).bibliography.unwrap())
+    };
+
+    assert_eq!(
+        render(false),
+        [
+            "Doe, J. (2001). A much cited book. Penguin.",
+            "Roe, R. (2010). A page. https://example.com/page",
+            "Smith, S. (1999). A forgotten book. Viking.",
+        ]
+    );
+    assert_eq!(
+        render(true),
+        [
+            "Doe, J. (2001). A much cited book. Penguin. Cited 42 times.",
+            "Roe, R. (2010). A page. https://example.com/page. Cited 1 time.",
+            "Smith, S. (1999). A forgotten book. Viking.",
+        ]
+    );
+
+    // The sentence follows the language of the locale.
+    let locales = locales();
+    let lib = from_yaml_str(yaml).unwrap();
+    let locale = Some(LocaleCode("de-DE".into()));
+    let options = StyleOptions { citation_counts: true, ..Default::default() };
+    let mut driver: BibliographyDriver<'_, Entry> = BibliographyDriver::new();
+    driver.citation(
+        CitationRequest::new(
+            vec![CitationItem::with_entry(lib.get("cited").unwrap())],
+            &apa,
+            locale.clone(),
+            &locales,
+            None,
+        )
+        .with_options(options.clone()),
+    );
+    let rendered = driver
+        .finish(BibliographyRequest::new(&apa, locale, &locales).with_options(options));
+    assert_eq!(
+        plain(&rendered.bibliography.unwrap().items[0].content),
+        "Doe, J. (2001). A much cited book. Penguin. 42-mal zitiert."
+    );
+}
+
+#[test]
+fn web_pages_with_parents() {
+    let yaml = r#"
+visited:
+    type: web
+    title: A subpage of things
+    url:
+        value: https://example.com/sub
+        date: 2022-01-02
+    parent:
+        type: web
+        title: Example site
+unvisited:
+    type: web
+    title: Another subpage
+    date: 2021
+    url: https://example.com/other
+    parent:
+        type: web
+        title: Example site
+untitled:
+    type: web
+    title: Untitled parent page
+    date: 2021
+    url: https://example.com/x
+    parent:
+        type: web
+        url: https://example.com
+"#;
+
+    // APA prints the name of the site in title case. A parent without a
+    // title leaves only the URL.
+    assert_eq!(
+        render_bibliography("apa", yaml),
+        [
+            "A subpage of things. (n.d.). Example Site. Retrieved January 2, 2022, from \
+             https://example.com/sub",
+            "Another subpage. (2021). Example Site. https://example.com/other",
+            "Untitled parent page. (2021). https://example.com/x",
+        ]
+    );
+}
+
+#[test]
+fn doi_without_trailing_period() {
+    let Style::Independent(apa) =
+        style_by_name("american-psychological-association").unwrap()
+    else {
+        panic!("APA is an independent style")
+    };
+    let yaml = r#"
+final:
+    type: Article
+    title: Final
+    author: Doe, Jane
+    date: 2020
+    serial-number:
+        doi: 10.1000/182
+    parent:
+        type: Periodical
+        title: Nature
+        volume: 12
+noted:
+    type: Book
+    title: Does it work?
+    author: Roe, Rich
+    date: 2021
+    note: Cited for its method.
+    serial-number:
+        doi: https://doi.org/10.1000/183.
+"#;
+
+    let render = |options: &StyleOptions| -> Vec<String> {
+        render_with_options(&apa, yaml, options)
+            .bibliography
+            .unwrap()
+            .items
+            .into_iter()
+            .map(|item| {
+                // The resolver is part of the label of the link.
+                let html = render_html(&item.content);
+                assert!(html.contains("\">https://doi.org/"));
+                assert!(html.ends_with("</a>"));
+
+                plain(&item.content)
+            })
+            .collect()
+    };
+
+    let expected = [
+        "Doe, J. (2020). Final. Nature, 12. https://doi.org/10.1000/182",
+        "Roe, R. (2021). Does it work? https://doi.org/10.1000/183",
+    ];
+    assert_eq!(
+        render(&StyleOptions { tidy_punctuation: true, ..Default::default() }),
+        expected
+    );
+    // Neither a trailing period nor an annotation after the DOI add a period.
+    assert_eq!(
+        render(&StyleOptions {
+            tidy_punctuation: true,
+            trailing_period: Some(true),
+            annotations: true,
+            ..Default::default()
+        }),
+        expected
+    );
+}
+
+#[test]
+fn trailing_abbreviation_period() {
+    let style = independent_style("chicago-author-date");
+    let yaml = r#"
+inc:
+    type: Book
+    title: The Book
+    author: Smith, John
+    date: 2020
+    publisher: Acme Inc.
+plain:
+    type: Book
+    title: Another Book
+    author: Doe, Jane
+    date: 2019
+    publisher: Penguin
+"#;
+
+    let render = |trailing_period| -> Vec<String> {
+        let options = StyleOptions { trailing_period, ..Default::default() };
+        plain_items(&render_with_options(&style, yaml, &options).bibliography.unwrap())
+    };
+
+    // The abbreviation keeps its period when the entry ends without one, and
+    // the period of an entry is not doubled after it.
+    assert_eq!(
+        render(Some(false)),
+        [
+            "Doe, Jane. 2019. Another Book. Penguin",
+            "Smith, John. 2020. The Book. Acme Inc.",
+        ]
+    );
+    assert_eq!(
+        render(Some(true)),
+        [
+            "Doe, Jane. 2019. Another Book. Penguin.",
+            "Smith, John. 2020. The Book. Acme Inc.",
+        ]
+    );
+}
+
+#[test]
+fn multi_entry_citations() {
+    let library = from_yaml_str(
+        r#"
+smith-a:
+    type: Article
+    title: First
+    author: Smith, John
+    date: 2021
+lee:
+    type: Article
+    title: Second
+    author: Lee, Ann
+    date: 2017
+smith-jones:
+    type: Article
+    title: Third
+    author: ["Smith, John", "Jones, Bob"]
+    date: 2020
+smith-b:
+    type: Article
+    title: Fourth
+    author: Smith, John
+    date: 2021
+five:
+    type: Article
+    title: Fifth
+    author: Five, Fay
+    date: 2001
+six:
+    type: Article
+    title: Sixth
+    author: Six, Sam
+    date: 2001
+seven:
+    type: Article
+    title: Seventh
+    author: Seven, Sue
+    date: 2001
+eight:
+    type: Article
+    title: Eighth
+    author: Eight, Eve
+    date: 2001
+"#,
+    )
+    .unwrap();
+    let locales = locales();
+
+    let document = |style: &str, citations: &[&[&str]]| -> Vec<String> {
+        let style = independent_style(style);
+        let mut engine = CitationEngine::new(&library, &style, None, &locales);
+        for keys in citations {
+            engine
+                .cite(keys.iter().map(|&key| KeyedCite::new(key)).collect(), None)
+                .unwrap();
+        }
+        engine.finish().citations.iter().map(|c| plain(&c.citation)).collect()
+    };
+
+    // Works of a single author precede those with coauthors.
+    assert_eq!(
+        document("apa", &[&["smith-b", "lee", "smith-jones", "smith-a", "lee"]]),
+        ["(Lee, 2017; Smith, 2021a, 2021b; Smith & Jones, 2020)"]
+    );
+
+    assert_eq!(
+        document(
+            "ieee",
+            &[
+                &["smith-a", "lee", "smith-jones", "smith-b", "five"],
+                &["six", "seven"],
+                &["eight", "smith-b", "five", "lee", "smith-jones", "eight", "six"],
+            ]
+        ),
+        ["[1]–[5]", "[6], [7]", "[2]–[6], [8]"]
+    );
 }
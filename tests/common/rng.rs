@@ -0,0 +1,33 @@
+//! A linear congruential generator for randomized tests, so that failures
+//! can be reproduced from the seed.
+
+pub struct Rng(pub u64);
+
+#[allow(unused)]
+impl Rng {
+    /// A number below `bound`.
+    pub fn next(&mut self, bound: usize) -> usize {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 33) as usize % bound
+    }
+
+    /// One of the items.
+    pub fn pick<'a>(&mut self, items: &[&'a str]) -> &'a str {
+        items[self.next(items.len())]
+    }
+
+    /// Whether a coin toss came up heads.
+    pub fn chance(&mut self) -> bool {
+        self.next(2) == 0
+    }
+
+    /// Shuffle the items with the Fisher-Yates algorithm.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.next(i + 1));
+        }
+    }
+}
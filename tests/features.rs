@@ -0,0 +1,72 @@
+//! Checks that the crate works with each combination of its optional
+//! features. CI runs these tests without default features, with the default
+//! features, and with all features.
+
+use hayagriva::io::from_yaml_str;
+use hayagriva::{BibliographyDriver, BibliographyRequest, CitationItem, CitationRequest};
+
+/// A minimal style, so that formatting can be tested without the archive.
+const STYLE: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<style xmlns="http://purl.org/net/xbiblio/csl" class="in-text" version="1.0">
+  <info><title>Minimal</title><id>minimal</id><updated>2023-01-01T00:00:00+00:00</updated></info>
+  <citation><layout><text variable="title"/></layout></citation>
+  <bibliography><layout><text variable="title" font-style="italic"/></layout></bibliography>
+</style>"#;
+
+const YAML: &str = r#"
+crazy-rich:
+    type: Book
+    title: Crazy Rich Asians
+    author: Kwan, Kevin
+    date: 2014
+"#;
+
+#[test]
+fn core_formats_without_archive() {
+    let style = citationberg::IndependentStyle::from_xml(STYLE).unwrap();
+    let library = from_yaml_str(YAML).unwrap();
+    let mut driver = BibliographyDriver::new();
+    driver.citation(CitationRequest::from_items(
+        vec![CitationItem::with_entry(library.get("crazy-rich").unwrap())],
+        &style,
+        &[],
+    ));
+
+    let rendered = driver.finish(BibliographyRequest::new(&style, None, &[]));
+    assert_eq!(format!("{:#}", rendered.citations[0].citation), "Crazy Rich Asians");
+    assert_eq!(
+        format!("{:#}", rendered.bibliography.unwrap().items[0].content),
+        "Crazy Rich Asians"
+    );
+}
+
+#[cfg(feature = "archive")]
+#[test]
+fn archive_registry() {
+    use hayagriva::archive::{locales, style, style_by_name, styles};
+
+    let names: Vec<_> = styles().map(|s| s.name).collect();
+    for name in ["apa", "chicago-author-date", "ieee", "modern-language-association"] {
+        assert!(names.contains(&name), "{name} is missing");
+    }
+
+    // Every listed style can be loaded by its name.
+    for archived in styles() {
+        assert_eq!(style_by_name(archived.name), Some(style(archived)));
+    }
+    assert_eq!(style_by_name("no-such-style"), None);
+    assert!(!locales().is_empty());
+}
+
+#[cfg(feature = "biblatex")]
+#[test]
+fn biblatex_importer() {
+    let library = hayagriva::io::from_biblatex_str(
+        "@book{kwan, title = {Crazy Rich Asians}, author = {Kwan, Kevin}, year = 2014}",
+    )
+    .unwrap();
+    assert_eq!(
+        library.get("kwan").unwrap().title().unwrap().value.to_string(),
+        "Crazy Rich Asians"
+    );
+}
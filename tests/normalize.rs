@@ -0,0 +1,289 @@
+//! Randomized checks that normalizing entries is idempotent and does not
+//! change their formatting.
+
+#![cfg(feature = "archive")]
+
+use std::fmt::Write;
+
+use citationberg::Style;
+use hayagriva::archive::{locales, style, styles};
+use hayagriva::io::from_yaml_str;
+use hayagriva::{BufWriteFormat, CitationEngine, KeyedCite, Library};
+
+use self::rng::Rng;
+
+#[path = "common/rng.rs"]
+mod rng;
+
+const WORDS: &[&str] = &[
+    "quantum",
+    "river",
+    "Café",
+    "naïve",
+    "study",
+    "of",
+    "the",
+    "NASA",
+    "Zürich",
+    "models",
+    "élan",
+    "deep",
+    "learning",
+    "Ångström",
+    "data",
+];
+const FAMILY: &[&str] = &["Doe", "Müller", "García", "Nguyen", "Smith", "Øster"];
+const GIVEN: &[&str] = &["Jane", "José", "Zoë", "Li", "Mark"];
+
+/// Whitespace that normalizes to a single space.
+const SPACES: &[&str] = &["  ", "\\t", " \\n ", "\\u2003"];
+
+/// Decompose the first precomposed letter, like "é" to "e" and a combining
+/// acute accent.
+fn decompose(word: &str) -> String {
+    let pairs = [
+        ('é', "e\\u0301"),
+        ('ï', "i\\u0308"),
+        ('ü', "u\\u0308"),
+        ('ë', "e\\u0308"),
+        ('Å', "A\\u030A"),
+        ('í', "i\\u0301"),
+    ];
+    for (c, decomposed) in pairs {
+        if word.contains(c) {
+            return word.replacen(c, decomposed, 1);
+        }
+    }
+    word.to_string()
+}
+
+/// Generate a text for a double-quoted YAML string in its tidy form and in a
+/// messy form with stray whitespace and decomposed characters.
+fn text(rng: &mut Rng, words: usize) -> (String, String) {
+    let mut tidy = String::new();
+    let mut messy = String::new();
+    if rng.chance() {
+        messy.push_str(rng.pick(SPACES));
+    }
+    for i in 0..words {
+        if i > 0 {
+            tidy.push(' ');
+            messy.push_str(if rng.chance() { rng.pick(SPACES) } else { " " });
+        }
+        let word = rng.pick(WORDS);
+        tidy.push_str(word);
+        match rng.chance() {
+            true => messy.push_str(&decompose(word)),
+            false => messy.push_str(word),
+        }
+    }
+    if rng.chance() {
+        messy.push_str(rng.pick(SPACES));
+    }
+    (tidy, messy)
+}
+
+fn person(rng: &mut Rng) -> (String, String) {
+    let space = if rng.chance() { rng.pick(SPACES) } else { " " };
+    let family = rng.pick(FAMILY);
+    let given = rng.pick(GIVEN);
+    (
+        format!("\"{family}, {given}\""),
+        format!("\"{family},{space}{}\"", decompose(given)),
+    )
+}
+
+/// The DOI in its canonical form and in one of the forms people paste it in.
+fn doi(rng: &mut Rng) -> (String, String) {
+    let doi = format!("10.{}/Ab.{}", 1000 + rng.next(9000), rng.next(100000));
+    let messy = match rng.next(4) {
+        0 => doi.clone(),
+        1 => format!("https://doi.org/{doi}"),
+        2 => format!("doi: {doi}"),
+        _ => format!(" {doi}. "),
+    };
+    (doi.to_ascii_lowercase(), messy)
+}
+
+/// The YAML source of a library in its tidy and its messy form.
+#[derive(Default)]
+struct Sources {
+    tidy: String,
+    messy: String,
+}
+
+impl Sources {
+    /// Add a line that is the same in both forms.
+    fn both(&mut self, line: &str) {
+        self.pair(line, line);
+    }
+
+    /// Add a line in each form.
+    fn pair(&mut self, tidy: &str, messy: &str) {
+        writeln!(self.tidy, "{tidy}").unwrap();
+        writeln!(self.messy, "{messy}").unwrap();
+    }
+}
+
+/// Generate a library whose entries have the most common fields, in a tidy
+/// form and in a messy form that normalizes to the tidy one.
+fn libraries(rng: &mut Rng, len: usize) -> (Library, Library) {
+    let mut src = Sources::default();
+    for i in 0..len {
+        let kind = rng.pick(&["article", "book", "chapter", "report", "web"]);
+        src.both(&format!("entry-{i}:\n    type: {kind}"));
+        let words = 2 + rng.next(5);
+        let (tidy, messy) = text(rng, words);
+        src.pair(&format!("    title: \"{tidy}\""), &format!("    title: \"{messy}\""));
+        let authors: Vec<_> = (0..rng.next(4)).map(|_| person(rng)).collect();
+        if !authors.is_empty() {
+            let list = |f: fn(&(String, String)) -> &String| {
+                authors.iter().map(f).cloned().collect::<Vec<_>>().join(", ")
+            };
+            src.pair(
+                &format!("    author: [{}]", list(|a| &a.0)),
+                &format!("    author: [{}]", list(|a| &a.1)),
+            );
+        }
+        if rng.next(4) > 0 {
+            src.both(&format!(
+                "    date: {}-{:02}",
+                1950 + rng.next(75),
+                1 + rng.next(12)
+            ));
+        }
+        if rng.chance() {
+            let start = 1 + rng.next(500);
+            src.both(&format!("    page-range: {}-{}", start, start + rng.next(40)));
+        }
+        if rng.chance() {
+            let (tidy, messy) = doi(rng);
+            src.pair(
+                &format!("    serial-number:\n        doi: \"{tidy}\""),
+                &format!("    serial-number:\n        doi: \"{messy}\""),
+            );
+        }
+        if kind == "web" && rng.chance() {
+            src.both(&format!("    url: https://example.com/{i}"));
+        }
+
+        let parent = match kind {
+            "article" => Some("periodical"),
+            "chapter" => Some("book"),
+            _ => None,
+        };
+        if let Some(parent) = parent {
+            src.both(&format!("    parent:\n        type: {parent}"));
+            let words = 1 + rng.next(3);
+            let (tidy, messy) = text(rng, words);
+            src.pair(
+                &format!("        title: \"{tidy}\""),
+                &format!("        title: \"{messy}\""),
+            );
+            if rng.chance() {
+                src.both(&format!("        volume: {}", 1 + rng.next(60)));
+            }
+            if rng.chance() {
+                let (tidy, messy) = text(rng, 2);
+                src.pair(
+                    &format!("        publisher: \"{tidy}\""),
+                    &format!("        publisher: \"{messy}\""),
+                );
+            }
+        }
+    }
+
+    (from_yaml_str(&src.tidy).unwrap(), from_yaml_str(&src.messy).unwrap())
+}
+
+fn normalized(library: &Library) -> Library {
+    library.iter().map(|entry| entry.normalize()).collect()
+}
+
+/// Cite each entry once and render the citations and the bibliography with
+/// every independent style of the archive.
+fn render(library: &Library) -> Vec<String> {
+    let locales = locales();
+    let mut res = Vec::new();
+    for archived in styles().filter(|s| !s.alias) {
+        let Style::Independent(style) = style(archived) else { continue };
+        let mut engine = CitationEngine::new(library, &style, None, &locales);
+        for (i, key) in library.keys().enumerate() {
+            engine.cite(vec![KeyedCite::new(key)], Some(i + 1)).unwrap();
+        }
+
+        let rendered = engine.finish();
+        let mut out = String::new();
+        for citation in &rendered.citations {
+            citation.citation.write_buf(&mut out, BufWriteFormat::Plain).unwrap();
+            out.push('\n');
+        }
+        for item in rendered.bibliography.iter().flat_map(|b| &b.items) {
+            item.content.write_buf(&mut out, BufWriteFormat::Plain).unwrap();
+            out.push('\n');
+        }
+        res.push(out);
+    }
+
+    res
+}
+
+#[test]
+fn normalize_is_idempotent() {
+    for seed in 0..200 {
+        let (_, library) = libraries(&mut Rng(seed), 5);
+        for entry in library.iter() {
+            let once = entry.normalize();
+            assert_eq!(once.normalize(), once, "seed {seed}, {}", entry.key());
+
+            let mut in_place = entry.clone();
+            in_place.normalize_mut();
+            assert_eq!(in_place, once, "seed {seed}, {}", entry.key());
+        }
+    }
+}
+
+#[test]
+fn normalize_keeps_formatting() {
+    for seed in 0..10 {
+        let (tidy, messy) = libraries(&mut Rng(seed), 8);
+        let expected = render(&tidy);
+        assert_eq!(render(&normalized(&tidy)), expected, "seed {seed}");
+        // Untidy entries format like the tidy ones once they are normalized.
+        assert_eq!(render(&normalized(&messy)), expected, "seed {seed}");
+    }
+}
+
+#[test]
+fn normalize_tidies_text() {
+    let library = from_yaml_str(
+        r#"
+messy:
+    type: article
+    title: "  Café  culture\tin  Zürich "
+    author: "Doe,  Jane"
+    date: 2020
+    parent:
+        type: periodical
+        title: " Journal  of   Things"
+        volume: 3
+tidy:
+    type: article
+    title: "Café culture in Zürich"
+    author: "Doe, Jane"
+    date: 2020
+    parent:
+        type: periodical
+        title: "Journal of Things"
+        volume: 3
+"#,
+    )
+    .unwrap();
+
+    let normalized = normalized(&library);
+    let messy = normalized.get("messy").unwrap();
+    let tidy = library.get("tidy").unwrap();
+    assert_eq!(messy.title(), tidy.title());
+    assert_eq!(messy.authors(), tidy.authors());
+    assert_eq!(messy.parents()[0].title(), tidy.parents()[0].title());
+}
@@ -0,0 +1,145 @@
+//! Checks that the output does not depend on the order in which the entries
+//! were added to the library.
+
+#![cfg(feature = "archive")]
+
+use std::fmt::Write;
+
+use citationberg::Style;
+use hayagriva::archive::{locales, style_by_name};
+use hayagriva::io::from_yaml_str;
+use hayagriva::{BufWriteFormat, CitationEngine, Entry, KeyedCite, Library};
+
+use self::rng::Rng;
+
+#[path = "common/rng.rs"]
+mod rng;
+
+/// The styles of the snapshot, covering author-date, numeric, and note
+/// styles.
+const STYLES: [&str; 4] = ["apa", "chicago-author-date", "ieee", "chicago-fullnotes"];
+
+/// The snapshot of the output. Set `UPDATE_SNAPSHOTS` to rewrite it.
+const SNAPSHOT: &str = "tests/data/stability.txt";
+
+/// Shuffle the entries with the seed.
+fn shuffled(entries: &[Entry], seed: u64) -> Library {
+    let mut entries = entries.to_vec();
+    Rng(seed).shuffle(&mut entries);
+    entries.into_iter().collect()
+}
+
+/// Render the document with every style. The document cites the entries in
+/// pairs in the order of `keys`, independent of the order of the library.
+fn render(library: &Library, keys: &[String]) -> String {
+    let locales = locales();
+    let mut out = String::new();
+    for name in STYLES {
+        let Style::Independent(style) = style_by_name(name).unwrap() else {
+            panic!("test has dependent style");
+        };
+
+        let mut engine = CitationEngine::new(library, &style, None, &locales);
+        for (i, pair) in keys.chunks(2).enumerate() {
+            let cites = pair.iter().map(|key| KeyedCite::new(key)).collect();
+            engine.cite(cites, Some(i + 1)).unwrap();
+        }
+
+        let rendered = engine.finish();
+        writeln!(out, "# {name}").unwrap();
+        for citation in &rendered.citations {
+            citation.citation.write_buf(&mut out, BufWriteFormat::Plain).unwrap();
+            out.push('\n');
+        }
+        for item in rendered.bibliography.iter().flat_map(|b| &b.items) {
+            if let Some(first) = &item.first_field {
+                first.write_buf(&mut out, BufWriteFormat::Plain).unwrap();
+                out.push(' ');
+            }
+            item.content.write_buf(&mut out, BufWriteFormat::Plain).unwrap();
+            out.push('\n');
+        }
+        out.push('\n');
+    }
+
+    out
+}
+
+#[test]
+fn stable_output() {
+    let source = std::fs::read_to_string("tests/data/basic.yml").unwrap();
+    let library = from_yaml_str(&source).unwrap();
+    let keys: Vec<String> = library.keys().map(ToString::to_string).collect();
+    let entries: Vec<Entry> = library.iter().cloned().collect();
+
+    let expected = render(&library, &keys);
+    for seed in 0..20 {
+        let library = shuffled(&entries, seed);
+        assert_eq!(render(&library, &keys), expected, "seed {seed}");
+    }
+
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        std::fs::write(SNAPSHOT, &expected).unwrap();
+    }
+    let snapshot = std::fs::read_to_string(SNAPSHOT).unwrap();
+    assert_eq!(expected, snapshot.replace("\r\n", "\n"));
+}
+
+#[test]
+fn tied_entries() {
+    // The styles cannot tell these entries apart, so only their keys decide
+    // their order and year suffixes.
+    let library = from_yaml_str(
+        r#"
+zebra:
+    type: Book
+    title: Collected Papers
+    author: Doe, Jane
+    date: 2020
+    publisher: Penguin
+aardvark:
+    type: Book
+    title: Collected Papers
+    author: Doe, Jane
+    date: 2020
+    publisher: Knopf
+"#,
+    )
+    .unwrap();
+
+    let bibliography = |name: &str, keys: [&str; 2]| -> Vec<String> {
+        let locales = locales();
+        let Style::Independent(style) = style_by_name(name).unwrap() else {
+            panic!("test has dependent style");
+        };
+        let mut engine = CitationEngine::new(&library, &style, None, &locales);
+        for key in keys {
+            engine.cite(vec![KeyedCite::new(key)], None).unwrap();
+        }
+
+        let rendered = engine.finish();
+        rendered
+            .bibliography
+            .iter()
+            .flat_map(|b| &b.items)
+            .map(|item| {
+                let mut out = String::new();
+                item.content.write_buf(&mut out, BufWriteFormat::Plain).unwrap();
+                out
+            })
+            .collect()
+    };
+
+    for name in ["apa", "chicago-author-date"] {
+        let forward = bibliography(name, ["aardvark", "zebra"]);
+        assert_eq!(forward, bibliography(name, ["zebra", "aardvark"]), "{name}");
+        assert!(forward[0].contains("Knopf"), "{name}");
+    }
+    assert_eq!(
+        bibliography("apa", ["zebra", "aardvark"]),
+        [
+            "Doe, J. (2020a). Collected Papers. Knopf.",
+            "Doe, J. (2020b). Collected Papers. Penguin.",
+        ]
+    );
+}